@@ -0,0 +1,232 @@
+use crate::{
+    error::TimeCardError,
+    models::{self, TimeCardData},
+    targets,
+};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono_tz::Tz;
+
+/// Hours expected and worked on one day of the ledger.
+#[derive(Debug, Clone, Copy)]
+pub struct LedgerDay {
+    pub date: NaiveDate,
+    pub target: f64,
+    pub worked: f64,
+}
+
+impl LedgerDay {
+    /// Hours the day adds to the balance, negative for under-hours.
+    pub fn change(&self) -> f64 {
+        self.worked - self.target
+    }
+}
+
+/// One month of the ledger, or the part of it the ledger covers.
+#[derive(Debug, Clone)]
+pub struct MonthStatement {
+    /// First day of the month
+    pub month: NaiveDate,
+    pub opening: f64,
+    pub days: Vec<LedgerDay>,
+    /// Hours above the carry-over cap dropped when the month ended
+    pub forfeited: f64,
+    /// Balance carried into the next month
+    pub closing: f64,
+}
+
+impl MonthStatement {
+    pub fn target(&self) -> f64 {
+        models::sum_hours(self.days.iter().map(|day| day.target))
+    }
+    
+    pub fn worked(&self) -> f64 {
+        models::sum_hours(self.days.iter().map(|day| day.worked))
+    }
+    
+    pub fn change(&self) -> f64 {
+        models::sum_hours(self.days.iter().map(LedgerDay::change))
+    }
+}
+
+/// Flex-time balance from the start date through a day, month by month.
+#[derive(Debug, Clone)]
+pub struct Ledger {
+    pub start: NaiveDate,
+    pub through: NaiveDate,
+    /// Most hours carried into the next month, if capped
+    pub cap: Option<f64>,
+    pub months: Vec<MonthStatement>,
+}
+
+impl Ledger {
+    pub fn balance(&self) -> f64 {
+        self.months.last().map_or(0.0, |month| month.closing)
+    }
+    
+    pub fn month(&self, month: NaiveDate) -> Option<&MonthStatement> {
+        self.months.iter().find(|statement| statement.month == month.with_day(1).unwrap())
+    }
+}
+
+/// Day the ledger starts: the `balance-start` setting, or the day of the
+/// first entry.
+pub fn start_date(data: &TimeCardData, tz: &Tz) -> Option<NaiveDate> {
+    data.settings.balance_start.or_else(|| {
+        data.time_entries
+            .iter()
+            .map(|entry| entry.start_time.with_timezone(tz).date_naive())
+            .min()
+    })
+}
+
+/// Work out the balance through the local day `through`, counting the
+/// active entry up to `now`. Each day's target is less the vacation, sick
+/// days and public holidays recorded on it, see [`targets::day_target`].
+/// Positive balances above the `balance-cap` setting are cut back to it at
+/// the end of each month.
+pub fn ledger(data: &TimeCardData, tz: &Tz, through: NaiveDate, now: DateTime<Utc>) -> anyhow::Result<Ledger> {
+    if data.settings.targets.days.iter().all(Option::is_none) {
+        return Err(TimeCardError::InvalidInput(
+            "No day targets set. Use `timecard target set <HOURS> --day weekdays` first".to_string(),
+        ).into());
+    }
+    
+    let cap = data.settings.balance_cap;
+    let start = start_date(data, tz).unwrap_or(through);
+    let mut months: Vec<MonthStatement> = Vec::new();
+    let mut balance = 0.0;
+    
+    let entries = data.get_entries_by_dates(start, through, tz);
+    
+    for date in start.iter_days().take_while(|date| *date <= through) {
+        let first_of_month = date.with_day(1).unwrap();
+        if months.last().is_none_or(|month| month.month != first_of_month) {
+            months.push(MonthStatement {
+                month: first_of_month,
+                opening: balance,
+                days: Vec::new(),
+                forfeited: 0.0,
+                closing: balance,
+            });
+        }
+        
+        let day = LedgerDay {
+            date,
+            target: targets::day_target(data, date),
+            worked: models::sum_hours(
+                entries.get(&date)
+                    .into_iter()
+                    .flatten()
+                    .map(|entry| entry.net_duration_until(now).num_seconds() as f64 / 3600.0),
+            ),
+        };
+        balance += day.change();
+        
+        let month = months.last_mut().unwrap();
+        month.days.push(day);
+        if let Some(cap) = cap.filter(|_| is_month_end(date)) {
+            month.forfeited = (balance - cap).max(0.0);
+            balance -= month.forfeited;
+        }
+        month.closing = balance;
+    }
+    
+    Ok(Ledger { start, through, cap, months })
+}
+
+fn is_month_end(date: NaiveDate) -> bool {
+    date.succ_opt().is_none_or(|next| next.month() != date.month())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Leave, LeaveKind, TimeEntry};
+    use chrono::TimeZone;
+    
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+    
+    /// One hour a day, every day, from Monday 2025-09-29.
+    fn data(cap: Option<f64>) -> TimeCardData {
+        let mut data = TimeCardData::default();
+        data.settings.targets.days = [Some(1.0); 7];
+        data.settings.balance_start = Some(date(9, 29));
+        data.settings.balance_cap = cap;
+        data
+    }
+    
+    fn work(data: &mut TimeCardData, day: NaiveDate, hours: i64) {
+        let mut entry = TimeEntry::new(None, None);
+        entry.start_time = Utc.from_utc_datetime(&day.and_hms_opt(9, 0, 0).unwrap());
+        entry.end_time = Some(entry.start_time + chrono::Duration::hours(hours));
+        data.add_time_entry(entry);
+    }
+    
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 10, 3, 12, 0, 0).unwrap()
+    }
+    
+    #[test]
+    fn cap_cuts_the_balance_back_at_month_end() {
+        let mut data = data(Some(5.0));
+        work(&mut data, date(9, 29), 8);
+        work(&mut data, date(10, 1), 1);
+        
+        let ledger = ledger(&data, &Tz::UTC, date(10, 2), now()).unwrap();
+        
+        let september = ledger.month(date(9, 1)).unwrap();
+        assert_eq!(september.change(), 6.0);
+        assert_eq!(september.forfeited, 1.0);
+        assert_eq!(september.closing, 5.0);
+        let october = ledger.month(date(10, 1)).unwrap();
+        assert_eq!(october.opening, 5.0);
+        assert_eq!(october.forfeited, 0.0);
+        assert_eq!(ledger.balance(), 4.0);
+    }
+    
+    #[test]
+    fn cap_leaves_negative_balances_and_uncapped_ledgers_alone() {
+        let capped = ledger(&data(Some(5.0)), &Tz::UTC, date(10, 2), now()).unwrap();
+        assert_eq!(capped.months[0].forfeited, 0.0);
+        assert_eq!(capped.balance(), -4.0);
+        
+        let mut uncapped = data(None);
+        work(&mut uncapped, date(9, 29), 8);
+        let ledger = ledger(&uncapped, &Tz::UTC, date(9, 30), now()).unwrap();
+        assert_eq!(ledger.balance(), 6.0);
+        assert_eq!(ledger.months[0].forfeited, 0.0);
+    }
+    
+    #[test]
+    fn leave_lowers_the_day_target() {
+        let mut data = data(None);
+        data.leave.push(Leave::new(date(9, 30), LeaveKind::Vacation, None, None));
+        data.leave.push(Leave::new(date(10, 1), LeaveKind::CompTime, None, None));
+        
+        let ledger = ledger(&data, &Tz::UTC, date(10, 1), now()).unwrap();
+        
+        let targets: Vec<_> = ledger.months.iter().flat_map(|month| &month.days).map(|day| day.target).collect();
+        assert_eq!(targets, [1.0, 0.0, 1.0]);
+    }
+    
+    #[test]
+    fn active_entry_counts_until_now() {
+        let mut data = data(None);
+        let mut entry = TimeEntry::new(None, None);
+        entry.start_time = now() - chrono::Duration::hours(3);
+        data.add_time_entry(entry);
+        
+        let ledger = ledger(&data, &Tz::UTC, date(10, 3), now()).unwrap();
+        
+        assert_eq!(ledger.months[1].days.last().unwrap().worked, 3.0);
+    }
+    
+    #[test]
+    fn needs_day_targets() {
+        let mut data = data(None);
+        data.settings.targets.days = [None; 7];
+        assert!(ledger(&data, &Tz::UTC, date(10, 2), now()).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{error::TimeCardError, models::{Rate, RateScope, TimeCardData, TimeEntry}};
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Validate a currency code given on the command line, e.g. `eur` → `EUR`.
+pub fn normalize_currency(code: &str) -> anyhow::Result<String> {
+    let code = code.trim().to_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(TimeCardError::InvalidInput(format!(
+            "Invalid currency code: {} (expected e.g. USD, EUR)",
+            code
+        )).into());
+    }
+    Ok(code)
+}
+
+/// The rate for `scope` in force on `date`: the one with the latest
+/// effective date not after it.
+fn effective_rate<'a>(data: &'a TimeCardData, scope: &RateScope, date: NaiveDate) -> Option<&'a Rate> {
+    data.rates
+        .iter()
+        .filter(|rate| &rate.scope == scope && rate.effective_from <= date)
+        .max_by_key(|rate| (rate.effective_from, rate.created_at))
+}
+
+/// Hourly rate for an entry on the day it started. Tag rates win over
+/// project rates, which win over client rates; if several tags have a rate
+/// the highest one applies.
+pub fn rate_for<'a>(data: &'a TimeCardData, entry: &TimeEntry, tz: &Tz) -> Option<&'a Rate> {
+    let date = entry.start_time.with_timezone(tz).date_naive();
+    
+    let tag_rate = entry.tags
+        .iter()
+        .filter_map(|tag| effective_rate(data, &RateScope::Tag(tag.clone()), date))
+        .max_by(|a, b| a.hourly_rate.total_cmp(&b.hourly_rate));
+    
+    tag_rate
+        .or_else(|| entry.project_id.and_then(|id| effective_rate(data, &RateScope::Project(id), date)))
+        .or_else(|| {
+            data.client_for_project(entry.project_id)
+                .and_then(|client| effective_rate(data, &RateScope::Client(client.id), date))
+        })
+}
+
+/// Billable amount of a finished entry, or `None` if it is non-billable,
+/// still running or has no rate.
+pub fn entry_amount<'a>(data: &'a TimeCardData, entry: &TimeEntry, tz: &Tz) -> Option<(f64, &'a Rate)> {
+    if !entry.billable {
+        return None;
+    }
+    let hours = entry.hours()?;
+    let rate = rate_for(data, entry, tz)?;
+    Some((hours * rate.hourly_rate, rate))
+}
+
+/// Money totals kept apart per currency, since rates may differ in currency.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct Amounts(BTreeMap<String, f64>);
+
+impl Amounts {
+    pub fn add(&mut self, currency: &str, amount: f64) {
+        *self.0.entry(currency.to_string()).or_insert(0.0) += amount;
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.0.iter().map(|(currency, amount)| (currency.as_str(), *amount))
+    }
+}
+
+impl fmt::Display for Amounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "-");
+        }
+        let parts: Vec<_> = self.iter().map(|(currency, amount)| format_amount(amount, currency)).collect();
+        write!(f, "{}", parts.join(" + "))
+    }
+}
+
+pub fn format_amount(amount: f64, currency: &str) -> String {
+    format!("{:.2} {}", amount, currency)
+}
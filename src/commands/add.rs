@@ -1,82 +1,54 @@
-use crate::{models::TimeEntry, storage};
-use chrono::{DateTime, Utc};
+use crate::{commands::{project, target}, output::{EntryJson, OutputFormat}};
 use colored::*;
-use std::path::Path;
+use timecard::{datetime, doctor, tracker::{Activity, NewEntry, TimeTracker}};
 
 pub fn add_entry(
-    file_path: &Path,
-    project: Option<String>,
-    description: Option<String>,
+    tracker: &mut TimeTracker,
+    activity: Activity,
     start_time_str: &str,
     end_time_str: &str,
+    output: OutputFormat,
 ) -> anyhow::Result<()> {
-    let start_time = parse_datetime(start_time_str)?;
-    let end_time = parse_datetime(end_time_str)?;
+    let tz = tracker.timezone()?;
     
-    if end_time <= start_time {
-        return Err(anyhow::anyhow!("End time must be after start time"));
-    }
-    
-    let mut data = storage::load_data(file_path)?;
+    let start = datetime::parse_datetime(start_time_str, &tz)?;
+    let end = datetime::parse_datetime(end_time_str, &tz)?;
     
-    // Create manual time entry
-    let mut entry = TimeEntry::new(project, description);
-    entry.start_time = start_time;
-    entry.end_time = Some(end_time);
-    entry.updated_at = Utc::now();
+    let added = tracker.add(NewEntry { activity, start, end })?;
+    project::note_created(added.created_project.as_ref(), output);
+    let data = tracker.load()?;
+    let entry = &added.entry;
     
-    let duration = entry.duration().unwrap();
-    let hours = duration.num_seconds() as f64 / 3600.0;
+    if output.is_machine() {
+        for other in &added.overlaps {
+            output.note(format!("Overlaps {}", doctor::describe_entry(&data, other, &tz)));
+        }
+        target::note_budget(&data, entry.project_id, entry.hours().unwrap_or(0.0), output);
+        return output.emit(&EntryJson::new(&data, entry, &tz));
+    }
     
     println!("{}", "✅ Manual time entry added!".green());
-    println!("Start: {}", start_time.format("%Y-%m-%d %H:%M:%S"));
-    println!("End: {}", end_time.format("%Y-%m-%d %H:%M:%S"));
-    println!("Duration: {:.2} hours", hours);
+    println!("Start: {}", start.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"));
+    println!("End: {}", end.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"));
+    println!("Duration: {:.2} hours", entry.hours().unwrap());
     
-    if let Some(proj) = &entry.project {
+    if let Some(proj) = data.project_name(entry.project_id) {
         println!("Project: {}", proj.blue());
     }
     if let Some(desc) = &entry.description {
         println!("Description: {}", desc);
     }
-    
-    data.add_time_entry(entry);
-    storage::save_data(file_path, &data)?;
-    
-    Ok(())
-}
-
-fn parse_datetime(datetime_str: &str) -> anyhow::Result<DateTime<Utc>> {
-    // Try different datetime formats
-    let formats = [
-        "%Y-%m-%d %H:%M:%S",
-        "%Y-%m-%d %H:%M",
-        "%Y-%m-%d",
-        "%H:%M:%S",
-        "%H:%M",
-    ];
-    
-    for format in &formats {
-        if let Ok(datetime) = chrono::NaiveDateTime::parse_from_str(datetime_str, format) {
-            return Ok(DateTime::from_naive_utc_and_offset(datetime, Utc));
-        }
+    if !entry.tags.is_empty() {
+        println!("Tags: {}", entry.format_tags().cyan());
     }
-    
-    // If only time is provided, assume today's date
-    if let Ok(time) = chrono::NaiveTime::parse_from_str(datetime_str, "%H:%M") {
-        let today = Utc::now().date_naive();
-        let datetime = today.and_time(time);
-        return Ok(DateTime::from_naive_utc_and_offset(datetime, Utc));
+    if !entry.billable {
+        println!("{}", "Non-billable".dimmed());
     }
     
-    if let Ok(time) = chrono::NaiveTime::parse_from_str(datetime_str, "%H:%M:%S") {
-        let today = Utc::now().date_naive();
-        let datetime = today.and_time(time);
-        return Ok(DateTime::from_naive_utc_and_offset(datetime, Utc));
+    for other in &added.overlaps {
+        println!("{}", format!("⚠️  Overlaps {}", doctor::describe_entry(&data, other, &tz)).yellow());
     }
+    target::note_budget(&data, entry.project_id, entry.hours().unwrap_or(0.0), output);
     
-    Err(anyhow::anyhow!(
-        "Invalid datetime format: {}. Use formats like: 2024-01-15 14:30, 14:30, 2024-01-15",
-        datetime_str
-    ))
+    Ok(())
 }
@@ -0,0 +1,58 @@
+use colored::*;
+use timecard::storage::{self, backup, Storage};
+
+pub fn list_backups(store: &mut dyn Storage) -> anyhow::Result<()> {
+    let backups = backup::list_backups(store.path())?;
+    
+    if backups.is_empty() {
+        println!("{}", "No backups found.".yellow());
+        return Ok(());
+    }
+    
+    let tz = store.load_settings()?.timezone()?;
+    
+    println!("{}", "💾 Backups".bold());
+    println!("{}", "=".repeat(50));
+    
+    for (index, backup) in backups.iter().enumerate() {
+        println!("{:>3}. {}  {} ({:.1} KB)", 
+            index + 1,
+            backup.created_at.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"),
+            backup.name().dimmed(),
+            backup.size as f64 / 1024.0
+        );
+    }
+    
+    Ok(())
+}
+
+pub fn create_backup(store: &mut dyn Storage) -> anyhow::Result<()> {
+    let retention = store.load_settings()?.backup_retention().max(1);
+    
+    match backup::create_backup(store.path(), retention)? {
+        Some(path) => println!("{}", format!("💾 Backup created: {}", path.display()).green()),
+        None => println!("{}", "Nothing to back up yet.".yellow()),
+    }
+    
+    Ok(())
+}
+
+pub fn restore_backup(store: &mut dyn Storage, selector: &str) -> anyhow::Result<()> {
+    let backup = backup::find_backup(store.path(), selector)?;
+    let data = storage::open(&backup.path, None)?.load()?;
+    
+    // Keep the current state so the restore itself can be undone
+    let retention = store.load_settings()?.backup_retention().max(1);
+    let safety = backup::create_backup(store.path(), retention)?;
+    
+    store.save(&data)?;
+    
+    println!("{}", format!("✅ Restored backup: {}", backup.name()).green());
+    println!("Entries: {}", data.time_entries.len());
+    println!("Projects: {}", data.projects.len());
+    if let Some(path) = safety {
+        println!("Previous state saved to: {}", path.display());
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use crate::output::OutputFormat;
+use chrono::{Duration, NaiveDate, Utc};
+use colored::*;
+use serde::Serialize;
+use timecard::{
+    balance::{self, Ledger, LedgerDay, MonthStatement},
+    datetime,
+    models::{self, TimeCardData},
+    period,
+    storage::Storage,
+    targets,
+    TimeCardError,
+};
+
+#[derive(Serialize)]
+struct BalanceJson {
+    start: NaiveDate,
+    /// Last day counted; today is left out until it's over
+    through: NaiveDate,
+    cap: Option<f64>,
+    balance: f64,
+    months: Vec<MonthJson>,
+}
+
+#[derive(Serialize)]
+struct MonthJson {
+    month: String,
+    opening: f64,
+    target: f64,
+    worked: f64,
+    change: f64,
+    forfeited: f64,
+    closing: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    days: Option<Vec<DayJson>>,
+}
+
+#[derive(Serialize)]
+struct DayJson {
+    date: NaiveDate,
+    target: f64,
+    worked: f64,
+    change: f64,
+}
+
+impl MonthJson {
+    fn new(statement: &MonthStatement, with_days: bool) -> Self {
+        Self {
+            month: statement.month.format("%Y-%m").to_string(),
+            opening: statement.opening,
+            target: statement.target(),
+            worked: statement.worked(),
+            change: statement.change(),
+            forfeited: statement.forfeited,
+            closing: statement.closing,
+            days: with_days.then(|| {
+                statement.days
+                    .iter()
+                    .map(|day| DayJson {
+                        date: day.date,
+                        target: day.target,
+                        worked: day.worked,
+                        change: day.change(),
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Show the flex-time balance month by month, or the statement of the month
+/// a period expression falls in.
+pub fn show_balance(store: &mut dyn Storage, month: Option<&str>, output: OutputFormat) -> anyhow::Result<()> {
+    let data = store.load()?;
+    let tz = data.settings.timezone()?;
+    let now = Utc::now();
+    let today = datetime::today(&tz);
+    let ledger = balance::ledger(&data, &tz, today - Duration::days(1), now)?;
+    
+    let statement = match month {
+        Some(month) => {
+            let first_day = period::parse_period(month, &tz)?.first_day;
+            Some(ledger.month(first_day).ok_or_else(|| {
+                TimeCardError::NotFound(format!(
+                    "No balance for {}. The balance runs from {} through {}",
+                    first_day.format("%B %Y"),
+                    ledger.start,
+                    ledger.through
+                ))
+            })?)
+        }
+        None => None,
+    };
+    
+    if output.is_machine() {
+        let months = match statement {
+            Some(statement) => vec![MonthJson::new(statement, true)],
+            None => ledger.months.iter().map(|statement| MonthJson::new(statement, false)).collect(),
+        };
+        return output.emit(&BalanceJson {
+            start: ledger.start,
+            through: ledger.through,
+            cap: ledger.cap,
+            balance: ledger.balance(),
+            months,
+        });
+    }
+    
+    match statement {
+        Some(statement) => print_statement(&data, statement, &ledger),
+        None => print_ledger(&ledger),
+    }
+    
+    let worked = models::sum_hours(
+        data.get_entries_by_date(today, &tz)
+            .iter()
+            .map(|entry| entry.net_duration_until(now).num_seconds() as f64 / 3600.0),
+    );
+    println!(
+        "{}",
+        format!(
+            "Today: {:.2} of {:.2} hours worked, counted once the day is over",
+            worked,
+            targets::day_target(&data, today)
+        ).dimmed()
+    );
+    
+    Ok(())
+}
+
+fn print_ledger(ledger: &Ledger) {
+    println!("{}", format!("⚖️  Flex Balance since {}", ledger.start).bold());
+    println!("{}", "=".repeat(60));
+    
+    if ledger.months.is_empty() {
+        println!("{}", "No completed days yet.".yellow());
+        return;
+    }
+    
+    println!("{:<10} {:>9} {:>9} {:>9} {:>10}", "Month", "Target", "Worked", "Change", "Balance");
+    for statement in &ledger.months {
+        println!(
+            "{:<10} {:>9.2} {:>9.2} {:>9} {:>10}",
+            statement.month.format("%Y-%m"),
+            statement.target(),
+            statement.worked(),
+            signed(statement.change()),
+            signed(statement.closing)
+        );
+        if statement.forfeited > 0.0 {
+            println!("{}", format!("  {:.2} hours above the cap forfeited", statement.forfeited).dimmed());
+        }
+    }
+    
+    println!("{}", "=".repeat(60));
+    print_closing(ledger);
+}
+
+fn print_statement(data: &TimeCardData, statement: &MonthStatement, ledger: &Ledger) {
+    println!("{}", format!("⚖️  Flex Statement {}", statement.month.format("%B %Y")).bold());
+    println!("{}", "=".repeat(60));
+    println!("Opening balance: {}", colored(statement.opening));
+    println!();
+    
+    println!("{:<14} {:>9} {:>9} {:>9} {:>10}", "Date", "Target", "Worked", "Change", "Balance");
+    let mut balance = statement.opening;
+    for day in &statement.days {
+        balance += day.change();
+        println!("{}", day_line(data, day, balance));
+    }
+    
+    println!("{}", "=".repeat(60));
+    println!(
+        "Target: {:.2}  Worked: {:.2}  Change: {}",
+        statement.target(),
+        statement.worked(),
+        signed(statement.change())
+    );
+    if statement.forfeited > 0.0 {
+        println!(
+            "Forfeited above the cap of {:.2} hours: {:.2}",
+            ledger.cap.unwrap_or(0.0),
+            statement.forfeited
+        );
+    }
+    if ledger.months.last().is_some_and(|last| last.month == statement.month) {
+        print_closing(ledger);
+    } else {
+        println!("Closing balance: {}", colored(statement.closing));
+    }
+}
+
+fn day_line(data: &TimeCardData, day: &LedgerDay, balance: f64) -> ColoredString {
+    let mut line = format!(
+        "{:<14} {:>9.2} {:>9.2} {:>9} {:>10}",
+        day.date.format("%a %Y-%m-%d"),
+        day.target,
+        day.worked,
+        signed(day.change()),
+        signed(balance)
+    );
+    for leave in data.leave_on(day.date) {
+        line.push_str(&format!("  {}", leave.kind.label()));
+    }
+    if day.target == 0.0 && day.worked == 0.0 {
+        line.dimmed()
+    } else {
+        line.normal()
+    }
+}
+
+fn print_closing(ledger: &Ledger) {
+    println!("Balance at end of {}: {} hours", ledger.through, colored(ledger.balance()));
+}
+
+/// Hours with their sign, e.g. `+1.50`. Rounding first keeps tiny float
+/// errors from printing as `-0.00`.
+fn signed(hours: f64) -> String {
+    format!("{:+.2}", (hours * 100.0).round() / 100.0 + 0.0)
+}
+
+fn colored(hours: f64) -> ColoredString {
+    let text = signed(hours);
+    if hours <= -0.005 {
+        text.red()
+    } else {
+        text.green()
+    }
+}
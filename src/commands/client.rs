@@ -0,0 +1,74 @@
+use colored::*;
+use timecard::{billing, storage::Storage};
+
+pub fn add_client(
+    store: &mut dyn Storage,
+    name: &str,
+    currency: Option<&str>,
+) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    
+    let currency = currency.map(billing::normalize_currency).transpose()?;
+    let id = data.add_client(name, currency)?;
+    store.insert_client(data.get_client(id).unwrap())?;
+    
+    println!("{}", format!("✅ Client added: {}", name.trim()).green());
+    
+    Ok(())
+}
+
+pub fn list_clients(store: &mut dyn Storage) -> anyhow::Result<()> {
+    let data = store.load()?;
+    
+    let mut clients: Vec<_> = data.clients.iter().collect();
+    clients.sort_by_key(|client| client.name.to_lowercase());
+    
+    if clients.is_empty() {
+        println!("{}", "No clients found.".yellow());
+        return Ok(());
+    }
+    
+    println!("{}", "🤝 Clients".bold());
+    println!("{}", "=".repeat(50));
+    
+    for client in clients {
+        println!("{} ({})", client.name.blue(), data.client_currency(Some(client)));
+        
+        let mut projects: Vec<_> = data.projects
+            .iter()
+            .filter(|project| project.client_id == Some(client.id))
+            .map(|project| project.name.as_str())
+            .collect();
+        projects.sort_by_key(|name| name.to_lowercase());
+        if !projects.is_empty() {
+            println!("    Projects: {}", projects.join(", "));
+        }
+    }
+    
+    Ok(())
+}
+
+pub fn rename_client(store: &mut dyn Storage, name: &str, new_name: &str) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    
+    let client = data.rename_client(name, new_name)?;
+    store.update_client(client)?;
+    
+    println!("{}", format!("✅ Client renamed: {} → {}", name.trim(), new_name.trim()).green());
+    
+    Ok(())
+}
+
+pub fn delete_client(store: &mut dyn Storage, name: &str, force: bool) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    
+    let detached = data.delete_client(name, force)?;
+    store.save(&data)?;
+    
+    println!("{}", format!("🗑️  Client deleted: {}", name.trim()).green());
+    if !detached.is_empty() {
+        println!("{} projects are now unassigned", detached.len());
+    }
+    
+    Ok(())
+}
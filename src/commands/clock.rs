@@ -1,86 +1,178 @@
-use crate::{models::TimeEntry, storage};
-use chrono::Utc;
+use crate::{commands::{project, target}, output::{EntryJson, OutputFormat}};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use colored::*;
-use std::path::Path;
+use timecard::{
+    datetime,
+    models::{TimeCardData, TimeEntry},
+    tracker::{Activity, TimeTracker},
+};
 
 pub fn clock_in(
-    file_path: &Path,
-    project: Option<String>,
-    description: Option<String>,
+    tracker: &mut TimeTracker,
+    activity: Activity,
+    at: Option<&str>,
+    output: OutputFormat,
 ) -> anyhow::Result<()> {
-    let mut data = storage::load_data(file_path)?;
-    
-    // Check if already clocked in
-    if let Some(active_entry) = data.get_active_entry() {
-        println!("{}", "❌ Already clocked in!".red());
-        println!("Started: {}", active_entry.start_time.format("%Y-%m-%d %H:%M:%S"));
-        if let Some(proj) = &active_entry.project {
-            println!("Project: {}", proj);
-        }
-        if let Some(desc) = &active_entry.description {
-            println!("Description: {}", desc);
-        }
-        return Ok(());
-    }
+    let tz = tracker.timezone()?;
+    let at = parse_at(at, &tz)?;
     
-    // Create new time entry
-    let entry = TimeEntry::new(project, description);
+    let started = tracker.clock_in(activity, at)?;
+    project::note_created(started.created_project.as_ref(), output);
     
+    let data = tracker.load()?;
+    if output.is_machine() {
+        return output.emit(&EntryJson::new(&data, &started.entry, &tz));
+    }
     println!("{}", "✅ Clocked in!".green());
-    println!("Started: {}", entry.start_time.format("%Y-%m-%d %H:%M:%S"));
-    if let Some(proj) = &entry.project {
+    print_started(&data, &started.entry, &tz);
+    
+    Ok(())
+}
+
+/// Stop the active entry and start a new one at the same instant, in one write.
+pub fn switch(tracker: &mut TimeTracker, activity: Activity, at: Option<&str>) -> anyhow::Result<()> {
+    let tz = tracker.timezone()?;
+    let at = parse_at(at, &tz)?;
+    
+    let switched = tracker.switch(activity, at)?;
+    project::note_created(switched.created_project.as_ref(), OutputFormat::Text);
+    
+    let data = tracker.load()?;
+    println!("{}", "🔀 Switched!".green());
+    println!("Finished: {} ({:.2} hours)",
+        data.project_name(switched.previous.project_id).unwrap_or("No Project").blue(),
+        switched.previous.hours().unwrap_or(0.0)
+    );
+    print_started(&data, &switched.next, &tz);
+    target::note_budget(&data, switched.previous.project_id, switched.previous.hours().unwrap_or(0.0), OutputFormat::Text);
+    
+    Ok(())
+}
+
+/// Start a new entry with the project, description and tags of an earlier
+/// one: the given entry, or the most recent.
+pub fn continue_entry(tracker: &mut TimeTracker, id_prefix: Option<&str>) -> anyhow::Result<()> {
+    let tz = tracker.timezone()?;
+    
+    let entry = tracker.continue_entry(id_prefix)?;
+    
+    println!("{}", "✅ Continuing!".green());
+    print_started(&tracker.load()?, &entry, &tz);
+    
+    Ok(())
+}
+
+/// Discard the active entry, e.g. one started by mistake.
+pub fn cancel(tracker: &mut TimeTracker) -> anyhow::Result<()> {
+    let tz = tracker.timezone()?;
+    
+    let entry = tracker.cancel()?;
+    let data = tracker.load()?;
+    
+    println!("{}", "🗑️  Active entry discarded".green());
+    println!("Started: {}", entry.start_time.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"));
+    if let Some(proj) = data.project_name(entry.project_id) {
         println!("Project: {}", proj.blue());
     }
     if let Some(desc) = &entry.description {
         println!("Description: {}", desc);
     }
     
-    data.add_time_entry(entry);
-    storage::save_data(file_path, &data)?;
-    
     Ok(())
 }
 
-pub fn clock_out(file_path: &Path, description: Option<String>) -> anyhow::Result<()> {
-    let mut data = storage::load_data(file_path)?;
-    
-    // Find active entry
-    let active_entry = data
-        .time_entries
-        .iter_mut()
-        .find(|entry| entry.is_active());
-    
-    match active_entry {
-        Some(entry) => {
-            let end_time = Utc::now();
-            entry.end_time = Some(end_time);
-            entry.updated_at = end_time;
-            
-            if let Some(desc) = description {
-                entry.description = Some(desc);
-            }
-            
-            let duration = entry.duration().unwrap();
-            let hours = duration.num_seconds() as f64 / 3600.0;
-            
-            println!("{}", "✅ Clocked out!".green());
-            println!("Started: {}", entry.start_time.format("%Y-%m-%d %H:%M:%S"));
-            println!("Ended: {}", end_time.format("%Y-%m-%d %H:%M:%S"));
-            println!("Duration: {:.2} hours", hours);
-            
-            if let Some(proj) = &entry.project {
-                println!("Project: {}", proj.blue());
-            }
-            if let Some(desc) = &entry.description {
-                println!("Description: {}", desc);
-            }
-            
-            storage::save_data(file_path, &data)?;
-        }
-        None => {
-            println!("{}", "❌ Not clocked in!".red());
-        }
+/// Read an `--at` time in the configured timezone.
+fn parse_at(at: Option<&str>, tz: &Tz) -> anyhow::Result<Option<DateTime<Utc>>> {
+    at.map(|at| datetime::parse_datetime(at, tz)).transpose()
+}
+
+fn print_started(data: &TimeCardData, entry: &TimeEntry, tz: &Tz) {
+    println!("Started: {}", entry.start_time.with_timezone(tz).format("%Y-%m-%d %H:%M:%S"));
+    if let Some(proj) = data.project_name(entry.project_id) {
+        println!("Project: {}", proj.blue());
+    }
+    if let Some(desc) = &entry.description {
+        println!("Description: {}", desc);
+    }
+    if !entry.tags.is_empty() {
+        println!("Tags: {}", entry.format_tags().cyan());
+    }
+    if !entry.billable {
+        println!("{}", "Non-billable".dimmed());
     }
+}
+
+pub fn clock_out(
+    tracker: &mut TimeTracker,
+    description: Option<String>,
+    at: Option<&str>,
+    output: OutputFormat,
+) -> anyhow::Result<()> {
+    let tz = tracker.timezone()?;
+    let at = parse_at(at, &tz)?;
+    
+    let entry = tracker.clock_out(description, at)?;
+    let data = tracker.load()?;
+    
+    if output.is_machine() {
+        target::note_budget(&data, entry.project_id, entry.hours().unwrap_or(0.0), output);
+        return output.emit(&EntryJson::new(&data, &entry, &tz));
+    }
+    println!("{}", "✅ Clocked out!".green());
+    println!("Started: {}", entry.start_time.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"));
+    println!("Ended: {}", entry.end_time.unwrap().with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"));
+    println!("Duration: {:.2} hours", entry.hours().unwrap());
+    if !entry.pauses.is_empty() {
+        println!("Breaks: {:.2} hours (gross time {:.2} hours)",
+            hours_of(entry.break_duration()),
+            hours_of(entry.gross_duration().unwrap())
+        );
+    }
+    
+    if let Some(proj) = data.project_name(entry.project_id) {
+        println!("Project: {}", proj.blue());
+    }
+    if let Some(desc) = &entry.description {
+        println!("Description: {}", desc);
+    }
+    if !entry.tags.is_empty() {
+        println!("Tags: {}", entry.format_tags().cyan());
+    }
+    target::note_budget(&data, entry.project_id, entry.hours().unwrap_or(0.0), output);
     
     Ok(())
 }
+
+pub fn pause(tracker: &mut TimeTracker, at: Option<&str>) -> anyhow::Result<()> {
+    let tz = tracker.timezone()?;
+    let at = parse_at(at, &tz)?;
+    
+    let entry = tracker.pause(at)?;
+    let break_start = entry.pauses.last().unwrap().start;
+    
+    println!("{}", "⏸️  Paused".yellow());
+    println!("Break started: {}", break_start.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"));
+    println!("Worked so far: {:.2} hours", hours_of(entry.net_duration_until(break_start)));
+    
+    Ok(())
+}
+
+pub fn resume(tracker: &mut TimeTracker, at: Option<&str>) -> anyhow::Result<()> {
+    let tz = tracker.timezone()?;
+    let at = parse_at(at, &tz)?;
+    
+    let entry = tracker.resume(at)?;
+    let pause = entry.pauses.last().unwrap();
+    let break_end = pause.end.unwrap();
+    
+    println!("{}", "▶️  Resumed".green());
+    println!("Break: {} minutes", (break_end - pause.start).num_minutes());
+    println!("Total breaks: {:.2} hours", hours_of(entry.break_duration_until(break_end)));
+    
+    Ok(())
+}
+
+fn hours_of(duration: chrono::Duration) -> f64 {
+    duration.num_seconds() as f64 / 3600.0
+}
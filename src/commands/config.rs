@@ -0,0 +1,117 @@
+use chrono::NaiveDate;
+use colored::*;
+use timecard::{billing, datetime, storage::Storage, TimeCardError};
+
+pub fn show_config(store: &mut dyn Storage) -> anyhow::Result<()> {
+    let settings = store.load_settings()?;
+    
+    println!("{}", "⚙️  Settings".bold());
+    println!("{}", "=".repeat(30));
+    
+    match &settings.timezone {
+        Some(tz) => println!("timezone: {}", tz),
+        None => println!("timezone: {} {}", datetime::system_timezone().name(), "(system)".dimmed()),
+    }
+    match settings.backup_retention {
+        Some(count) => println!("backup-retention: {}", count),
+        None => println!("backup-retention: {} {}", settings.backup_retention(), "(default)".dimmed()),
+    }
+    match &settings.currency {
+        Some(currency) => println!("currency: {}", currency),
+        None => println!("currency: {} {}", settings.currency(), "(default)".dimmed()),
+    }
+    match settings.tax_rate {
+        Some(rate) => println!("tax-rate: {}", rate),
+        None => println!("tax-rate: {} {}", settings.tax_rate(), "(default)".dimmed()),
+    }
+    match settings.invoice_rounding {
+        Some(minutes) => println!("invoice-rounding: {}", minutes),
+        None => println!("invoice-rounding: {} {}", settings.invoice_rounding(), "(default)".dimmed()),
+    }
+    match &settings.invoice_prefix {
+        Some(prefix) => println!("invoice-prefix: {}", prefix),
+        None => println!("invoice-prefix: {} {}", settings.invoice_prefix(), "(default)".dimmed()),
+    }
+    match settings.balance_start {
+        Some(date) => println!("balance-start: {}", date),
+        None => println!("balance-start: {}", "(first entry)".dimmed()),
+    }
+    match settings.balance_cap {
+        Some(hours) => println!("balance-cap: {}", hours),
+        None => println!("balance-cap: {}", "(none)".dimmed()),
+    }
+    
+    Ok(())
+}
+
+pub fn set_config(store: &mut dyn Storage, key: &str, value: Option<&str>) -> anyhow::Result<()> {
+    let mut settings = store.load_settings()?;
+    
+    match key {
+        "timezone" => {
+            settings.timezone = match value {
+                Some(value) => Some(datetime::parse_timezone(value)?.name().to_string()),
+                None => None,
+            };
+        }
+        "backup-retention" => {
+            settings.backup_retention = match value {
+                Some(value) => Some(value.trim().parse().map_err(|_| {
+                    TimeCardError::InvalidInput(format!("Invalid backup-retention: {}. Use a number of backups, 0 disables them", value))
+                })?),
+                None => None,
+            };
+        }
+        "currency" => {
+            settings.currency = value.map(billing::normalize_currency).transpose()?;
+        }
+        "tax-rate" => {
+            settings.tax_rate = match value {
+                Some(value) => Some(value.trim().trim_end_matches('%').parse::<f64>().ok()
+                    .filter(|rate| rate.is_finite() && *rate >= 0.0)
+                    .ok_or_else(|| TimeCardError::InvalidInput(format!("Invalid tax-rate: {}. Use a percentage, e.g. 19", value)))?),
+                None => None,
+            };
+        }
+        "invoice-rounding" => {
+            settings.invoice_rounding = match value {
+                Some(value) => Some(value.trim().parse().map_err(|_| {
+                    TimeCardError::InvalidInput(format!("Invalid invoice-rounding: {}. Use minutes, e.g. 15; 0 bills exact time", value))
+                })?),
+                None => None,
+            };
+        }
+        "invoice-prefix" => {
+            settings.invoice_prefix = value.map(|value| value.trim().to_string());
+        }
+        "balance-start" => {
+            settings.balance_start = match value {
+                Some(value) => Some(NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+                    TimeCardError::InvalidInput(format!("Invalid balance-start: {}. Use a date, e.g. 2025-01-01", value))
+                })?),
+                None => None,
+            };
+        }
+        "balance-cap" => {
+            settings.balance_cap = match value {
+                Some(value) => Some(value.trim().parse::<f64>().ok()
+                    .filter(|hours| hours.is_finite() && *hours >= 0.0)
+                    .ok_or_else(|| TimeCardError::InvalidInput(format!("Invalid balance-cap: {}. Use hours, e.g. 40", value)))?),
+                None => None,
+            };
+        }
+        _ => return Err(TimeCardError::InvalidInput(format!(
+            "Unknown setting: {}. Available: timezone, backup-retention, currency, tax-rate, invoice-rounding, invoice-prefix, balance-start, balance-cap",
+            key
+        )).into()),
+    }
+    
+    store.save_settings(&settings)?;
+    
+    match value {
+        Some(value) => println!("{}", format!("✅ {} set to {}", key, value.trim()).green()),
+        None => println!("{}", format!("✅ {} reset to default", key).green()),
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use chrono::Utc;
+use colored::*;
+use timecard::{
+    datetime,
+    doctor::{self, FixStrategy},
+    storage::Storage,
+};
+
+pub fn run_doctor(
+    store: &mut dyn Storage,
+    strategies: &[FixStrategy],
+    close_at: Option<&str>,
+    max_hours: i64,
+) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    let tz = data.settings.timezone()?;
+    let close_at = close_at.map(|at| datetime::parse_datetime(at, &tz)).transpose()?;
+    
+    println!("{}", "🩺 Data Check".bold());
+    println!("{}", "=".repeat(50));
+    
+    let issues = doctor::diagnose(&data, Utc::now(), max_hours);
+    if issues.is_empty() {
+        println!("{}", "✅ No problems found.".green());
+        return Ok(());
+    }
+    
+    for issue in &issues {
+        let hint = match issue.strategy() {
+            Some(strategy) => format!("[--fix {}]", clap::ValueEnum::to_possible_value(&strategy).unwrap().get_name()),
+            None => "[fix with edit or delete]".to_string(),
+        };
+        println!("{} {} {}", "⚠️ ".yellow(), issue.describe(&data, &tz), hint.dimmed());
+    }
+    println!();
+    
+    if strategies.is_empty() {
+        println!("{}", format!("Found {} problems.", issues.len()).yellow());
+        return Ok(());
+    }
+    
+    let log = doctor::fix(&mut data, strategies, close_at, max_hours, &tz);
+    for line in &log {
+        println!("🔧 {}", line);
+    }
+    if log.is_empty() {
+        println!("{}", "Nothing the chosen strategies can fix.".yellow());
+        return Ok(());
+    }
+    
+    // Fixes may touch any entry, including copies sharing an id, so the
+    // whole document is written back
+    store.save(&data)?;
+    
+    let remaining = doctor::diagnose(&data, Utc::now(), max_hours).len();
+    println!();
+    if remaining == 0 {
+        println!("{}", "✅ All problems fixed.".green());
+    } else {
+        println!("{}", format!("{} problems remain.", remaining).yellow());
+    }
+    
+    Ok(())
+}
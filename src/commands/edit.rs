@@ -0,0 +1,76 @@
+use crate::{commands::project, output::OutputFormat};
+use chrono_tz::Tz;
+use colored::*;
+use timecard::{
+    datetime,
+    models::{TimeCardData, TimeEntry},
+    tracker::{EntryChanges, TimeTracker},
+    TimeCardError,
+};
+
+/// `edit` options as given on the command line; times are still text.
+pub struct EditRequest {
+    pub changes: EntryChanges,
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+pub fn edit_entry(tracker: &mut TimeTracker, id_prefix: &str, request: EditRequest) -> anyhow::Result<()> {
+    let tz = tracker.timezone()?;
+    let mut changes = request.changes;
+    changes.start = request.start.map(|start| datetime::parse_datetime(&start, &tz)).transpose()?;
+    changes.end = request.end.map(|end| datetime::parse_datetime(&end, &tz)).transpose()?;
+    
+    if changes.is_empty() {
+        return Err(TimeCardError::InvalidInput(
+            "Nothing to change. Use --project, --no-project, --description, --tag, --untag, --billable, --start or --end".to_string()
+        ).into());
+    }
+    
+    let edited = tracker.edit(id_prefix, changes)?;
+    project::note_created(edited.created_project.as_ref(), OutputFormat::Text);
+    let data = tracker.load()?;
+    
+    println!("{}", "✏️  Time entry updated!".green());
+    println!("{}", "Before:".bold());
+    print_entry(&data, &edited.before, &tz);
+    println!("{}", "After:".bold());
+    print_entry(&data, &edited.after, &tz);
+    
+    Ok(())
+}
+
+pub fn delete_entry(tracker: &mut TimeTracker, id_prefix: &str) -> anyhow::Result<()> {
+    let tz = tracker.timezone()?;
+    
+    let entry = tracker.delete(id_prefix)?;
+    let data = tracker.load()?;
+    
+    println!("{}", "🗑️  Time entry deleted!".green());
+    print_entry(&data, &entry, &tz);
+    
+    Ok(())
+}
+
+fn print_entry(data: &TimeCardData, entry: &TimeEntry, tz: &Tz) {
+    println!("  Id: {}", entry.short_id());
+    println!("  Start: {}", entry.start_time.with_timezone(tz).format("%Y-%m-%d %H:%M:%S"));
+    match entry.end_time {
+        Some(end_time) => {
+            println!("  End: {}", end_time.with_timezone(tz).format("%Y-%m-%d %H:%M:%S"));
+            println!("  Duration: {:.2} hours", entry.hours().unwrap_or(0.0));
+            if !entry.pauses.is_empty() {
+                println!("  Breaks: {:.2} hours", entry.break_duration().num_seconds() as f64 / 3600.0);
+            }
+        }
+        None => println!("  End: {}", "active".green()),
+    }
+    println!("  Project: {}", data.project_name(entry.project_id).unwrap_or("No Project").blue());
+    if let Some(desc) = &entry.description {
+        println!("  Description: {}", desc);
+    }
+    if !entry.tags.is_empty() {
+        println!("  Tags: {}", entry.format_tags().cyan());
+    }
+    println!("  Billable: {}", if entry.billable { "yes" } else { "no" });
+}
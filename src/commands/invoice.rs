@@ -0,0 +1,167 @@
+use chrono::Utc;
+use colored::*;
+use std::fs;
+use std::path::PathBuf;
+use timecard::{
+    billing,
+    datetime,
+    invoice::{self, InvoiceFormat, InvoiceOptions},
+    models::Invoice,
+    period,
+    storage::Storage,
+    TimeCardError,
+};
+
+/// Options of `invoice` as given on the command line. `None` falls back to
+/// the settings.
+pub struct InvoiceRequest {
+    pub client: String,
+    pub period: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub tax_rate: Option<f64>,
+    pub rounding: Option<u32>,
+    pub format: InvoiceFormat,
+    pub out_file: Option<PathBuf>,
+    pub dry_run: bool,
+}
+
+pub fn create_invoice(store: &mut dyn Storage, request: InvoiceRequest) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    let tz = data.settings.timezone()?;
+    
+    let range = if request.from.is_some() || request.to.is_some() {
+        period::parse_range(request.from.as_deref(), request.to.as_deref(), &tz)?
+    } else {
+        period::parse_period(&request.period, &tz)?
+    };
+    let client = data.require_client(&request.client)?;
+    
+    let tax_rate = request.tax_rate.unwrap_or(data.settings.tax_rate());
+    if !tax_rate.is_finite() || tax_rate < 0.0 {
+        return Err(TimeCardError::InvalidInput(format!("Tax rate must be zero or positive: {}", tax_rate)).into());
+    }
+    let options = InvoiceOptions {
+        tax_rate,
+        rounding: request.rounding.unwrap_or(data.settings.invoice_rounding()),
+        issued_on: datetime::today(&tz),
+    };
+    let mut invoice = invoice::build_invoice(&data, client, &range, &options, &tz)?;
+    
+    if request.dry_run {
+        print!("{}", invoice::render(&invoice, request.format));
+        println!();
+        println!("{}", "Dry run: no invoice was created and entries are still uninvoiced.".yellow());
+        return Ok(());
+    }
+    
+    invoice.sequence = data.next_invoice_sequence();
+    invoice.number = format!("{}{:04}", data.settings.invoice_prefix(), invoice.sequence);
+    
+    // Mark the billed entries so the next invoice skips them, saving them
+    // together with the invoice
+    let now = Utc::now();
+    for entry in data.time_entries.iter_mut().filter(|entry| invoice.entry_ids.contains(&entry.id)) {
+        entry.invoice_id = Some(invoice.id);
+        entry.updated_at = now;
+    }
+    data.invoices.push(invoice.clone());
+    store.save(&data)?;
+    
+    println!("{}", format!(
+        "🧾 Invoice {} created: {} entries, {}",
+        invoice.number,
+        invoice.entry_ids.len(),
+        billing::format_amount(invoice.total, &invoice.currency)
+    ).green());
+    write_invoice(&invoice, request.format, request.out_file)
+}
+
+pub fn list_invoices(store: &mut dyn Storage) -> anyhow::Result<()> {
+    let mut invoices = store.load_invoices()?;
+    
+    if invoices.is_empty() {
+        println!("{}", "No invoices found.".yellow());
+        return Ok(());
+    }
+    
+    invoices.sort_by_key(|invoice| invoice.sequence);
+    
+    println!("{}", "🧾 Invoices".bold());
+    println!("{}", "=".repeat(70));
+    
+    for invoice in invoices {
+        let line = format!("{} {} {} {} to {} {:>12} {}",
+            invoice.number,
+            invoice.issued_on.format("%Y-%m-%d"),
+            invoice.client_name,
+            invoice.period_start.format("%Y-%m-%d"),
+            invoice.period_end.format("%Y-%m-%d"),
+            format!("{:.2}", invoice.total),
+            invoice.currency
+        );
+        if invoice.void {
+            println!("{} {}", line.dimmed(), "[void]".dimmed());
+        } else {
+            println!("{}", line);
+        }
+    }
+    
+    Ok(())
+}
+
+pub fn show_invoice(
+    store: &mut dyn Storage,
+    number: &str,
+    format: InvoiceFormat,
+    out_file: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let data = store.load()?;
+    
+    let invoice = data.find_invoice(number)?;
+    write_invoice(invoice, format, out_file)
+}
+
+/// Cancel an invoice. Its number stays taken, but its entries can be billed
+/// again.
+pub fn void_invoice(store: &mut dyn Storage, number: &str) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    
+    let id = data.find_invoice(number)?.id;
+    let now = Utc::now();
+    let invoice = data.invoices.iter_mut().find(|invoice| invoice.id == id).unwrap();
+    if invoice.void {
+        return Err(TimeCardError::InvalidInput(format!("Invoice {} is already void", invoice.number)).into());
+    }
+    invoice.void = true;
+    invoice.updated_at = now;
+    let invoice = invoice.clone();
+    
+    let mut released = 0;
+    for entry in data.time_entries.iter_mut().filter(|entry| entry.invoice_id == Some(invoice.id)) {
+        entry.invoice_id = None;
+        entry.updated_at = now;
+        released += 1;
+    }
+    store.save(&data)?;
+    
+    println!("{}", format!("🗑️  Invoice {} voided", invoice.number).green());
+    println!("{} time entries can be invoiced again", released);
+    
+    Ok(())
+}
+
+fn write_invoice(invoice: &Invoice, format: InvoiceFormat, out_file: Option<PathBuf>) -> anyhow::Result<()> {
+    let rendered = invoice::render(invoice, format);
+    match out_file {
+        Some(path) => {
+            fs::write(&path, rendered)?;
+            println!("{}", format!("📄 Invoice written to: {}", path.display()).green());
+        }
+        None => {
+            println!();
+            print!("{}", rendered);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use colored::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use timecard::{
+    datetime,
+    leave,
+    models::{Leave, LeaveKind, TimeCardData},
+    period,
+    storage::Storage,
+    TimeCardError,
+};
+
+/// Options of `leave add` as given on the command line.
+pub struct LeaveRequest {
+    pub kind: LeaveKind,
+    pub from: String,
+    pub to: Option<String>,
+    pub hours: Option<f64>,
+    pub description: Option<String>,
+}
+
+pub fn add_leave(store: &mut dyn Storage, request: LeaveRequest) -> anyhow::Result<()> {
+    let data = store.load()?;
+    let tz = data.settings.timezone()?;
+    
+    let first = parse_date(&request.from, &tz)?;
+    let last = match &request.to {
+        Some(to) => parse_date(to, &tz)?,
+        None => first,
+    };
+    if last < first {
+        return Err(TimeCardError::InvalidInput(format!("{} is before {}", last, first)).into());
+    }
+    if let Some(hours) = request.hours {
+        if !(hours > 0.0 && hours <= 24.0) {
+            return Err(TimeCardError::InvalidInput(format!("Hours must be more than 0 and at most 24: {}", hours)).into());
+        }
+    }
+    
+    // Over a range, days without a target (like weekends) aren't taken off
+    let targets = &data.settings.targets;
+    let working_days_only = first != last && targets.days.iter().any(Option::is_some);
+    let days: Vec<_> = first.iter_days()
+        .take_while(|date| *date <= last)
+        .filter(|date| !working_days_only || targets.date(*date).is_some_and(|hours| hours > 0.0))
+        .filter(|date| !data.leave_on(*date).any(|leave| leave.kind == request.kind))
+        .map(|date| Leave::new(date, request.kind, request.hours, request.description.clone()))
+        .collect();
+    if days.is_empty() {
+        return Err(TimeCardError::InvalidInput(format!(
+            "Nothing to add: {} is already recorded on these days, or they have no target",
+            request.kind.label()
+        )).into());
+    }
+    store.insert_leave(&days)?;
+    
+    let amount = request.hours.map(|hours| format!("{:.2} hours of ", hours)).unwrap_or_default();
+    match days.as_slice() {
+        [day] => println!("{}", format!("🏖️  Added {}{} on {}", amount, request.kind.label(), day.date.format("%a %Y-%m-%d")).green()),
+        _ => println!("{}", format!(
+            "🏖️  Added {}{} on {} days from {} to {}",
+            amount,
+            request.kind.label(),
+            days.len(),
+            first,
+            last
+        ).green()),
+    }
+    
+    Ok(())
+}
+
+pub fn list_leave(store: &mut dyn Storage, period: &str) -> anyhow::Result<()> {
+    let data = store.load()?;
+    let tz = data.settings.timezone()?;
+    let range = period::parse_period(period, &tz)?;
+    let days = leave::leave_between(&data, range.first_day, range.last_day);
+    
+    if days.is_empty() {
+        println!("{}", format!("No leave in {}.", range.name).yellow());
+        return Ok(());
+    }
+    
+    println!("{}", format!("🏖️  Leave - {}", range.name).bold());
+    println!("{}", "=".repeat(50));
+    
+    let mut totals: BTreeMap<LeaveKind, (usize, f64)> = BTreeMap::new();
+    for day in days {
+        let hours = leave::leave_hours(&data, day);
+        let total = totals.entry(day.kind).or_default();
+        total.0 += 1;
+        total.1 += hours;
+        println!("{}", leave_line(&data, day));
+    }
+    
+    println!("{}", "=".repeat(50));
+    for (kind, (days, hours)) in totals {
+        println!("{}: {} {}, {:.2} hours", kind.label(), days, if days == 1 { "day" } else { "days" }, hours);
+    }
+    
+    Ok(())
+}
+
+pub fn remove_leave(store: &mut dyn Storage, prefix: &str) -> anyhow::Result<()> {
+    let data = store.load()?;
+    
+    let id = data.find_leave_by_prefix(prefix)?;
+    store.delete_leave(id)?;
+    
+    println!("{}", "🗑️  Leave removed".green());
+    
+    Ok(())
+}
+
+/// Record the days of an iCalendar file's events as leave, skipping days
+/// that already have leave of that kind.
+pub fn import_leave(store: &mut dyn Storage, path: &Path, kind: LeaveKind) -> anyhow::Result<()> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| TimeCardError::NotFound(format!("Can't read {}: {}", path.display(), e)))?;
+    let data = store.load()?;
+    let calendar = leave::parse_ics(&text, &data.settings.timezone()?)?;
+    
+    let found = calendar.len();
+    let mut days: Vec<Leave> = Vec::new();
+    for day in calendar {
+        let recorded = data.leave_on(day.date).chain(&days).any(|leave| leave.date == day.date && leave.kind == kind);
+        if !recorded {
+            days.push(Leave::new(day.date, kind, None, day.summary));
+        }
+    }
+    
+    if days.is_empty() {
+        println!("{}", format!("Every day in {} is already recorded as {}.", path.display(), kind.label()).yellow());
+        return Ok(());
+    }
+    store.insert_leave(&days)?;
+    
+    for day in &days {
+        println!("{}", leave_line(&data, day));
+    }
+    println!("{}", format!("📥 Imported {} days of {} from {}", days.len(), kind.label(), path.display()).green());
+    if found > days.len() {
+        println!("{}", format!("{} days were already recorded", found - days.len()).dimmed());
+    }
+    
+    Ok(())
+}
+
+fn leave_line(data: &TimeCardData, leave: &Leave) -> String {
+    let hours = match leave.hours {
+        Some(hours) => format!("{:.2} hours", hours),
+        None => format!("whole day ({:.2} hours)", leave::leave_hours(data, leave)),
+    };
+    let mut line = format!(
+        "{} {} {} {}",
+        leave.short_id().dimmed(),
+        leave.date.format("%a %Y-%m-%d"),
+        leave.kind.label().blue(),
+        hours
+    );
+    if let Some(description) = &leave.description {
+        line.push_str(&format!(" - {}", description));
+    }
+    line
+}
+
+fn parse_date(input: &str, tz: &Tz) -> anyhow::Result<NaiveDate> {
+    Ok(datetime::parse_datetime(input, tz)?.with_timezone(tz).date_naive())
+}
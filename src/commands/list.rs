@@ -1,29 +1,64 @@
-use crate::storage;
+use crate::output::{EntryJson, OutputFormat};
 use colored::*;
-use std::path::Path;
+use serde::Serialize;
+use timecard::{models::{self, TagFilter}, storage::Storage, TimeCardError};
+
+#[derive(Serialize)]
+struct ListJson {
+    entries: Vec<EntryJson>,
+    summary: ListSummaryJson,
+}
+
+#[derive(Serialize)]
+struct ListSummaryJson {
+    entries: usize,
+    hours: f64,
+    active: usize,
+}
 
 pub fn list_entries(
-    file_path: &Path,
+    store: &mut dyn Storage,
     project_filter: Option<&str>,
+    tag_filter: &TagFilter,
     limit: Option<usize>,
+    output: OutputFormat,
 ) -> anyhow::Result<()> {
-    let data = storage::load_data(file_path)?;
+    let data = store.load()?;
+    let tz = data.settings.timezone()?;
     
     let mut entries: Vec<_> = data.time_entries.iter().collect();
     
     // Sort by start time (newest first)
-    entries.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.start_time));
     
     // Apply project filter
     if let Some(project) = project_filter {
-        entries.retain(|entry| entry.project.as_deref() == Some(project));
+        let project_id = data.find_project(project)
+            .map(|project| project.id)
+            .ok_or_else(|| TimeCardError::NotFound(format!("Unknown project: {}", project)))?;
+        entries.retain(|entry| entry.project_id == Some(project_id));
     }
     
+    // Apply tag filter
+    entries.retain(|entry| tag_filter.matches(entry));
+    
     // Apply limit
     if let Some(limit) = limit {
         entries.truncate(limit);
     }
     
+    let total_entries = entries.len();
+    let total_hours = models::sum_hours(entries.iter().filter_map(|entry| entry.hours()));
+    let active_entries = entries.iter().filter(|entry| entry.is_active()).count();
+    
+    if output.is_machine() {
+        let items: Vec<_> = entries.iter().map(|entry| EntryJson::new(&data, entry, &tz)).collect();
+        return output.emit_list(items, |items| ListJson {
+            entries: items,
+            summary: ListSummaryJson { entries: total_entries, hours: total_hours, active: active_entries },
+        });
+    }
+    
     if entries.is_empty() {
         println!("{}", "No time entries found.".yellow());
         return Ok(());
@@ -40,11 +75,12 @@ pub fn list_entries(
         };
         
         let hours = entry.hours().unwrap_or(0.0);
-        let project = entry.project.as_deref().unwrap_or("No Project");
+        let project = data.project_name(entry.project_id).unwrap_or("No Project");
         
-        println!("{} {} - {} ({:.2}h)", 
+        println!("{} {} {} - {} ({:.2}h)", 
             status,
-            entry.start_time.format("%Y-%m-%d %H:%M"),
+            entry.short_id().dimmed(),
+            entry.start_time.with_timezone(&tz).format("%Y-%m-%d %H:%M"),
             project.blue(),
             hours
         );
@@ -53,18 +89,18 @@ pub fn list_entries(
             println!("    Description: {}", desc);
         }
         
+        if !entry.tags.is_empty() {
+            println!("    Tags: {}", entry.format_tags().cyan());
+        }
+        
         if let Some(end_time) = entry.end_time {
-            println!("    Ended: {}", end_time.format("%Y-%m-%d %H:%M"));
+            println!("    Ended: {}", end_time.with_timezone(&tz).format("%Y-%m-%d %H:%M"));
         }
         
         println!();
     }
     
     // Summary
-    let total_entries = entries.len();
-    let total_hours: f64 = entries.iter().filter_map(|entry| entry.hours()).sum();
-    let active_entries = entries.iter().filter(|entry| entry.is_active()).count();
-    
     println!("{}", "📊 Summary".bold());
     println!("Total Entries: {}", total_entries);
     println!("Total Hours: {:.2}", total_hours);
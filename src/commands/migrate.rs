@@ -0,0 +1,29 @@
+use colored::*;
+use std::path::PathBuf;
+use timecard::{storage::{self, Backend, Storage}, TimeCardError};
+
+pub fn migrate(store: &mut dyn Storage, to: Backend, out_file: Option<PathBuf>) -> anyhow::Result<()> {
+    let target = out_file.unwrap_or_else(|| store.path().with_extension(to.extension()));
+    
+    if target == store.path() {
+        return Err(TimeCardError::InvalidInput(format!(
+            "Target file is the current data file: {}",
+            target.display()
+        )).into());
+    }
+    if target.exists() {
+        return Err(TimeCardError::InvalidInput(format!("Target file already exists: {}", target.display())).into());
+    }
+    
+    let data = store.load()?;
+    let mut destination = storage::open(&target, Some(to))?;
+    destination.save(&data)?;
+    
+    println!("{}", format!("✅ Migrated to {}", target.display()).green());
+    println!("Entries: {}", data.time_entries.len());
+    println!("Projects: {}", data.projects.len());
+    println!();
+    println!("Use it with: timecard --data-file {}", target.display());
+    
+    Ok(())
+}
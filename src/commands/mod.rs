@@ -3,3 +3,15 @@ pub mod status;
 pub mod report;
 pub mod list;
 pub mod add;
+pub mod project;
+pub mod config;
+pub mod edit;
+pub mod migrate;
+pub mod backup;
+pub mod client;
+pub mod rate;
+pub mod target;
+pub mod balance;
+pub mod leave;
+pub mod invoice;
+pub mod doctor;
@@ -0,0 +1,130 @@
+use crate::output::OutputFormat;
+use colored::*;
+use timecard::{models::Project, storage::Storage};
+
+/// Mention a project created on the fly, e.g. by clocking in.
+pub fn note_created(project: Option<&Project>, output: OutputFormat) {
+    if let Some(project) = project {
+        output.note(format!("📁 Created project: {}", project.name).yellow());
+    }
+}
+
+pub fn add_project(
+    store: &mut dyn Storage,
+    name: &str,
+    description: Option<String>,
+    client: Option<&str>,
+) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    
+    if let Some(client) = client {
+        data.require_client(client)?;
+    }
+    data.add_project(name, description)?;
+    let project = data.set_project_client(name, client)?;
+    store.insert_project(project)?;
+    
+    println!("{}", format!("✅ Project added: {}", name.trim()).green());
+    
+    Ok(())
+}
+
+pub fn list_projects(store: &mut dyn Storage, include_archived: bool) -> anyhow::Result<()> {
+    let data = store.load()?;
+    
+    let mut projects: Vec<_> = data
+        .projects
+        .iter()
+        .filter(|project| include_archived || !project.archived)
+        .collect();
+    projects.sort_by_key(|project| project.name.to_lowercase());
+    
+    if projects.is_empty() {
+        println!("{}", "No projects found.".yellow());
+        return Ok(());
+    }
+    
+    println!("{}", "📁 Projects".bold());
+    println!("{}", "=".repeat(50));
+    
+    for project in projects {
+        let hours = data.total_hours_by_project(project.id);
+        let entries = data.get_entries_by_project(project.id).len();
+        
+        if project.archived {
+            println!("{} ({:.2}h, {} entries) {}", 
+                project.name.dimmed(), 
+                hours, 
+                entries, 
+                "[archived]".dimmed()
+            );
+        } else {
+            println!("{} ({:.2}h, {} entries)", project.name.blue(), hours, entries);
+        }
+        
+        if let Some(desc) = &project.description {
+            println!("    Description: {}", desc);
+        }
+        if let Some(client) = data.client_for_project(Some(project.id)) {
+            println!("    Client: {}", client.name);
+        }
+    }
+    
+    Ok(())
+}
+
+pub fn rename_project(store: &mut dyn Storage, name: &str, new_name: &str) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    
+    let project = data.rename_project(name, new_name)?;
+    store.update_project(project)?;
+    
+    println!("{}", format!("✅ Project renamed: {} → {}", name.trim(), new_name.trim()).green());
+    
+    Ok(())
+}
+
+pub fn archive_project(store: &mut dyn Storage, name: &str, archived: bool) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    
+    let project = data.set_project_archived(name, archived)?;
+    store.update_project(project)?;
+    
+    if archived {
+        println!("{}", format!("📦 Project archived: {}", name.trim()).green());
+    } else {
+        println!("{}", format!("✅ Project restored: {}", name.trim()).green());
+    }
+    
+    Ok(())
+}
+
+pub fn set_project_client(store: &mut dyn Storage, name: &str, client: Option<&str>) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    
+    let project = data.set_project_client(name, client)?;
+    store.update_project(project)?;
+    
+    match client {
+        Some(client) => println!("{}", format!("✅ Project {} now belongs to {}", name.trim(), client.trim()).green()),
+        None => println!("{}", format!("✅ Project {} no longer has a client", name.trim()).green()),
+    }
+    
+    Ok(())
+}
+
+pub fn delete_project(store: &mut dyn Storage, name: &str, force: bool) -> anyhow::Result<()> {
+    let mut data = store.load()?;
+    
+    // The project, its rates and the entries it leaves unassigned are saved
+    // together
+    let (_, detached) = data.delete_project(name, force)?;
+    store.save(&data)?;
+    
+    println!("{}", format!("🗑️  Project deleted: {}", name.trim()).green());
+    if !detached.is_empty() {
+        println!("{} time entries are now unassigned", detached.len());
+    }
+    
+    Ok(())
+}
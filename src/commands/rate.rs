@@ -0,0 +1,110 @@
+use colored::*;
+use timecard::{
+    billing,
+    datetime,
+    models::{normalize_tag, Rate, RateScope},
+    storage::Storage,
+    TimeCardError,
+};
+
+/// What `rate set` applies to, as given on the command line.
+pub enum RateTarget {
+    Client(String),
+    Project(String),
+    Tag(String),
+}
+
+pub fn set_rate(
+    store: &mut dyn Storage,
+    target: RateTarget,
+    hourly_rate: f64,
+    currency: Option<&str>,
+    from: Option<&str>,
+) -> anyhow::Result<()> {
+    let data = store.load()?;
+    let tz = data.settings.timezone()?;
+    
+    if !hourly_rate.is_finite() || hourly_rate < 0.0 {
+        return Err(TimeCardError::InvalidInput(format!("Hourly rate must be zero or positive: {}", hourly_rate)).into());
+    }
+    
+    // Rates default to the currency of the client they bill
+    let (scope, client) = match &target {
+        RateTarget::Client(name) => {
+            let client = data.require_client(name)?;
+            (RateScope::Client(client.id), Some(client))
+        }
+        RateTarget::Project(name) => {
+            let project = data.find_project(name)
+                .ok_or_else(|| TimeCardError::NotFound(format!("Unknown project: {}", name)))?;
+            (RateScope::Project(project.id), data.client_for_project(Some(project.id)))
+        }
+        RateTarget::Tag(tag) => {
+            let tag = normalize_tag(tag)
+                .ok_or_else(|| TimeCardError::InvalidInput(format!("Invalid tag: {}", tag)))?;
+            (RateScope::Tag(tag), None)
+        }
+    };
+    let currency = match currency {
+        Some(currency) => billing::normalize_currency(currency)?,
+        None => data.client_currency(client),
+    };
+    let effective_from = match from {
+        Some(from) => datetime::parse_datetime(from, &tz)?.with_timezone(&tz).date_naive(),
+        None => datetime::today(&tz),
+    };
+    
+    let rate = Rate::new(scope, hourly_rate, currency, effective_from);
+    store.insert_rate(&rate)?;
+    
+    println!("{}", format!(
+        "✅ Rate for {} set to {}/h from {}",
+        data.rate_scope_name(&rate.scope),
+        billing::format_amount(rate.hourly_rate, &rate.currency),
+        rate.effective_from.format("%Y-%m-%d")
+    ).green());
+    
+    Ok(())
+}
+
+pub fn list_rates(store: &mut dyn Storage) -> anyhow::Result<()> {
+    let data = store.load()?;
+    
+    if data.rates.is_empty() {
+        println!("{}", "No rates set.".yellow());
+        return Ok(());
+    }
+    
+    let mut rates: Vec<_> = data.rates
+        .iter()
+        .map(|rate| (data.rate_scope_name(&rate.scope), rate))
+        .collect();
+    rates.sort_by(|(a_scope, a), (b_scope, b)| {
+        a_scope.to_lowercase().cmp(&b_scope.to_lowercase()).then(a.effective_from.cmp(&b.effective_from))
+    });
+    
+    println!("{}", "💰 Hourly Rates".bold());
+    println!("{}", "=".repeat(50));
+    
+    for (scope, rate) in rates {
+        println!("{} {} {}/h from {}",
+            rate.short_id().dimmed(),
+            scope.blue(),
+            billing::format_amount(rate.hourly_rate, &rate.currency),
+            rate.effective_from.format("%Y-%m-%d")
+        );
+    }
+    
+    Ok(())
+}
+
+pub fn remove_rate(store: &mut dyn Storage, prefix: &str) -> anyhow::Result<()> {
+    let data = store.load()?;
+    
+    let id = data.find_rate_by_prefix(prefix)?;
+    store.delete_rate(id)?;
+    
+    println!("{}", "🗑️  Rate removed".green());
+    
+    Ok(())
+}
@@ -1,183 +1,352 @@
-use crate::storage;
-use chrono::{DateTime, Duration, Utc, Datelike};
+use crate::output::{EntryJson, OutputFormat};
+use chrono::NaiveDate;
+use chrono_tz::Tz;
 use colored::*;
 use csv::Writer;
-use std::path::Path;
+use serde::Serialize;
+use timecard::{
+    billing::{self, Amounts},
+    leave,
+    models::{self, Leave, LeaveKind, TagFilter, TimeCardData, TimeEntry},
+    period::{self, Period},
+    report::{self, Report},
+    storage::Storage,
+};
 
-pub fn generate_report(
-    file_path: &Path,
-    period: &str,
-    project_filter: Option<&str>,
-    csv_export: bool,
-) -> anyhow::Result<()> {
-    let data = storage::load_data(file_path)?;
+/// Options of `report` as given on the command line.
+pub struct ReportRequest {
+    pub period: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub project: Option<String>,
+    pub tag_filter: TagFilter,
+    pub csv: bool,
+}
+
+#[derive(Serialize)]
+struct ReportJson {
+    period: PeriodJson,
+    summary: SummaryJson,
+    projects: Vec<ProjectJson>,
+    tags: Vec<TagJson>,
+    untagged_hours: f64,
+    breaks: Vec<BreakJson>,
+    leave: Vec<LeaveJson>,
+    entries: Vec<EntryJson>,
+}
+
+#[derive(Serialize)]
+struct PeriodJson {
+    name: String,
+    first_day: NaiveDate,
+    last_day: NaiveDate,
+}
+
+#[derive(Serialize)]
+struct SummaryJson {
+    hours: f64,
+    billable_hours: f64,
+    unrated_hours: f64,
+    amounts: Amounts,
+    leave_hours: f64,
+    entries: usize,
+}
+
+#[derive(Serialize)]
+struct ProjectJson {
+    project: Option<String>,
+    hours: f64,
+    amounts: Amounts,
+}
+
+#[derive(Serialize)]
+struct TagJson {
+    tag: String,
+    hours: f64,
+}
+
+#[derive(Serialize)]
+struct BreakJson {
+    date: NaiveDate,
+    hours: f64,
+}
+
+#[derive(Serialize)]
+struct LeaveJson {
+    date: NaiveDate,
+    #[serde(rename = "type")]
+    kind: LeaveKind,
+    hours: f64,
+    description: Option<String>,
+}
+
+pub fn generate_report(store: &mut dyn Storage, request: ReportRequest, output: OutputFormat) -> anyhow::Result<()> {
+    let tz = store.load_settings()?.timezone()?;
     
-    let (start_date, end_date, period_name) = parse_period(period)?;
+    let range = if request.from.is_some() || request.to.is_some() {
+        period::parse_range(request.from.as_deref(), request.to.as_deref(), &tz)?
+    } else {
+        period::parse_period(&request.period, &tz)?
+    };
+    let data = store.load_period(range.start, range.end)?;
+    let entries = report::select_entries(&data, &range, request.project.as_deref(), &request.tag_filter)?;
+    let report = Report::new(&data, entries, &tz);
     
-    println!("{}", format!("📊 Time Report - {}", period_name).bold());
-    println!("{}", "=".repeat(50));
-    println!("Period: {} to {}", 
-        start_date.format("%Y-%m-%d"), 
-        end_date.format("%Y-%m-%d")
+    // Leave isn't booked on projects or tags, so filtered reports leave it out
+    let leave = if request.project.is_some() || !request.tag_filter.is_empty() {
+        Vec::new()
+    } else {
+        leave::leave_between(&data, range.first_day, range.last_day)
+    };
+    // Comp time was worked earlier, so it doesn't add to the hours
+    let leave_hours = models::sum_hours(
+        leave.iter()
+            .filter(|day| day.kind.excuses_target())
+            .map(|day| leave::leave_hours(&data, day)),
     );
-    println!();
     
-    let entries = data.get_entries_by_period(start_date, end_date);
-    let filtered_entries: Vec<_> = if let Some(project) = project_filter {
-        entries.into_iter().filter(|entry| {
-            entry.project.as_deref() == Some(project)
-        }).collect()
+    let csv_file = if request.csv && !report.entries.is_empty() {
+        Some(export_to_csv(&data, &report.entries, &range, &tz)?)
     } else {
-        entries
+        None
     };
     
-    if filtered_entries.is_empty() {
+    if output.is_machine() {
+        if let Some(filename) = csv_file {
+            output.note(format!("CSV exported to: {}", filename));
+        }
+        let items: Vec<_> = report.entries.iter().map(|entry| EntryJson::new(&data, entry, &tz)).collect();
+        return output.emit_list(items, |entries| ReportJson {
+            period: PeriodJson { name: range.name.clone(), first_day: range.first_day, last_day: range.last_day },
+            summary: SummaryJson {
+                hours: report.total_hours,
+                billable_hours: report.billable_hours,
+                unrated_hours: report.unrated_hours,
+                amounts: report.amounts,
+                leave_hours,
+                entries: entries.len(),
+            },
+            projects: report.projects
+                .into_iter()
+                .map(|(project, total)| ProjectJson {
+                    project: project.map(str::to_string),
+                    hours: total.hours,
+                    amounts: total.amounts,
+                })
+                .collect(),
+            tags: report.tags
+                .into_iter()
+                .map(|(tag, hours)| TagJson { tag: tag.to_string(), hours })
+                .collect(),
+            untagged_hours: report.untagged_hours,
+            breaks: report.breaks
+                .into_iter()
+                .map(|(date, hours)| BreakJson { date, hours })
+                .collect(),
+            leave: leave
+                .iter()
+                .map(|day| LeaveJson {
+                    date: day.date,
+                    kind: day.kind,
+                    hours: leave::leave_hours(&data, day),
+                    description: day.description.clone(),
+                })
+                .collect(),
+            entries,
+        });
+    }
+    
+    println!("{}", format!("📊 Time Report - {}", range.name).bold());
+    println!("{}", "=".repeat(50));
+    println!("Period: {} to {}", 
+        range.first_day.format("%Y-%m-%d"), 
+        range.last_day.format("%Y-%m-%d")
+    );
+    println!();
+    
+    if report.entries.is_empty() {
+        print_leave(&data, &leave, leave_hours);
         println!("{}", "No time entries found for this period.".yellow());
         return Ok(());
     }
     
-    let total_hours: f64 = filtered_entries
-        .iter()
-        .filter_map(|entry| entry.hours())
-        .sum();
-    
     println!("{}", "📈 Summary".bold());
-    println!("Total Hours: {:.2}", total_hours);
-    println!("Total Entries: {}", filtered_entries.len());
+    println!("Total Hours: {:.2}", report.total_hours);
+    println!("Billable Hours: {:.2}", report.billable_hours);
+    if !report.amounts.is_empty() {
+        println!("Amount: {}", report.amounts.to_string().green());
+    }
+    if report.unrated_hours > 0.0 && !data.rates.is_empty() {
+        println!("{}", format!("⚠️  {:.2} billable hours have no rate", report.unrated_hours).yellow());
+    }
+    println!("Total Entries: {}", report.entries.len());
+    if leave_hours > 0.0 {
+        println!("Leave Hours: {:.2}", leave_hours);
+        println!("Worked and Leave: {:.2}", report.total_hours + leave_hours);
+    }
     println!();
     
-    // Project breakdown
-    let mut project_hours = std::collections::HashMap::new();
-    for entry in &filtered_entries {
-        let project = entry.project.as_deref().unwrap_or("No Project");
-        *project_hours.entry(project).or_insert(0.0) += entry.hours().unwrap_or(0.0);
+    if report.projects.len() > 1 || !report.amounts.is_empty() {
+        println!("{}", "🏷️  Project Breakdown".bold());
+        for (project, total) in &report.projects {
+            let project = project.unwrap_or("No Project");
+            let percentage = percent(total.hours, report.total_hours);
+            if total.amounts.is_empty() {
+                println!("  {}: {:.2} hours ({:.1}%)", project.blue(), total.hours, percentage);
+            } else {
+                println!("  {}: {:.2} hours ({:.1}%) - {}", project.blue(), total.hours, percentage, total.amounts);
+            }
+        }
+        println!();
     }
     
-    if project_hours.len() > 1 {
-        println!("{}", "🏷️  Project Breakdown".bold());
-        for (project, hours) in project_hours {
-            let percentage = (hours / total_hours) * 100.0;
-            println!("  {}: {:.2} hours ({:.1}%)", project.blue(), hours, percentage);
+    if !report.tags.is_empty() {
+        println!("{}", "🔖 Tag Breakdown".bold());
+        for (tag, hours) in &report.tags {
+            let percentage = percent(*hours, report.total_hours);
+            println!("  {}: {:.2} hours ({:.1}%)", format!("+{}", tag).cyan(), hours, percentage);
+        }
+        if report.untagged_hours > 0.0 {
+            let percentage = percent(report.untagged_hours, report.total_hours);
+            println!("  {}: {:.2} hours ({:.1}%)", "untagged".dimmed(), report.untagged_hours, percentage);
         }
         println!();
     }
     
+    if !report.breaks.is_empty() {
+        println!("{}", "☕ Breaks".bold());
+        for (day, hours) in &report.breaks {
+            println!("  {}: {:.2} hours", day.format("%Y-%m-%d %a"), hours);
+        }
+        println!("  Total: {:.2} hours", report.total_break_hours());
+        println!();
+    }
+    
+    print_leave(&data, &leave, leave_hours);
+    
     // Detailed entries
     println!("{}", "📝 Detailed Entries".bold());
-    for entry in &filtered_entries {
+    for entry in &report.entries {
         let hours = entry.hours().unwrap_or(0.0);
-        let project = entry.project.as_deref().unwrap_or("No Project");
+        let project = data.project_name(entry.project_id).unwrap_or("No Project");
+        
+        let billing = match billing::entry_amount(&data, entry, &tz) {
+            Some((amount, rate)) => format!(", {}", billing::format_amount(amount, &rate.currency)),
+            None if !entry.billable => ", non-billable".to_string(),
+            None => String::new(),
+        };
         
-        println!("  {} - {} ({:.2}h)", 
-            entry.start_time.format("%Y-%m-%d %H:%M"),
+        println!("  {} - {} ({:.2}h{})", 
+            entry.start_time.with_timezone(&tz).format("%Y-%m-%d %H:%M"),
             project.blue(),
-            hours
+            hours,
+            billing
         );
         
         if let Some(desc) = &entry.description {
             println!("    Description: {}", desc);
         }
+        if !entry.tags.is_empty() {
+            println!("    Tags: {}", entry.format_tags().cyan());
+        }
     }
     
-    // CSV export
-    if csv_export {
-        export_to_csv(&filtered_entries, period)?;
+    if let Some(filename) = csv_file {
+        println!("{}", format!("📄 CSV exported to: {}", filename).green());
     }
     
     Ok(())
 }
 
-fn parse_period(period: &str) -> anyhow::Result<(DateTime<Utc>, DateTime<Utc>, String)> {
-    let now = Utc::now();
-    let today = now.date_naive();
-    
-    match period.to_lowercase().as_str() {
-        "today" => {
-            let start = DateTime::from_naive_utc_and_offset(today.and_hms_opt(0, 0, 0).unwrap(), Utc);
-            let end = DateTime::from_naive_utc_and_offset(today.and_hms_opt(23, 59, 59).unwrap(), Utc);
-            Ok((start, end, "Today".to_string()))
-        }
-        "yesterday" => {
-            let yesterday = today - Duration::days(1);
-            let start = DateTime::from_naive_utc_and_offset(yesterday.and_hms_opt(0, 0, 0).unwrap(), Utc);
-            let end = DateTime::from_naive_utc_and_offset(yesterday.and_hms_opt(23, 59, 59).unwrap(), Utc);
-            Ok((start, end, "Yesterday".to_string()))
-        }
-        "week" | "this-week" => {
-            let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
-            let week_end = week_start + Duration::days(6);
-            let start = DateTime::from_naive_utc_and_offset(week_start.and_hms_opt(0, 0, 0).unwrap(), Utc);
-            let end = DateTime::from_naive_utc_and_offset(week_end.and_hms_opt(23, 59, 59).unwrap(), Utc);
-            Ok((start, end, "This Week".to_string()))
-        }
-        "last-week" => {
-            let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
-            let last_week_start = week_start - Duration::days(7);
-            let last_week_end = last_week_start + Duration::days(6);
-            let start = DateTime::from_naive_utc_and_offset(last_week_start.and_hms_opt(0, 0, 0).unwrap(), Utc);
-            let end = DateTime::from_naive_utc_and_offset(last_week_end.and_hms_opt(23, 59, 59).unwrap(), Utc);
-            Ok((start, end, "Last Week".to_string()))
-        }
-        "month" | "this-month" => {
-            let month_start = today.with_day(1).unwrap();
-            let next_month = if today.month() == 12 {
-                today.with_year(today.year() + 1).unwrap().with_month(1).unwrap()
-            } else {
-                today.with_month(today.month() + 1).unwrap()
-            };
-            let month_end = next_month.with_day(1).unwrap() - Duration::days(1);
-            let start = DateTime::from_naive_utc_and_offset(month_start.and_hms_opt(0, 0, 0).unwrap(), Utc);
-            let end = DateTime::from_naive_utc_and_offset(month_end.and_hms_opt(23, 59, 59).unwrap(), Utc);
-            Ok((start, end, "This Month".to_string()))
-        }
-        "last-month" => {
-            let month_start = today.with_day(1).unwrap();
-            let last_month_start = if today.month() == 1 {
-                today.with_year(today.year() - 1).unwrap().with_month(12).unwrap()
-            } else {
-                today.with_month(today.month() - 1).unwrap()
-            };
-            let last_month_start = last_month_start.with_day(1).unwrap();
-            let last_month_end = month_start - Duration::days(1);
-            let start = DateTime::from_naive_utc_and_offset(last_month_start.and_hms_opt(0, 0, 0).unwrap(), Utc);
-            let end = DateTime::from_naive_utc_and_offset(last_month_end.and_hms_opt(23, 59, 59).unwrap(), Utc);
-            Ok((start, end, "Last Month".to_string()))
-        }
-        _ => {
-            Err(anyhow::anyhow!("Invalid period: {}. Use: today, yesterday, week, last-week, month, last-month", period))
-        }
+fn print_leave(data: &TimeCardData, leave: &[&Leave], leave_hours: f64) {
+    if leave.is_empty() {
+        return;
     }
+    println!("{}", "🏖️  Leave".bold());
+    for day in leave {
+        let description = day.description.as_deref().map(|desc| format!(" - {}", desc)).unwrap_or_default();
+        let note = if day.kind.excuses_target() { "" } else { " (from the flex balance)" };
+        println!(
+            "  {}: {} {:.2} hours{}{}",
+            day.date.format("%Y-%m-%d %a"),
+            day.kind.label().blue(),
+            leave::leave_hours(data, day),
+            note.dimmed(),
+            description
+        );
+    }
+    println!("  Total: {:.2} hours", leave_hours);
+    println!();
 }
 
-fn export_to_csv(entries: &[&crate::models::TimeEntry], period: &str) -> anyhow::Result<()> {
-    let filename = format!("timecard_report_{}.csv", period.replace("-", "_"));
+fn export_to_csv(
+    data: &TimeCardData,
+    entries: &[&TimeEntry],
+    range: &Period,
+    tz: &Tz,
+) -> anyhow::Result<String> {
+    let slug: String = range.name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let filename = format!("timecard_report_{}.csv", slug);
     let mut wtr = Writer::from_path(&filename)?;
     
     // Write header
-    wtr.write_record(&["Date", "Start Time", "End Time", "Duration (hours)", "Project", "Description"])?;
+    wtr.write_record([
+        "Date", "Start Time", "End Time", "Duration (hours)", "Breaks (hours)", "Project", "Client", "Description", "Tags",
+        "Billable", "Rate", "Currency", "Amount",
+    ])?;
     
     // Write data
     for entry in entries {
-        let start_time = entry.start_time.format("%Y-%m-%d %H:%M:%S").to_string();
+        let start_time = entry.start_time.with_timezone(tz).format("%Y-%m-%d %H:%M:%S").to_string();
         let end_time = entry.end_time
-            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .map(|t| t.with_timezone(tz).format("%Y-%m-%d %H:%M:%S").to_string())
             .unwrap_or_else(|| "".to_string());
         let duration = entry.hours().unwrap_or(0.0);
-        let project = entry.project.as_deref().unwrap_or("");
+        let project = data.project_name(entry.project_id).unwrap_or("");
         let description = entry.description.as_deref().unwrap_or("");
+        let tags = entry.tags.iter().cloned().collect::<Vec<_>>().join(" ");
+        let client = data.client_for_project(entry.project_id).map(|client| client.name.as_str()).unwrap_or("");
+        let (rate, currency, amount) = match billing::entry_amount(data, entry, tz) {
+            Some((amount, rate)) => (
+                format!("{:.2}", rate.hourly_rate),
+                rate.currency.as_str(),
+                format!("{:.2}", amount),
+            ),
+            None => (String::new(), "", String::new()),
+        };
         
-        wtr.write_record(&[
-            &entry.start_time.format("%Y-%m-%d").to_string(),
+        wtr.write_record([
+            entry.start_time.with_timezone(tz).format("%Y-%m-%d").to_string().as_str(),
             &start_time,
             &end_time,
             &format!("{:.2}", duration),
+            &format!("{:.2}", entry.break_duration().num_seconds() as f64 / 3600.0),
             project,
+            client,
             description,
+            &tags,
+            if entry.billable { "yes" } else { "no" },
+            &rate,
+            currency,
+            &amount,
         ])?;
     }
     
     wtr.flush()?;
-    println!("{}", format!("📄 CSV exported to: {}", filename).green());
     
-    Ok(())
+    Ok(filename)
+}
+
+/// Share of `total` in percent. With nothing counted yet, e.g. only an
+/// active entry that just started, every share is 0%.
+fn percent(hours: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        hours / total * 100.0
+    } else {
+        0.0
+    }
 }
@@ -1,10 +1,145 @@
-use crate::storage;
-use chrono::{DateTime, Duration, Utc, Datelike};
+use crate::{commands::target, output::{self, EntryJson, OutputFormat}};
+use chrono::Utc;
 use colored::*;
-use std::path::Path;
+use serde::Serialize;
+use timecard::{
+    datetime,
+    models,
+    period,
+    storage::Storage,
+    targets::{self, BudgetLevel, Progress},
+};
 
-pub fn show_status(file_path: &Path) -> anyhow::Result<()> {
-    let data = storage::load_data(file_path)?;
+#[derive(Serialize)]
+struct StatusJson {
+    clocked_in: bool,
+    paused: bool,
+    break_since: Option<String>,
+    active: Option<EntryJson>,
+    today: SummaryJson,
+    week: SummaryJson,
+    targets: TargetsJson,
+}
+
+#[derive(Serialize)]
+struct SummaryJson {
+    start: String,
+    end: String,
+    hours: f64,
+    entries: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    projects: Option<Vec<ProjectHoursJson>>,
+}
+
+#[derive(Serialize)]
+struct TargetsJson {
+    today: Option<ProgressJson>,
+    week: Option<ProgressJson>,
+    /// When today's target is met if the active entry keeps running
+    finish_at: Option<String>,
+    budgets: Vec<BudgetJson>,
+}
+
+#[derive(Serialize)]
+struct ProgressJson {
+    target: f64,
+    worked: f64,
+    remaining: f64,
+}
+
+impl From<&Progress> for ProgressJson {
+    fn from(progress: &Progress) -> Self {
+        Self {
+            target: progress.target,
+            worked: progress.worked,
+            remaining: progress.remaining(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BudgetJson {
+    project: String,
+    #[serde(flatten)]
+    progress: ProgressJson,
+    /// `within`, `warning` (80% used) or `exceeded`
+    level: &'static str,
+}
+
+#[derive(Serialize)]
+struct ProjectHoursJson {
+    project: Option<String>,
+    hours: f64,
+}
+
+pub fn show_status(store: &mut dyn Storage, output: OutputFormat) -> anyhow::Result<()> {
+    let data = store.load()?;
+    let tz = data.settings.timezone()?;
+    
+    let today = datetime::today(&tz);
+    let today_entries = data.get_entries_by_date(today, &tz);
+    let today_hours = models::sum_hours(today_entries.iter().filter_map(|entry| entry.hours()));
+    
+    let week = period::parse_period("week", &tz)?;
+    let week_entries = data.get_entries_by_period(week.start, week.end);
+    let week_hours = data.total_hours_by_period(week.start, week.end);
+    
+    // Project breakdown for today, by name
+    let mut project_hours = std::collections::BTreeMap::new();
+    for entry in &today_entries {
+        let project = data.project_name(entry.project_id);
+        *project_hours.entry(project).or_insert(0.0) += entry.hours().unwrap_or(0.0);
+    }
+    
+    let targets = targets::target_status(&data, &tz, Utc::now())?;
+    
+    if output.is_machine() {
+        let active = data.get_active_entry();
+        let today_range = period::parse_period("today", &tz)?;
+        return output.emit(&StatusJson {
+            clocked_in: active.is_some(),
+            paused: active.is_some_and(|entry| entry.is_paused()),
+            break_since: active
+                .filter(|entry| entry.is_paused())
+                .and_then(|entry| entry.pauses.last())
+                .map(|pause| output::timestamp(pause.start, &tz)),
+            active: active.map(|entry| EntryJson::new(&data, entry, &tz)),
+            today: SummaryJson {
+                start: output::timestamp(today_range.start, &tz),
+                end: output::timestamp(today_range.end, &tz),
+                hours: today_hours,
+                entries: today_entries.len(),
+                projects: Some(project_hours
+                    .into_iter()
+                    .map(|(project, hours)| ProjectHoursJson { project: project.map(str::to_string), hours })
+                    .collect()),
+            },
+            week: SummaryJson {
+                start: output::timestamp(week.start, &tz),
+                end: output::timestamp(week.end, &tz),
+                hours: week_hours,
+                entries: week_entries.len(),
+                projects: None,
+            },
+            targets: TargetsJson {
+                today: targets.today.as_ref().map(ProgressJson::from),
+                week: targets.week.as_ref().map(ProgressJson::from),
+                finish_at: targets.finish_at.map(|at| output::timestamp(at, &tz)),
+                budgets: targets.budgets
+                    .iter()
+                    .map(|budget| BudgetJson {
+                        project: budget.project.name.clone(),
+                        progress: ProgressJson::from(&budget.progress),
+                        level: match budget.level() {
+                            BudgetLevel::Within => "within",
+                            BudgetLevel::Warning => "warning",
+                            BudgetLevel::Exceeded => "exceeded",
+                        },
+                    })
+                    .collect(),
+            },
+        });
+    }
     
     println!("{}", "📊 TimeCard Status".bold());
     println!("{}", "=".repeat(30));
@@ -12,14 +147,23 @@ pub fn show_status(file_path: &Path) -> anyhow::Result<()> {
     // Check if currently clocked in
     if let Some(active_entry) = data.get_active_entry() {
         let now = Utc::now();
-        let duration = now - active_entry.start_time;
-        let hours = duration.num_seconds() as f64 / 3600.0;
+        let gross = (now - active_entry.start_time).num_seconds() as f64 / 3600.0;
+        let breaks = active_entry.break_duration_until(now).num_seconds() as f64 / 3600.0;
         
-        println!("{}", "🟢 Currently Clocked In".green().bold());
-        println!("Started: {}", active_entry.start_time.format("%Y-%m-%d %H:%M:%S"));
-        println!("Duration: {:.2} hours", hours);
+        if let Some(pause) = active_entry.pauses.last().filter(|_| active_entry.is_paused()) {
+            println!("{}", "⏸️  Clocked In, On a Break".yellow().bold());
+            println!("Break since: {}", pause.start.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"));
+        } else {
+            println!("{}", "🟢 Currently Clocked In".green().bold());
+        }
+        println!("Started: {}", active_entry.start_time.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"));
+        if active_entry.pauses.is_empty() {
+            println!("Duration: {:.2} hours", gross);
+        } else {
+            println!("Duration: {:.2} hours net ({:.2} gross, {:.2} breaks)", gross - breaks, gross, breaks);
+        }
         
-        if let Some(proj) = &active_entry.project {
+        if let Some(proj) = data.project_name(active_entry.project_id) {
             println!("Project: {}", proj.blue());
         }
         if let Some(desc) = &active_entry.description {
@@ -32,47 +176,28 @@ pub fn show_status(file_path: &Path) -> anyhow::Result<()> {
     println!();
     
     // Today's summary
-    let today = Utc::now().date_naive();
-    let today_entries = data.get_entries_by_date(today);
-    let today_hours: f64 = today_entries
-        .iter()
-        .filter_map(|entry| entry.hours())
-        .sum();
-    
     println!("{}", "📅 Today's Summary".bold());
     println!("Total Hours: {:.2}", today_hours);
     println!("Entries: {}", today_entries.len());
     
     // This week's summary
-    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
-    let week_end = week_start + Duration::days(6);
-    let week_entries = data.get_entries_by_period(
-        DateTime::from_naive_utc_and_offset(week_start.and_hms_opt(0, 0, 0).unwrap(), Utc),
-        DateTime::from_naive_utc_and_offset(week_end.and_hms_opt(23, 59, 59).unwrap(), Utc),
-    );
-    let week_hours: f64 = week_entries
-        .iter()
-        .filter_map(|entry| entry.hours())
-        .sum();
-    
     println!();
     println!("{}", "📊 This Week's Summary".bold());
     println!("Total Hours: {:.2}", week_hours);
     println!("Entries: {}", week_entries.len());
     
-    // Project breakdown for today
-    if !today_entries.is_empty() {
+    if targets.today.is_some() || targets.week.is_some() || !targets.budgets.is_empty() {
+        println!();
+        println!("{}", "🎯 Targets".bold());
+        target::print_progress(&targets, &tz);
+    }
+    
+    if !project_hours.is_empty() {
         println!();
         println!("{}", "🏷️  Today's Projects".bold());
         
-        let mut project_hours = std::collections::HashMap::new();
-        for entry in today_entries {
-            let project = entry.project.as_deref().unwrap_or("No Project");
-            *project_hours.entry(project).or_insert(0.0) += entry.hours().unwrap_or(0.0);
-        }
-        
         for (project, hours) in project_hours {
-            println!("  {}: {:.2} hours", project.blue(), hours);
+            println!("  {}: {:.2} hours", project.unwrap_or("No Project").blue(), hours);
         }
     }
     
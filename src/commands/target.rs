@@ -0,0 +1,155 @@
+use crate::output::OutputFormat;
+use chrono::{Utc, Weekday};
+use chrono_tz::Tz;
+use colored::*;
+use timecard::{
+    models::TimeCardData,
+    storage::Storage,
+    targets::{self, Budget, BudgetLevel, Progress, TargetStatus},
+    TimeCardError,
+};
+use uuid::Uuid;
+
+/// What `target set` and `target unset` apply to, as given on the command
+/// line.
+pub enum TargetScope {
+    Days(String),
+    Week,
+    Project(String),
+}
+
+const BAR_WIDTH: usize = 20;
+
+/// Set a target, or remove it when `hours` is `None`.
+pub fn set_target(store: &mut dyn Storage, scope: TargetScope, hours: Option<f64>) -> anyhow::Result<()> {
+    if let Some(hours) = hours {
+        let (valid, range) = match &scope {
+            TargetScope::Days(_) => ((0.0..=24.0).contains(&hours), "between 0 and 24"),
+            TargetScope::Week => ((0.0..=168.0).contains(&hours), "between 0 and 168"),
+            TargetScope::Project(_) => (hours.is_finite() && hours > 0.0, "more than 0"),
+        };
+        if !valid {
+            return Err(TimeCardError::InvalidInput(format!("Hours must be {}: {}", range, hours)).into());
+        }
+    }
+    
+    let what = match scope {
+        TargetScope::Days(days) => {
+            let days = targets::parse_days(&days)?;
+            let mut settings = store.load_settings()?;
+            for day in &days {
+                settings.targets.set_day(*day, hours);
+            }
+            store.save_settings(&settings)?;
+            format!("Target for {}", days.iter().map(|day| day.to_string()).collect::<Vec<_>>().join(", "))
+        }
+        TargetScope::Week => {
+            let mut settings = store.load_settings()?;
+            settings.targets.week = hours;
+            store.save_settings(&settings)?;
+            "Week target".to_string()
+        }
+        TargetScope::Project(name) => {
+            let data = store.load()?;
+            let mut project = data.find_project(&name)
+                .ok_or_else(|| TimeCardError::NotFound(format!("Unknown project: {}", name)))?
+                .clone();
+            project.budget_hours = hours;
+            project.updated_at = Utc::now();
+            store.update_project(&project)?;
+            format!("Budget of {}", project.name)
+        }
+    };
+    
+    match hours {
+        Some(hours) => println!("{}", format!("🎯 {} set to {:.2} hours", what, hours).green()),
+        None => println!("{}", format!("🎯 {} removed", what).green()),
+    }
+    
+    Ok(())
+}
+
+pub fn show_targets(store: &mut dyn Storage) -> anyhow::Result<()> {
+    let data = store.load()?;
+    let tz = data.settings.timezone()?;
+    let targets = &data.settings.targets;
+    let status = targets::target_status(&data, &tz, Utc::now())?;
+    
+    if targets.is_empty() && status.budgets.is_empty() {
+        println!("{}", "No targets set. Use `timecard target set` to add some.".yellow());
+        return Ok(());
+    }
+    
+    println!("{}", "🎯 Targets".bold());
+    println!("{}", "=".repeat(50));
+    for day in [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun] {
+        match targets.day(day) {
+            Some(hours) => println!("{}: {:.2} hours", day, hours),
+            None => println!("{}: {}", day, "none".dimmed()),
+        }
+    }
+    match (targets.week, targets.week()) {
+        (Some(hours), _) => println!("Week: {:.2} hours", hours),
+        (None, Some(hours)) => println!("Week: {:.2} hours {}", hours, "(sum of days)".dimmed()),
+        (None, None) => println!("Week: {}", "none".dimmed()),
+    }
+    
+    println!();
+    print_progress(&status, &tz);
+    
+    Ok(())
+}
+
+/// Progress bars for the targets that are set.
+pub fn print_progress(status: &TargetStatus, tz: &Tz) {
+    if let Some(today) = &status.today {
+        let finish = status.finish_at
+            .map(|at| format!(", done at {}", at.with_timezone(tz).format("%H:%M")))
+            .unwrap_or_default();
+        println!("Today:  {}{}", progress_line(today), finish);
+    }
+    if let Some(week) = &status.week {
+        println!("Week:   {}", progress_line(week));
+    }
+    for budget in &status.budgets {
+        let line = format!("{}: {}", budget.project.name, progress_line(&budget.progress));
+        match budget.level() {
+            BudgetLevel::Within => println!("{}", line),
+            BudgetLevel::Warning => println!("{}", format!("⚠️  {}", line).yellow()),
+            BudgetLevel::Exceeded => println!("{}", format!("❌ {}", line).red()),
+        }
+    }
+}
+
+/// Warn when hours just booked on a project took it past 80% of its budget,
+/// or past the budget.
+pub fn note_budget(data: &TimeCardData, project_id: Option<Uuid>, added: f64, output: OutputFormat) {
+    if let Some(budget) = targets::crossed_budget(data, project_id, added, Utc::now()) {
+        output.note(budget_warning(&budget));
+    }
+}
+
+fn budget_warning(budget: &Budget) -> ColoredString {
+    let progress = &budget.progress;
+    match budget.level() {
+        BudgetLevel::Exceeded => format!(
+            "❌ Project {} is over its budget: {:.2} of {:.2} hours",
+            budget.project.name, progress.worked, progress.target
+        ).red(),
+        _ => format!(
+            "⚠️  Project {} has used {:.0}% of its budget: {:.2} of {:.2} hours",
+            budget.project.name, progress.fraction() * 100.0, progress.worked, progress.target
+        ).yellow(),
+    }
+}
+
+fn progress_line(progress: &Progress) -> String {
+    let filled = ((progress.fraction().min(1.0) * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+    let bar = format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled));
+    let rest = if progress.worked > progress.target {
+        format!("{:.2} over", progress.worked - progress.target)
+    } else {
+        format!("{:.2} left", progress.remaining())
+    };
+    format!("{} {:.2} of {:.2} hours, {}", bar, progress.worked, progress.target, rest)
+}
@@ -0,0 +1,240 @@
+use crate::error::TimeCardError;
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Timezone reported by the operating system, falling back to UTC when it
+/// can't be determined or isn't a known IANA name.
+pub fn system_timezone() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+pub fn parse_timezone(name: &str) -> anyhow::Result<Tz> {
+    name.trim()
+        .parse()
+        .map_err(|_| {
+            TimeCardError::InvalidInput(format!("Unknown timezone: {}. Use an IANA name like Europe/Berlin", name)).into()
+        })
+}
+
+pub fn today(tz: &Tz) -> NaiveDate {
+    Utc::now().with_timezone(tz).date_naive()
+}
+
+/// Convert a wall-clock time in `tz` to UTC. Ambiguous times (DST fall-back)
+/// resolve to the earlier instant; times skipped by DST are rejected.
+pub fn local_to_utc(naive: NaiveDateTime, tz: &Tz) -> anyhow::Result<DateTime<Utc>> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => Ok(dt.with_timezone(&Utc)),
+        LocalResult::Ambiguous(earliest, _) => Ok(earliest.with_timezone(&Utc)),
+        LocalResult::None => Err(TimeCardError::InvalidDatetime(format!(
+            "{} does not exist in timezone {}",
+            naive.format("%Y-%m-%d %H:%M"),
+            tz.name()
+        )).into()),
+    }
+}
+
+/// First instant of a local calendar day, in UTC.
+pub fn day_start(date: NaiveDate, tz: &Tz) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    // A DST jump at midnight means the day starts at the first valid minute
+    (0..=180)
+        .find_map(|minutes| local_to_utc(midnight + Duration::minutes(minutes), tz).ok())
+        .unwrap_or_else(|| DateTime::from_naive_utc_and_offset(midnight, Utc))
+}
+
+/// Inclusive UTC bounds covering the local days `first..=last`.
+pub fn day_range(first: NaiveDate, last: NaiveDate, tz: &Tz) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = day_start(first, tz);
+    let end = day_start(last + Duration::days(1), tz) - Duration::nanoseconds(1);
+    (start, end)
+}
+
+/// Parse a span like `15m`, `1h30m` or `20 minutes`. `None` means the input
+/// isn't a span; spans too long to represent are an error.
+fn parse_span(input: &str) -> Option<anyhow::Result<Duration>> {
+    let mut chars = input.trim().chars().peekable();
+    let mut total = Duration::zero();
+    let mut parts = 0;
+    
+    while chars.peek().is_some() {
+        let number: String = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_digit())).collect();
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let unit: String = std::iter::from_fn(|| chars.next_if(|c| c.is_alphabetic())).collect();
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        
+        if number.is_empty() {
+            return None;
+        }
+        let span: fn(i64) -> Option<Duration> = match unit.to_lowercase().as_str() {
+            "s" | "sec" | "secs" | "second" | "seconds" => Duration::try_seconds,
+            "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes,
+            "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours,
+            "d" | "day" | "days" => Duration::try_days,
+            _ => return None,
+        };
+        total = match number.parse().ok().and_then(span).and_then(|span| total.checked_add(&span)) {
+            Some(total) => total,
+            None => return Some(Err(span_too_long(input))),
+        };
+        parts += 1;
+    }
+    
+    (parts > 0).then_some(Ok(total))
+}
+
+fn span_too_long(input: &str) -> anyhow::Error {
+    TimeCardError::InvalidDatetime(format!("Time span is too long: {}", input.trim())).into()
+}
+
+/// `now` moved back by a span from [`parse_span`].
+fn before_now(now: DateTime<Utc>, span: anyhow::Result<Duration>, input: &str) -> anyhow::Result<DateTime<Utc>> {
+    now.checked_sub_signed(span?).ok_or_else(|| span_too_long(input))
+}
+
+/// Times relative to now: `now`, `-15m`, `20 minutes ago`, and `today`,
+/// `yesterday` or `tomorrow` with an optional time, e.g. `yesterday 17:30`.
+fn parse_relative(input: &str, tz: &Tz) -> Option<anyhow::Result<DateTime<Utc>>> {
+    let lower = input.to_lowercase();
+    let now = Utc::now();
+    
+    if lower == "now" {
+        return Some(Ok(now));
+    }
+    if let Some(span) = lower.strip_prefix('-') {
+        return parse_span(span).map(|result| before_now(now, result, span));
+    }
+    if let Some(span) = lower.strip_suffix("ago") {
+        return parse_span(span).map(|result| before_now(now, result, span));
+    }
+    
+    let (day, time) = lower.split_once(char::is_whitespace).unwrap_or((&lower, ""));
+    let date = match day {
+        "today" => today(tz),
+        "yesterday" => today(tz) - Duration::days(1),
+        "tomorrow" => today(tz) + Duration::days(1),
+        _ => return None,
+    };
+    let time = time.trim();
+    if time.is_empty() {
+        return Some(Ok(day_start(date, tz)));
+    }
+    let parsed = ["%H:%M:%S", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(time, format).ok());
+    Some(match parsed {
+        Some(time) => local_to_utc(date.and_time(time), tz),
+        None => Err(TimeCardError::InvalidDatetime(format!("Invalid time after {}: {}", day, time)).into()),
+    })
+}
+
+/// Parse user-entered date/time text as wall-clock time in `tz`.
+pub fn parse_datetime(datetime_str: &str, tz: &Tz) -> anyhow::Result<DateTime<Utc>> {
+    let input = datetime_str.trim();
+    
+    if let Some(result) = parse_relative(input, tz) {
+        return result;
+    }
+    
+    // Explicit offsets are taken as-is
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+    
+    let datetime_formats = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ];
+    for format in &datetime_formats {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(input, format) {
+            return local_to_utc(datetime, tz);
+        }
+    }
+    
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(day_start(date, tz));
+    }
+    
+    // If only time is provided, assume today's date
+    for format in &["%H:%M:%S", "%H:%M"] {
+        if let Ok(time) = NaiveTime::parse_from_str(input, format) {
+            return local_to_utc(today(tz).and_time(time), tz);
+        }
+    }
+    
+    Err(TimeCardError::InvalidDatetime(format!(
+        "Invalid datetime format: {}. Use formats like: 2024-01-15 14:30, 14:30, 2024-01-15, -15m, \"20 minutes ago\", \"yesterday 17:30\"",
+        datetime_str
+    )).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn is_invalid_datetime(error: anyhow::Error) -> bool {
+        matches!(error.downcast_ref(), Some(TimeCardError::InvalidDatetime(_)))
+    }
+    
+    #[test]
+    fn spans() {
+        assert_eq!(parse_span("15m").unwrap().unwrap(), Duration::minutes(15));
+        assert_eq!(parse_span("1h30m").unwrap().unwrap(), Duration::minutes(90));
+        assert_eq!(parse_span(" 20 minutes ").unwrap().unwrap(), Duration::minutes(20));
+        assert_eq!(parse_span("1 day, 2 hours").unwrap().unwrap(), Duration::hours(26));
+        assert!(parse_span("").is_none());
+        assert!(parse_span("h").is_none());
+        assert!(parse_span("15 parsecs").is_none());
+    }
+    
+    #[test]
+    fn spans_too_long_are_errors() {
+        for input in ["200000000000d", "99999999999999999999s", "9223372036854775h", "100000000000d 100000000000d"] {
+            let result = parse_span(input).expect(input);
+            assert!(is_invalid_datetime(result.unwrap_err()), "{}", input);
+        }
+    }
+    
+    #[test]
+    fn relative_times() {
+        let before = Utc::now();
+        let parsed = parse_relative("-15m", &Tz::UTC).unwrap().unwrap();
+        assert!(parsed <= before - Duration::minutes(15) + Duration::seconds(5));
+        assert!(parsed >= before - Duration::minutes(15) - Duration::seconds(5));
+        
+        let parsed = parse_relative("2 hours ago", &Tz::UTC).unwrap().unwrap();
+        assert!((before - Duration::hours(2) - parsed).num_seconds().abs() < 5);
+        
+        let yesterday = today(&Tz::UTC) - Duration::days(1);
+        let parsed = parse_relative("yesterday 17:30", &Tz::UTC).unwrap().unwrap();
+        assert_eq!(parsed.naive_utc(), yesterday.and_hms_opt(17, 30, 0).unwrap());
+        
+        assert!(parse_relative("2025-09-15", &Tz::UTC).is_none());
+        assert!(is_invalid_datetime(parse_relative("today 25:00", &Tz::UTC).unwrap().unwrap_err()));
+    }
+    
+    #[test]
+    fn relative_times_too_far_back_are_errors() {
+        for input in ["-1000000000d", "100000000000 days ago", "-99999999999999999999m"] {
+            let result = parse_relative(input, &Tz::UTC).expect(input);
+            assert!(is_invalid_datetime(result.unwrap_err()), "{}", input);
+        }
+        assert!(is_invalid_datetime(parse_datetime("-1000000000d", &Tz::UTC).unwrap_err()));
+    }
+    
+    #[test]
+    fn local_times_around_dst() {
+        let berlin: Tz = "Europe/Berlin".parse().unwrap();
+        let skipped = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap().and_hms_opt(2, 30, 0).unwrap();
+        assert!(is_invalid_datetime(local_to_utc(skipped, &berlin).unwrap_err()));
+        
+        let repeated = NaiveDate::from_ymd_opt(2025, 10, 26).unwrap().and_hms_opt(2, 30, 0).unwrap();
+        let utc = local_to_utc(repeated, &berlin).unwrap();
+        assert_eq!(utc.naive_utc(), NaiveDate::from_ymd_opt(2025, 10, 26).unwrap().and_hms_opt(0, 30, 0).unwrap());
+    }
+}
@@ -0,0 +1,495 @@
+use crate::models::{TimeCardData, TimeEntry};
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Entries longer than this are reported as implausible.
+pub const DEFAULT_MAX_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FixStrategy {
+    /// Cut the earlier of two overlapping entries short, and clip breaks
+    /// that stick out of their entry
+    Trim,
+    /// Like trim, but an entry that fully contains another keeps its
+    /// remainder as a new entry after it
+    Split,
+    /// Close stray active entries, at --at or when the next entry starts
+    Close,
+    /// Unassign entries from projects that no longer exist
+    Detach,
+    /// Drop exact copies of an entry and give differing entries that share an id a fresh one
+    Dedupe,
+}
+
+/// A consistency problem. Entries are referred to by their position in
+/// `time_entries`, since duplicate ids can't tell them apart.
+#[derive(Debug, Clone)]
+pub enum Issue {
+    DuplicateId { id: Uuid, count: usize },
+    MultipleActive { entries: Vec<usize> },
+    InvalidDuration { entry: usize },
+    LongEntry { entry: usize, hours: f64, active: bool },
+    Overlap { earlier: usize, later: usize },
+    OrphanProject { entry: usize, project: Uuid },
+    OrphanClient { project: Uuid, client: Uuid },
+    BadPauses { entry: usize },
+}
+
+/// Scan the data for problems, oldest entries first.
+pub fn diagnose(data: &TimeCardData, now: DateTime<Utc>, max_hours: i64) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let entries = &data.time_entries;
+    
+    let mut id_counts: HashMap<Uuid, usize> = HashMap::new();
+    for entry in entries {
+        *id_counts.entry(entry.id).or_insert(0) += 1;
+    }
+    let mut duplicates: Vec<_> = id_counts.iter().filter(|(_, count)| **count > 1).collect();
+    duplicates.sort();
+    for (id, count) in duplicates {
+        issues.push(Issue::DuplicateId { id: *id, count: *count });
+    }
+    
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by_key(|&i| entries[i].start_time);
+    
+    let active: Vec<usize> = order.iter().copied().filter(|&i| entries[i].is_active()).collect();
+    if active.len() > 1 {
+        issues.push(Issue::MultipleActive { entries: active });
+    }
+    
+    for &i in &order {
+        let entry = &entries[i];
+        let end = entry.end_time.unwrap_or(now);
+        if end <= entry.start_time {
+            issues.push(Issue::InvalidDuration { entry: i });
+        } else if Duration::try_hours(max_hours).is_some_and(|max| end - entry.start_time > max) {
+            let hours = (end - entry.start_time).num_seconds() as f64 / 3600.0;
+            issues.push(Issue::LongEntry { entry: i, hours, active: entry.is_active() });
+        }
+        if entry.check_pauses().is_err() || entry.pauses.iter().any(|p| p.end.is_some_and(|end| end < p.start)) {
+            issues.push(Issue::BadPauses { entry: i });
+        }
+        if let Some(project) = entry.project_id.filter(|id| data.get_project(*id).is_none()) {
+            issues.push(Issue::OrphanProject { entry: i, project });
+        }
+    }
+    
+    for project in &data.projects {
+        if let Some(client) = project.client_id.filter(|id| data.get_client(*id).is_none()) {
+            issues.push(Issue::OrphanClient { project: project.id, client });
+        }
+    }
+    
+    // Sweep by start time, comparing each entry with the one reaching
+    // furthest so far. Copies sharing an id are left to dedupe first.
+    let mut furthest: Option<usize> = None;
+    for &i in &order {
+        let entry = &entries[i];
+        let end = entry.end_time.unwrap_or(now);
+        if end <= entry.start_time || id_counts[&entry.id] > 1 {
+            continue;
+        }
+        if let Some(f) = furthest {
+            if entry.start_time < entries[f].end_time.unwrap_or(now) {
+                issues.push(Issue::Overlap { earlier: f, later: i });
+            }
+        }
+        if furthest.is_none_or(|f| end > entries[f].end_time.unwrap_or(now)) {
+            furthest = Some(i);
+        }
+    }
+    
+    issues
+}
+
+/// Entries that would overlap a new entry from `start` to `end`.
+pub fn overlapping(data: &TimeCardData, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<&TimeEntry> {
+    let now = Utc::now();
+    data.time_entries
+        .iter()
+        .filter(|entry| entry.start_time < end && entry.end_time.unwrap_or(now) > start)
+        .collect()
+}
+
+pub fn describe_entry(data: &TimeCardData, entry: &TimeEntry, tz: &Tz) -> String {
+    let end = match entry.end_time {
+        Some(end) => end.with_timezone(tz).format("%Y-%m-%d %H:%M").to_string(),
+        None => "active".to_string(),
+    };
+    format!(
+        "{} {} → {} {}",
+        entry.short_id(),
+        entry.start_time.with_timezone(tz).format("%Y-%m-%d %H:%M"),
+        end,
+        data.project_name(entry.project_id).unwrap_or("No Project")
+    )
+}
+
+impl Issue {
+    pub fn describe(&self, data: &TimeCardData, tz: &Tz) -> String {
+        let entry = |i: &usize| describe_entry(data, &data.time_entries[*i], tz);
+        match self {
+            Issue::DuplicateId { id, count } => format!("{} entries share the id {}", count, id),
+            Issue::MultipleActive { entries } => format!(
+                "{} entries are active at once: {}",
+                entries.len(),
+                entries.iter().map(entry).collect::<Vec<_>>().join("; ")
+            ),
+            Issue::InvalidDuration { entry: i } => format!("Entry ends before it starts: {}", entry(i)),
+            Issue::LongEntry { entry: i, hours, .. } => format!("Entry lasts {:.1} hours: {}", hours, entry(i)),
+            Issue::Overlap { earlier, later } => format!("Entries overlap: {} and {}", entry(earlier), entry(later)),
+            Issue::OrphanProject { entry: i, project } => {
+                format!("Entry refers to missing project {}: {}", project, entry(i))
+            }
+            Issue::OrphanClient { project, client } => format!(
+                "Project {} refers to missing client {}",
+                data.project_name(Some(*project)).unwrap_or("?"),
+                client
+            ),
+            Issue::BadPauses { entry: i } => format!("Entry has breaks outside its time: {}", entry(i)),
+        }
+    }
+    
+    /// The strategy that repairs this kind of issue, if any.
+    pub fn strategy(&self) -> Option<FixStrategy> {
+        match self {
+            Issue::DuplicateId { .. } => Some(FixStrategy::Dedupe),
+            Issue::MultipleActive { .. } | Issue::LongEntry { active: true, .. } => Some(FixStrategy::Close),
+            Issue::Overlap { .. } | Issue::BadPauses { .. } => Some(FixStrategy::Trim),
+            Issue::OrphanProject { .. } | Issue::OrphanClient { .. } => Some(FixStrategy::Detach),
+            Issue::InvalidDuration { .. } | Issue::LongEntry { .. } => None,
+        }
+    }
+}
+
+/// Another entry that entry `i` would overlap if it ended at `end`.
+fn overlap_when_closed(data: &TimeCardData, i: usize, end: DateTime<Utc>) -> Option<&TimeEntry> {
+    let entry = &data.time_entries[i];
+    overlapping(data, entry.start_time, end).into_iter().find(|other| other.id != entry.id)
+}
+
+/// Cut an entry short at `end`, dropping or clipping breaks past it.
+fn truncate(entry: &mut TimeEntry, end: DateTime<Utc>) {
+    entry.pauses.retain(|pause| pause.start < end);
+    for pause in &mut entry.pauses {
+        pause.end = Some(pause.end.unwrap_or(end).min(end));
+    }
+    entry.end_time = Some(end);
+    entry.updated_at = Utc::now();
+}
+
+/// Apply the chosen strategies, returning a line for each change and each
+/// issue that had to be skipped.
+pub fn fix(
+    data: &mut TimeCardData,
+    strategies: &[FixStrategy],
+    close_at: Option<DateTime<Utc>>,
+    max_hours: i64,
+    tz: &Tz,
+) -> Vec<String> {
+    let mut log = Vec::new();
+    let now = Utc::now();
+    let wants = |strategy| strategies.contains(&strategy);
+    
+    if wants(FixStrategy::Dedupe) {
+        // Exact copies are dropped; differing entries keep their data
+        let mut seen: HashMap<Uuid, Vec<serde_json::Value>> = HashMap::new();
+        let mut kept = Vec::new();
+        for mut entry in std::mem::take(&mut data.time_entries) {
+            let value = serde_json::to_value(&entry).unwrap_or_default();
+            let copies = seen.entry(entry.id).or_default();
+            if copies.contains(&value) {
+                log.push(format!("Removed an exact copy of {}", entry.short_id()));
+                continue;
+            }
+            copies.push(value);
+            if copies.len() > 1 {
+                let old = entry.id;
+                entry.id = Uuid::new_v4();
+                log.push(format!("Gave a duplicate of {} the new id {}", old, entry.short_id()));
+            }
+            kept.push(entry);
+        }
+        data.time_entries = kept;
+    }
+    
+    if wants(FixStrategy::Detach) {
+        for i in 0..data.time_entries.len() {
+            let project = data.time_entries[i].project_id;
+            if let Some(project) = project.filter(|id| data.get_project(*id).is_none()) {
+                data.time_entries[i].project_id = None;
+                data.time_entries[i].updated_at = now;
+                log.push(format!("Unassigned {} from missing project {}", data.time_entries[i].short_id(), project));
+            }
+        }
+        let client_ids: Vec<_> = data.clients.iter().map(|client| client.id).collect();
+        for project in &mut data.projects {
+            if project.client_id.is_some_and(|id| !client_ids.contains(&id)) {
+                project.client_id = None;
+                project.updated_at = now;
+                log.push(format!("Unassigned project {} from its missing client", project.name));
+            }
+        }
+    }
+    
+    // Entries that can't be fixed, so the overlap loop doesn't retry them
+    let mut skipped = HashSet::new();
+    let skip = |log: &mut Vec<String>, skipped: &mut HashSet<Uuid>, data: &TimeCardData, i: usize, reason: &str| {
+        if skipped.insert(data.time_entries[i].id) {
+            log.push(format!("Skipped {}: {}", describe_entry(data, &data.time_entries[i], tz), reason));
+        }
+    };
+    
+    if wants(FixStrategy::Close) {
+        for issue in diagnose(data, now, max_hours) {
+            match issue {
+                Issue::MultipleActive { entries } => {
+                    // Keep the newest running; each older one ends at --at or
+                    // where the following entry starts
+                    for pair in entries.windows(2) {
+                        let (i, next) = (pair[0], pair[1]);
+                        let end = close_at.unwrap_or(data.time_entries[next].start_time);
+                        if end <= data.time_entries[i].start_time {
+                            skip(&mut log, &mut skipped, data, i, "the close time is before it starts");
+                            continue;
+                        }
+                        if let Some(other) = overlap_when_closed(data, i, end) {
+                            let reason = format!("closing it then would overlap {}", other.short_id());
+                            skip(&mut log, &mut skipped, data, i, &reason);
+                            continue;
+                        }
+                        truncate(&mut data.time_entries[i], end);
+                        log.push(format!("Closed {}", describe_entry(data, &data.time_entries[i], tz)));
+                    }
+                }
+                Issue::LongEntry { entry: i, .. } if data.time_entries[i].is_active() => match close_at {
+                    Some(end) if end > data.time_entries[i].start_time => {
+                        if let Some(other) = overlap_when_closed(data, i, end) {
+                            let reason = format!("closing it then would overlap {}", other.short_id());
+                            skip(&mut log, &mut skipped, data, i, &reason);
+                            continue;
+                        }
+                        truncate(&mut data.time_entries[i], end);
+                        log.push(format!("Closed {}", describe_entry(data, &data.time_entries[i], tz)));
+                    }
+                    Some(_) => skip(&mut log, &mut skipped, data, i, "the close time is before it starts"),
+                    None => skip(&mut log, &mut skipped, data, i, "use --at to choose when it ended"),
+                },
+                _ => {}
+            }
+        }
+    }
+    
+    if wants(FixStrategy::Trim) || wants(FixStrategy::Split) {
+        for issue in diagnose(data, now, max_hours) {
+            if let Issue::BadPauses { entry: i } = issue {
+                if data.time_entries[i].invoice_id.is_some() {
+                    skip(&mut log, &mut skipped, data, i, "it is invoiced");
+                    continue;
+                }
+                let entry = &mut data.time_entries[i];
+                let (start, end) = (entry.start_time, entry.end_time);
+                entry.pauses.retain_mut(|pause| {
+                    pause.start = pause.start.max(start);
+                    if let (Some(pause_end), Some(end)) = (&mut pause.end, end) {
+                        *pause_end = (*pause_end).min(end);
+                    }
+                    end.is_none_or(|end| pause.start < end) && pause.end.is_none_or(|e| e > pause.start)
+                });
+                entry.updated_at = now;
+                log.push(format!("Clipped breaks of {}", describe_entry(data, &data.time_entries[i], tz)));
+            }
+        }
+        
+        // Fixing one overlap can uncover the next, so rescan after each
+        let mut unfixable = HashSet::new();
+        loop {
+            let overlap = diagnose(data, now, max_hours).into_iter().find_map(|issue| match issue {
+                Issue::Overlap { earlier, later } => {
+                    let pair = (data.time_entries[earlier].id, data.time_entries[later].id);
+                    (!unfixable.contains(&pair)).then_some((earlier, later, pair))
+                }
+                _ => None,
+            });
+            let Some((earlier, later, pair)) = overlap else {
+                break;
+            };
+            
+            // Every pass either shortens an entry or gives up on this pair
+            unfixable.insert(pair);
+            if data.time_entries[earlier].invoice_id.is_some() {
+                skip(&mut log, &mut skipped, data, earlier, "it is invoiced");
+                continue;
+            }
+            let cut = data.time_entries[later].start_time;
+            let later_end = data.time_entries[later].end_time.unwrap_or(now);
+            let earlier_end = data.time_entries[earlier].end_time;
+            if cut <= data.time_entries[earlier].start_time {
+                skip(&mut log, &mut skipped, data, earlier, "both entries start at the same time");
+                continue;
+            }
+            
+            let contains = earlier_end.is_none_or(|end| end > later_end);
+            if contains && wants(FixStrategy::Split) && data.time_entries[later].end_time.is_some() {
+                let mut tail = data.time_entries[earlier].clone();
+                tail.id = Uuid::new_v4();
+                tail.start_time = later_end;
+                tail.pauses.retain(|pause| pause.end.is_none_or(|end| end > later_end));
+                for pause in &mut tail.pauses {
+                    pause.start = pause.start.max(later_end);
+                }
+                tail.created_at = now;
+                tail.updated_at = now;
+                truncate(&mut data.time_entries[earlier], cut);
+                log.push(format!(
+                    "Split {} around {}",
+                    describe_entry(data, &data.time_entries[earlier], tz),
+                    data.time_entries[later].short_id()
+                ));
+                data.time_entries.push(tail);
+            } else {
+                truncate(&mut data.time_entries[earlier], cut);
+                log.push(format!("Trimmed {}", describe_entry(data, &data.time_entries[earlier], tz)));
+            }
+        }
+    }
+    
+    if !log.is_empty() {
+        data.updated_at = now;
+    }
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 9, 15, hour, minute, 0).unwrap()
+    }
+    
+    fn add_entry(data: &mut TimeCardData, start: DateTime<Utc>, end: Option<DateTime<Utc>>) -> Uuid {
+        let mut entry = TimeEntry::new(None, None);
+        entry.start_time = start;
+        entry.end_time = end;
+        let id = entry.id;
+        data.add_time_entry(entry);
+        id
+    }
+    
+    fn times(data: &TimeCardData) -> Vec<(DateTime<Utc>, Option<DateTime<Utc>>)> {
+        let mut times: Vec<_> = data.time_entries.iter().map(|entry| (entry.start_time, entry.end_time)).collect();
+        times.sort();
+        times
+    }
+    
+    fn run(data: &mut TimeCardData, strategy: FixStrategy, close_at: Option<DateTime<Utc>>) -> Vec<String> {
+        fix(data, &[strategy], close_at, DEFAULT_MAX_HOURS, &Tz::UTC)
+    }
+    
+    #[test]
+    fn close_ends_older_active_entries_where_the_next_starts() {
+        let mut data = TimeCardData::default();
+        add_entry(&mut data, at(9, 0), None);
+        add_entry(&mut data, at(11, 0), None);
+        
+        let log = run(&mut data, FixStrategy::Close, None);
+        
+        assert!(log[0].starts_with("Closed"), "{:?}", log);
+        assert_eq!(times(&data), [(at(9, 0), Some(at(11, 0))), (at(11, 0), None)]);
+    }
+    
+    #[test]
+    fn close_skips_entries_that_would_overlap_another() {
+        let mut data = TimeCardData::default();
+        add_entry(&mut data, at(9, 0), None);
+        add_entry(&mut data, at(10, 0), Some(at(10, 30)));
+        add_entry(&mut data, at(11, 0), None);
+        
+        let log = run(&mut data, FixStrategy::Close, None);
+        
+        assert!(log[0].starts_with("Skipped"), "{:?}", log);
+        assert_eq!(data.time_entries.iter().filter(|entry| entry.is_active()).count(), 2);
+    }
+    
+    #[test]
+    fn close_needs_a_time_for_a_long_active_entry() {
+        let mut data = TimeCardData::default();
+        add_entry(&mut data, at(9, 0), None);
+        
+        let log = run(&mut data, FixStrategy::Close, None);
+        assert!(log[0].contains("use --at"), "{:?}", log);
+        assert!(data.time_entries[0].is_active());
+        
+        let log = run(&mut data, FixStrategy::Close, Some(at(8, 0)));
+        assert!(log[0].contains("before it starts"), "{:?}", log);
+        
+        run(&mut data, FixStrategy::Close, Some(at(17, 0)));
+        assert_eq!(data.time_entries[0].end_time, Some(at(17, 0)));
+    }
+    
+    #[test]
+    fn split_keeps_the_remainder_after_a_contained_entry() {
+        let mut data = TimeCardData::default();
+        let outer = add_entry(&mut data, at(9, 0), Some(at(17, 0)));
+        add_entry(&mut data, at(12, 0), Some(at(13, 0)));
+        
+        let log = run(&mut data, FixStrategy::Split, None);
+        
+        assert_eq!(log.len(), 1);
+        assert_eq!(times(&data), [
+            (at(9, 0), Some(at(12, 0))),
+            (at(12, 0), Some(at(13, 0))),
+            (at(13, 0), Some(at(17, 0))),
+        ]);
+        let tail = data.time_entries.last().unwrap();
+        assert_ne!(tail.id, outer);
+        assert!(diagnose(&data, Utc::now(), DEFAULT_MAX_HOURS).is_empty());
+    }
+    
+    #[test]
+    fn split_moves_breaks_into_the_remainder() {
+        let mut data = TimeCardData::default();
+        add_entry(&mut data, at(9, 0), Some(at(17, 0)));
+        add_entry(&mut data, at(12, 0), Some(at(13, 0)));
+        data.time_entries[0].pauses = vec![
+            crate::models::Pause { start: at(10, 0), end: Some(at(10, 15)) },
+            crate::models::Pause { start: at(15, 0), end: Some(at(15, 30)) },
+        ];
+        
+        run(&mut data, FixStrategy::Split, None);
+        
+        let pauses = |i: usize| data.time_entries[i].pauses.iter().map(|pause| pause.start).collect::<Vec<_>>();
+        assert_eq!(pauses(0), [at(10, 0)]);
+        assert_eq!(pauses(2), [at(15, 0)]);
+    }
+    
+    #[test]
+    fn trim_cuts_the_earlier_entry_short() {
+        let mut data = TimeCardData::default();
+        add_entry(&mut data, at(9, 0), Some(at(17, 0)));
+        add_entry(&mut data, at(12, 0), Some(at(13, 0)));
+        
+        run(&mut data, FixStrategy::Trim, None);
+        
+        assert_eq!(times(&data), [(at(9, 0), Some(at(12, 0))), (at(12, 0), Some(at(13, 0)))]);
+    }
+    
+    #[test]
+    fn invoiced_entries_are_not_changed() {
+        let mut data = TimeCardData::default();
+        add_entry(&mut data, at(9, 0), Some(at(17, 0)));
+        add_entry(&mut data, at(12, 0), Some(at(13, 0)));
+        data.time_entries[0].invoice_id = Some(Uuid::new_v4());
+        
+        let log = run(&mut data, FixStrategy::Split, None);
+        
+        assert!(log[0].contains("it is invoiced"), "{:?}", log);
+        assert_eq!(data.time_entries.len(), 2);
+        assert_eq!(data.time_entries[0].end_time, Some(at(17, 0)));
+    }
+}
@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+/// Failures callers may want to tell apart. Functions still return
+/// `anyhow::Result`; find the kind of an error with
+/// [`TimeCardError::of`].
+#[derive(Debug, thiserror::Error)]
+pub enum TimeCardError {
+    /// Clocking in while an entry is active
+    #[error("Already clocked in since {since}")]
+    AlreadyClockedIn { since: String },
+    /// Clocking out, switching or pausing with no active entry
+    #[error("Not clocked in")]
+    NotClockedIn,
+    #[error("Already paused")]
+    AlreadyPaused,
+    #[error("Not paused")]
+    NotPaused,
+    #[error("Invalid period: {0}")]
+    InvalidPeriod(String),
+    /// Unreadable times, times in the future and end times before start times
+    #[error("{0}")]
+    InvalidDatetime(String),
+    /// A change that would make entries overlap
+    #[error("{0}")]
+    Overlap(String),
+    /// No entry, project, client, rate, invoice, leave or backup by that name or id
+    #[error("{0}")]
+    NotFound(String),
+    /// Any other input that can't be accepted
+    #[error("{0}")]
+    InvalidInput(String),
+    /// Changing an entry billed on an invoice
+    #[error("Entry is billed on invoice {number}. Void the invoice first to change it.")]
+    Invoiced { number: String },
+    #[error("Data file is locked by another timecard process: {}", .0.display())]
+    Locked(PathBuf),
+    #[error("Data file {} is corrupt: {reason}", .path.display())]
+    StorageCorrupt { path: PathBuf, reason: String },
+    /// A data file written by a newer timecard
+    #[error(
+        "{} was written by a newer timecard (schema version {version}, this build supports up to {supported}). Please upgrade timecard.",
+        .path.display()
+    )]
+    UnsupportedSchema { path: PathBuf, version: u32, supported: u32 },
+}
+
+impl TimeCardError {
+    /// The `TimeCardError` an error is or wraps, if any.
+    pub fn of(error: &anyhow::Error) -> Option<&TimeCardError> {
+        error.chain().find_map(|cause| cause.downcast_ref())
+    }
+    
+    /// Process exit code for the command line. 1 is left for other errors
+    /// and 2 for usage errors.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            TimeCardError::AlreadyClockedIn { .. } => 3,
+            TimeCardError::NotClockedIn => 4,
+            TimeCardError::AlreadyPaused => 5,
+            TimeCardError::NotPaused => 6,
+            TimeCardError::InvalidPeriod(_) => 7,
+            TimeCardError::InvalidDatetime(_) => 8,
+            TimeCardError::Overlap(_) => 9,
+            TimeCardError::NotFound(_) => 10,
+            TimeCardError::InvalidInput(_) => 11,
+            TimeCardError::Invoiced { .. } => 12,
+            TimeCardError::Locked(_) => 13,
+            TimeCardError::StorageCorrupt { .. } => 14,
+            TimeCardError::UnsupportedSchema { .. } => 15,
+        }
+    }
+}
@@ -0,0 +1,621 @@
+use super::entries::EntryAction;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
+use chrono_tz::Tz;
+use eframe::egui::{self, Align2, Color32, CursorIcon, FontId, Pos2, Rect, Sense, Stroke, Vec2};
+use timecard::{
+    datetime,
+    models::{self, TimeCardData, TimeEntry},
+    period::week_start,
+    tracker::{EntryChanges, NewEntry},
+};
+use uuid::Uuid;
+
+const HOUR_HEIGHT: f32 = 48.0;
+const GUTTER_WIDTH: f32 = 48.0;
+const HEADER_HEIGHT: f32 = 20.0;
+/// Height of the grab zones at the top and bottom of a block
+const EDGE: f32 = 6.0;
+/// Dragged times snap to this many minutes
+const DRAG_SNAP: i64 = 5;
+/// Clicks on empty space start an entry at a multiple of this many minutes
+const CREATE_SNAP: i64 = 15;
+/// The timeline opens scrolled to this hour
+const FIRST_HOUR: f32 = 7.0;
+/// Daily hours that get the darkest colour in the month view, unless a day
+/// had more
+const FULL_DAY_HOURS: f64 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grip {
+    Start,
+    End,
+    Body,
+}
+
+/// A block being dragged, with the times it had when the drag began.
+struct Drag {
+    id: Uuid,
+    grip: Grip,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    /// Where on the timeline the block was grabbed
+    grabbed_at: DateTime<Utc>,
+    grabbed_pos: Pos2,
+    /// Whether the pointer moved since, so a mere click changes nothing
+    moved: bool,
+    /// The times the block would get if dropped now
+    preview: (DateTime<Utc>, DateTime<Utc>),
+}
+
+/// An entry to be created from a click on empty space, as text.
+struct Draft {
+    start: String,
+    end: String,
+    project: String,
+    description: String,
+}
+
+/// The hours of the visible days.
+struct Timeline {
+    rect: Rect,
+    days: Vec<NaiveDate>,
+    column_width: f32,
+    tz: Tz,
+    now: DateTime<Utc>,
+}
+
+impl Timeline {
+    fn column(&self, index: usize) -> Rect {
+        let left = self.rect.left() + GUTTER_WIDTH + index as f32 * self.column_width;
+        Rect::from_min_size(Pos2::new(left, self.rect.top()), Vec2::new(self.column_width, self.rect.height()))
+    }
+    
+    fn day_bounds(&self, index: usize) -> (DateTime<Utc>, DateTime<Utc>) {
+        let date = self.days[index];
+        (datetime::day_start(date, &self.tz), datetime::day_start(date + Duration::days(1), &self.tz))
+    }
+    
+    /// Height of a time in a day column. DST days are drawn squeezed or
+    /// stretched to the same height.
+    fn y_of(&self, index: usize, time: DateTime<Utc>) -> f32 {
+        let (start, end) = self.day_bounds(index);
+        let fraction = (time - start).num_seconds() as f32 / (end - start).num_seconds() as f32;
+        self.rect.top() + fraction.clamp(0.0, 1.0) * self.rect.height()
+    }
+    
+    fn time_at(&self, pos: Pos2) -> DateTime<Utc> {
+        let index = ((pos.x - self.rect.left() - GUTTER_WIDTH) / self.column_width).floor();
+        let index = (index.max(0.0) as usize).min(self.days.len() - 1);
+        let (start, end) = self.day_bounds(index);
+        let fraction = ((pos.y - self.rect.top()) / self.rect.height()).clamp(0.0, 1.0) as f64;
+        start + Duration::seconds(((end - start).num_seconds() as f64 * fraction) as i64)
+    }
+    
+    fn draw_grid(&self, ui: &egui::Ui) {
+        let painter = ui.painter();
+        let visuals = ui.visuals();
+        let line = Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color);
+        let rect = self.rect;
+        
+        for hour in 0..24 {
+            let y = rect.top() + hour as f32 * HOUR_HEIGHT;
+            painter.hline(rect.left() + GUTTER_WIDTH..=rect.right(), y, line);
+            painter.text(
+                Pos2::new(rect.left() + GUTTER_WIDTH - 6.0, y + 2.0),
+                Align2::RIGHT_TOP,
+                format!("{:02}:00", hour),
+                FontId::proportional(11.0),
+                visuals.weak_text_color(),
+            );
+        }
+        for index in 0..=self.days.len() {
+            let x = rect.left() + GUTTER_WIDTH + index as f32 * self.column_width;
+            painter.vline(x, rect.y_range(), line);
+        }
+        
+        if let Some(index) = self.days.iter().position(|date| *date == datetime::today(&self.tz)) {
+            let column = self.column(index);
+            painter.hline(column.x_range(), self.y_of(index, self.now), Stroke::new(2.0, Color32::RED));
+        }
+    }
+    
+    fn draw_block(&self, ui: &egui::Ui, index: usize, block: Rect, entry: &TimeEntry, name: &str, dragged: bool) {
+        let locked = entry.invoice_id.is_some();
+        let painter = ui.painter().with_clip_rect(block.intersect(ui.clip_rect()));
+        painter.rect_filled(block, 3.0, project_color(entry.project_id, locked));
+        if dragged {
+            painter.rect_stroke(block, 3.0, Stroke::new(2.0, ui.visuals().strong_text_color()));
+        } else {
+            // Breaks are shaded, except while the block is being moved
+            let (day_start, day_end) = self.day_bounds(index);
+            for pause in &entry.pauses {
+                let pause_end = pause.end.unwrap_or(self.now);
+                if pause_end <= day_start || pause.start >= day_end {
+                    continue;
+                }
+                let shade = Rect::from_x_y_ranges(
+                    block.x_range(),
+                    self.y_of(index, pause.start)..=self.y_of(index, pause_end),
+                );
+                painter.rect_filled(shade, 0.0, Color32::from_black_alpha(60));
+            }
+        }
+        
+        let mut label = name.to_string();
+        if let Some(desc) = &entry.description {
+            label.push('\n');
+            label.push_str(desc);
+        }
+        label.push_str(&format!("\n{:.2}h", entry.net_duration_until(self.now).num_seconds() as f64 / 3600.0));
+        if locked {
+            label.push_str(" 🔒");
+        }
+        painter.text(block.left_top() + Vec2::new(4.0, 2.0), Align2::LEFT_TOP, label, FontId::proportional(11.0), Color32::BLACK);
+    }
+}
+
+/// Day and week timelines with entries as blocks that can be moved,
+/// resized and created with the mouse, and a month heatmap of daily hours.
+pub struct Calendar {
+    mode: Mode,
+    /// A day in the shown range
+    anchor: Option<NaiveDate>,
+    drag: Option<Drag>,
+    draft: Option<Draft>,
+    scroll_to_morning: bool,
+}
+
+impl Calendar {
+    pub fn new() -> Self {
+        Self {
+            mode: Mode::Week,
+            anchor: None,
+            drag: None,
+            draft: None,
+            scroll_to_morning: true,
+        }
+    }
+    
+    pub fn show(&mut self, ui: &mut egui::Ui, data: &TimeCardData, tz: &Tz, undo: Option<&str>) -> Option<EntryAction> {
+        let mut action = None;
+        let today = datetime::today(tz);
+        let anchor = *self.anchor.get_or_insert(today);
+        
+        ui.horizontal(|ui| {
+            let mut mode = self.mode;
+            ui.selectable_value(&mut mode, Mode::Day, "Day");
+            ui.selectable_value(&mut mode, Mode::Week, "Week");
+            ui.selectable_value(&mut mode, Mode::Month, "Month");
+            ui.separator();
+            let mut anchor = anchor;
+            if ui.button("◀").clicked() {
+                anchor = self.step(anchor, -1);
+            }
+            if ui.button("Today").clicked() {
+                anchor = today;
+            }
+            if ui.button("▶").clicked() {
+                anchor = self.step(anchor, 1);
+            }
+            if mode != self.mode || anchor != self.anchor.unwrap_or(today) {
+                self.set_range(mode, anchor);
+            }
+            
+            let days = self.days();
+            let hours = days.iter().map(|date| day_hours(data, *date, tz)).sum::<f64>();
+            ui.strong(self.range_label());
+            ui.label(format!("{:.2} hours", hours));
+            if let Some(label) = undo {
+                if ui.button(format!("↶ Undo {}", label)).clicked() {
+                    action = Some(EntryAction::Undo);
+                }
+            }
+        });
+        
+        if let Some(added) = self.show_draft(ui, tz) {
+            action = Some(added);
+        }
+        ui.separator();
+        
+        let changed = match self.mode {
+            Mode::Month => {
+                self.show_month(ui, data, tz);
+                None
+            }
+            Mode::Day | Mode::Week => self.show_timeline(ui, data, tz),
+        };
+        changed.or(action)
+    }
+    
+    fn step(&self, anchor: NaiveDate, delta: i32) -> NaiveDate {
+        match self.mode {
+            Mode::Day => anchor + Duration::days(delta as i64),
+            Mode::Week => anchor + Duration::days(7 * delta as i64),
+            Mode::Month if delta < 0 => anchor.checked_sub_months(Months::new(1)).unwrap_or(anchor),
+            Mode::Month => anchor.checked_add_months(Months::new(1)).unwrap_or(anchor),
+        }
+    }
+    
+    fn set_range(&mut self, mode: Mode, anchor: NaiveDate) {
+        self.mode = mode;
+        self.anchor = Some(anchor);
+        self.drag = None;
+        self.scroll_to_morning = true;
+    }
+    
+    fn anchor(&self) -> NaiveDate {
+        self.anchor.unwrap_or_default()
+    }
+    
+    /// Days of the timeline, or of the month.
+    fn days(&self) -> Vec<NaiveDate> {
+        let anchor = self.anchor();
+        match self.mode {
+            Mode::Day => vec![anchor],
+            Mode::Week => {
+                let monday = week_start(anchor);
+                (0..7).map(|day| monday + Duration::days(day)).collect()
+            }
+            Mode::Month => anchor.with_day(1).unwrap_or(anchor).iter_days().take_while(|date| date.month() == anchor.month()).collect(),
+        }
+    }
+    
+    fn range_label(&self) -> String {
+        let anchor = self.anchor();
+        match self.mode {
+            Mode::Day => anchor.format("%A %Y-%m-%d").to_string(),
+            Mode::Week => {
+                let monday = week_start(anchor);
+                format!("{} to {}", monday.format("%Y-%m-%d"), (monday + Duration::days(6)).format("%Y-%m-%d"))
+            }
+            Mode::Month => anchor.format("%B %Y").to_string(),
+        }
+    }
+    
+    /// The form for an entry started by clicking empty space.
+    fn show_draft(&mut self, ui: &mut egui::Ui, tz: &Tz) -> Option<EntryAction> {
+        let draft = self.draft.as_mut()?;
+        let mut action = None;
+        let mut close = false;
+        
+        ui.horizontal(|ui| {
+            ui.label("New entry from");
+            ui.add(egui::TextEdit::singleline(&mut draft.start).desired_width(120.0));
+            ui.label("to");
+            ui.add(egui::TextEdit::singleline(&mut draft.end).desired_width(120.0));
+            ui.add(egui::TextEdit::singleline(&mut draft.project).hint_text("Project").desired_width(120.0));
+            ui.add(egui::TextEdit::singleline(&mut draft.description).hint_text("Description (+tag to tag)"));
+            
+            let times = datetime::parse_datetime(&draft.start, tz)
+                .and_then(|start| Ok((start, datetime::parse_datetime(&draft.end, tz)?)));
+            match times {
+                Ok((start, end)) if end > start => {
+                    if ui.button("Add").clicked() {
+                        action = Some(EntryAction::Add(NewEntry {
+                            activity: super::activity(&draft.project, &draft.description),
+                            start,
+                            end,
+                        }));
+                        close = true;
+                    }
+                }
+                Ok(_) => {
+                    ui.colored_label(Color32::RED, "End time must be after start time");
+                }
+                Err(e) => {
+                    ui.colored_label(Color32::RED, e.to_string());
+                }
+            }
+            close |= ui.button("Cancel").clicked();
+        });
+        if close {
+            self.draft = None;
+        }
+        action
+    }
+    
+    fn show_timeline(&mut self, ui: &mut egui::Ui, data: &TimeCardData, tz: &Tz) -> Option<EntryAction> {
+        let days = self.days();
+        let now = Utc::now();
+        
+        // Day names and totals stay put while the hours scroll
+        let width = ui.available_width() - ui.spacing().scroll.allocated_width();
+        let column_width = (width - GUTTER_WIDTH) / days.len() as f32;
+        let (header, _) = ui.allocate_exact_size(Vec2::new(width, HEADER_HEIGHT), Sense::hover());
+        for (index, date) in days.iter().enumerate() {
+            let center = Pos2::new(header.left() + GUTTER_WIDTH + (index as f32 + 0.5) * column_width, header.center().y);
+            let color = if *date == datetime::today(tz) { ui.visuals().strong_text_color() } else { ui.visuals().text_color() };
+            ui.painter().text(
+                center,
+                Align2::CENTER_CENTER,
+                format!("{} {:.2}h", date.format("%a %d"), day_hours(data, *date, tz)),
+                FontId::proportional(13.0),
+                color,
+            );
+        }
+        
+        let mut scroll = egui::ScrollArea::vertical()
+            .id_source("calendar_timeline")
+            .drag_to_scroll(false)
+            .auto_shrink([false, false]);
+        if std::mem::take(&mut self.scroll_to_morning) {
+            scroll = scroll.vertical_scroll_offset(FIRST_HOUR * HOUR_HEIGHT);
+        }
+        scroll.show(ui, |ui| {
+            let (rect, _) = ui.allocate_exact_size(Vec2::new(width, 24.0 * HOUR_HEIGHT), Sense::hover());
+            let timeline = Timeline { rect, days, column_width, tz: *tz, now };
+            timeline.draw_grid(ui);
+            
+            let released = self.update_drag(ui, &timeline);
+            for entry in &data.time_entries {
+                self.show_entry(ui, &timeline, data, entry);
+            }
+            self.draw_draft(ui, &timeline);
+            
+            // Registered last, so clicks on blocks don't reach it
+            let background = ui.interact(rect, ui.id().with("calendar_background"), Sense::click());
+            if let Some(pos) = background.clicked().then(|| background.interact_pointer_pos()).flatten() {
+                let start = snap(timeline.time_at(pos), CREATE_SNAP, false);
+                let local = |time: DateTime<Utc>| time.with_timezone(tz).format("%Y-%m-%d %H:%M").to_string();
+                let (project, description) = self.draft.take().map(|d| (d.project, d.description)).unwrap_or_default();
+                self.draft = Some(Draft {
+                    start: local(start),
+                    end: local(start + Duration::hours(1)),
+                    project,
+                    description,
+                });
+            }
+            released
+        })
+        .inner
+    }
+    
+    /// Follow the pointer while a block is dragged. Dropping it asks for the
+    /// new times; the tracker checks them like an `edit` would.
+    fn update_drag(&mut self, ui: &egui::Ui, timeline: &Timeline) -> Option<EntryAction> {
+        let drag = self.drag.as_mut()?;
+        let (pointer, down) = ui.input(|input| (input.pointer.interact_pos(), input.pointer.primary_down()));
+        
+        if let Some(pos) = pointer.filter(|pos| drag.moved || pos.distance(drag.grabbed_pos) > 3.0) {
+            drag.moved = true;
+            let at = timeline.time_at(pos);
+            let min = Duration::minutes(DRAG_SNAP);
+            drag.preview = match drag.grip {
+                Grip::Start => (snap(at, DRAG_SNAP, true).min(drag.end - min), drag.end),
+                Grip::End => (drag.start, snap(at, DRAG_SNAP, true).max(drag.start + min)),
+                Grip::Body => {
+                    let start = snap(drag.start + (at - drag.grabbed_at), DRAG_SNAP, true);
+                    (start, start + (drag.end - drag.start))
+                }
+            };
+        }
+        if down {
+            ui.ctx().set_cursor_icon(match drag.grip {
+                Grip::Body => CursorIcon::Grabbing,
+                _ => CursorIcon::ResizeVertical,
+            });
+            return None;
+        }
+        
+        let drag = self.drag.take()?;
+        let (start, end) = drag.preview;
+        // Moving takes the breaks along, so they stay within the entry
+        let changes = match drag.grip {
+            Grip::Body => EntryChanges {
+                shift: (start != drag.start).then(|| start - drag.start),
+                ..EntryChanges::default()
+            },
+            _ => EntryChanges {
+                start: (start != drag.start).then_some(start),
+                end: (end != drag.end && drag.grip != Grip::Start).then_some(end),
+                ..EntryChanges::default()
+            },
+        };
+        (!changes.is_empty()).then_some(EntryAction::Edit { id: drag.id, changes })
+    }
+    
+    /// Draw an entry in every visible day it touches, and let finished
+    /// entries be moved and resized and the active one's start be dragged.
+    fn show_entry(&mut self, ui: &egui::Ui, timeline: &Timeline, data: &TimeCardData, entry: &TimeEntry) {
+        let preview = self.drag.as_ref().filter(|drag| drag.id == entry.id).map(|drag| drag.preview);
+        let (start, end) = preview.unwrap_or((entry.start_time, entry.end_time.unwrap_or(timeline.now)));
+        let locked = entry.invoice_id.is_some();
+        let name = data.project_name(entry.project_id).unwrap_or("No Project");
+        
+        for index in 0..timeline.days.len() {
+            let (day_start, day_end) = timeline.day_bounds(index);
+            if end <= day_start || start >= day_end {
+                continue;
+            }
+            let column = timeline.column(index).shrink2(Vec2::new(3.0, 0.0));
+            let top = timeline.y_of(index, start.max(day_start));
+            let bottom = timeline.y_of(index, end.min(day_end)).max(top + 4.0);
+            let block = Rect::from_x_y_ranges(column.x_range(), top..=bottom);
+            timeline.draw_block(ui, index, block, entry, name, preview.is_some());
+            
+            // Grips come first so they win over the body they lie on. Only
+            // the segment holding an end can be resized at that end.
+            let id = ui.id().with(("calendar_entry", entry.id, index));
+            let mut grips = Vec::new();
+            if !locked && start >= day_start {
+                grips.push((Grip::Start, Rect::from_x_y_ranges(block.x_range(), top..=(top + EDGE).min(bottom))));
+            }
+            if !locked && entry.end_time.is_some() && end <= day_end {
+                grips.push((Grip::End, Rect::from_x_y_ranges(block.x_range(), (bottom - EDGE).max(top)..=bottom)));
+            }
+            for (grip, rect) in grips {
+                let response = ui.interact(rect, id.with(grip as u8), Sense::drag()).on_hover_cursor(CursorIcon::ResizeVertical);
+                if response.drag_started() {
+                    self.start_drag(entry, grip, timeline, response.interact_pointer_pos());
+                }
+            }
+            
+            let movable = !locked && entry.end_time.is_some();
+            let sense = if movable { Sense::click_and_drag() } else { Sense::click() };
+            let mut body = ui.interact(block, id, sense).on_hover_text(entry_tooltip(entry, name, &timeline.tz, timeline.now));
+            if movable {
+                body = body.on_hover_cursor(CursorIcon::Grab);
+            }
+            if body.drag_started() && self.drag.is_none() {
+                self.start_drag(entry, Grip::Body, timeline, body.interact_pointer_pos());
+            }
+        }
+    }
+    
+    fn start_drag(&mut self, entry: &TimeEntry, grip: Grip, timeline: &Timeline, pointer: Option<Pos2>) {
+        let Some(pos) = pointer else {
+            return;
+        };
+        let (start, end) = (entry.start_time, entry.end_time.unwrap_or(timeline.now));
+        self.drag = Some(Drag {
+            id: entry.id,
+            grip,
+            start,
+            end,
+            grabbed_at: timeline.time_at(pos),
+            grabbed_pos: pos,
+            moved: false,
+            preview: (start, end),
+        });
+    }
+    
+    /// Outline where the entry being created will go.
+    fn draw_draft(&self, ui: &egui::Ui, timeline: &Timeline) {
+        let Some(draft) = &self.draft else {
+            return;
+        };
+        let (Ok(start), Ok(end)) = (datetime::parse_datetime(&draft.start, &timeline.tz), datetime::parse_datetime(&draft.end, &timeline.tz)) else {
+            return;
+        };
+        for index in 0..timeline.days.len() {
+            let (day_start, day_end) = timeline.day_bounds(index);
+            if end <= start || end <= day_start || start >= day_end {
+                continue;
+            }
+            let column = timeline.column(index).shrink2(Vec2::new(3.0, 0.0));
+            let block = Rect::from_x_y_ranges(
+                column.x_range(),
+                timeline.y_of(index, start.max(day_start))..=timeline.y_of(index, end.min(day_end)),
+            );
+            ui.painter().rect_stroke(block, 3.0, Stroke::new(2.0, ui.visuals().selection.stroke.color));
+        }
+    }
+    
+    /// Daily hours of the month as a grid of weeks, darker for longer days.
+    /// Clicking a day opens it in the day view.
+    fn show_month(&mut self, ui: &mut egui::Ui, data: &TimeCardData, tz: &Tz) {
+        let days = self.days();
+        let hours: Vec<f64> = days.iter().map(|date| day_hours(data, *date, tz)).collect();
+        let full = hours.iter().copied().fold(FULL_DAY_HOURS, f64::max);
+        let today = datetime::today(tz);
+        
+        let width = ui.available_width() / 7.0;
+        let height = (width * 0.6).clamp(40.0, 90.0);
+        let (rect, _) = ui.allocate_exact_size(Vec2::new(ui.available_width(), HEADER_HEIGHT + 6.0 * height), Sense::hover());
+        for (index, name) in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"].iter().enumerate() {
+            ui.painter().text(
+                Pos2::new(rect.left() + (index as f32 + 0.5) * width, rect.top() + HEADER_HEIGHT / 2.0),
+                Align2::CENTER_CENTER,
+                name,
+                FontId::proportional(13.0),
+                ui.visuals().text_color(),
+            );
+        }
+        
+        let offset = days[0].weekday().num_days_from_monday() as usize;
+        for (index, (date, hours)) in days.iter().zip(&hours).enumerate() {
+            let cell = offset + index;
+            let min = Pos2::new(rect.left() + (cell % 7) as f32 * width, rect.top() + HEADER_HEIGHT + (cell / 7) as f32 * height);
+            let cell_rect = Rect::from_min_size(min, Vec2::new(width, height)).shrink(2.0);
+            
+            let intensity = (*hours / full) as f32;
+            let fill = if *hours > 0.0 {
+                Color32::from_rgb(
+                    lerp(198, 33, intensity),
+                    lerp(228, 110, intensity),
+                    lerp(139, 57, intensity),
+                )
+            } else {
+                ui.visuals().faint_bg_color
+            };
+            let text = if intensity > 0.5 { Color32::WHITE } else { Color32::BLACK };
+            ui.painter().rect_filled(cell_rect, 3.0, fill);
+            if *date == today {
+                ui.painter().rect_stroke(cell_rect, 3.0, Stroke::new(2.0, ui.visuals().strong_text_color()));
+            }
+            ui.painter().text(cell_rect.left_top() + Vec2::new(4.0, 2.0), Align2::LEFT_TOP, date.day().to_string(), FontId::proportional(12.0), text);
+            if *hours > 0.0 {
+                ui.painter().text(cell_rect.center(), Align2::CENTER_CENTER, format!("{:.2}h", hours), FontId::proportional(14.0), text);
+            }
+            
+            let response = ui.interact(cell_rect, ui.id().with(("calendar_day", *date)), Sense::click())
+                .on_hover_text(format!("{}: {:.2} hours", date.format("%A %Y-%m-%d"), hours));
+            if response.clicked() {
+                self.set_range(Mode::Day, *date);
+            }
+        }
+    }
+}
+
+/// Net hours of the entries started on a local day, counting the active one
+/// up to now.
+fn day_hours(data: &TimeCardData, date: NaiveDate, tz: &Tz) -> f64 {
+    let now = Utc::now();
+    models::sum_hours(
+        data.get_entries_by_date(date, tz)
+            .iter()
+            .map(|entry| entry.net_duration_until(now).num_seconds() as f64 / 3600.0),
+    )
+}
+
+/// Round a time to a multiple of `minutes`, to the nearest one or down.
+fn snap(time: DateTime<Utc>, minutes: i64, nearest: bool) -> DateTime<Utc> {
+    let step = minutes * 60;
+    let seconds = time.timestamp();
+    let snapped = if nearest { (seconds + step / 2).div_euclid(step) } else { seconds.div_euclid(step) } * step;
+    DateTime::from_timestamp(snapped, 0).unwrap_or(time)
+}
+
+/// A steady colour per project, gray without one or once invoiced.
+pub(super) fn project_color(project_id: Option<Uuid>, locked: bool) -> Color32 {
+    match project_id {
+        Some(id) if !locked => {
+            let hue = (id.as_u128() % 360) as f32 / 360.0;
+            egui::ecolor::Hsva::new(hue, 0.35, 0.9, 1.0).into()
+        }
+        _ => Color32::from_gray(190),
+    }
+}
+
+fn lerp(from: u8, to: u8, t: f32) -> u8 {
+    (from as f32 + (to as f32 - from as f32) * t.clamp(0.0, 1.0)).round() as u8
+}
+
+fn entry_tooltip(entry: &TimeEntry, project: &str, tz: &Tz, now: DateTime<Utc>) -> String {
+    let end = match entry.end_time {
+        Some(end) => end.with_timezone(tz).format("%H:%M").to_string(),
+        None => "now".to_string(),
+    };
+    let mut tooltip = format!(
+        "{} {} to {}\n{}",
+        entry.short_id(),
+        entry.start_time.with_timezone(tz).format("%Y-%m-%d %H:%M"),
+        end,
+        project,
+    );
+    if let Some(desc) = &entry.description {
+        tooltip.push_str(&format!("\n{}", desc));
+    }
+    if !entry.tags.is_empty() {
+        tooltip.push_str(&format!("\n{}", entry.format_tags()));
+    }
+    tooltip.push_str(&format!("\n{:.2} hours", entry.net_duration_until(now).num_seconds() as f64 / 3600.0));
+    tooltip
+}
@@ -0,0 +1,409 @@
+use super::{calendar::project_color, PERIOD_PRESETS};
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use csv::Writer;
+use eframe::egui::{self, Color32, Pos2, Sense, Shape, Stroke, Vec2};
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints};
+use std::f32::consts::TAU;
+use timecard::{
+    datetime,
+    models::TimeCardData,
+    period::{self, Period},
+    report::Report,
+    targets,
+};
+
+const PLOT_HEIGHT: f32 = 220.0;
+const DONUT_SIZE: f32 = 200.0;
+
+/// What the charts show, worked out from a [`Report`] over the period.
+struct ChartData {
+    /// Every day from the first entry, or the start of the period, to its end
+    days: Vec<NaiveDate>,
+    /// Per project, most hours first: name, colour and hours per day
+    projects: Vec<(String, Color32, Vec<f64>)>,
+    /// Hours per tag, most first. Entries with several tags count towards
+    /// each of them.
+    tags: Vec<(String, f64)>,
+    /// Hours worked up to each day, until today
+    cumulative: Vec<f64>,
+    /// Hours targeted up to each day, if any day of the week has a target
+    target: Option<Vec<f64>>,
+}
+
+impl ChartData {
+    fn new(data: &TimeCardData, range: &Period, tz: &Tz) -> Self {
+        let report = Report::new(data, data.get_entries_by_period(range.start, range.end), tz);
+        let first_day = report.days.keys().next().map_or(range.last_day, |day| (*day).max(range.first_day));
+        let days: Vec<_> = first_day.iter_days().take_while(|day| *day <= range.last_day).collect();
+        
+        let mut projects: Vec<_> = report.projects
+            .iter()
+            .map(|(name, total)| {
+                let id = name.and_then(|name| data.find_project(name)).map(|project| project.id);
+                let per_day = days
+                    .iter()
+                    .map(|day| report.days.get(day).and_then(|hours| hours.get(name)).copied().unwrap_or(0.0))
+                    .collect();
+                (name.unwrap_or("No Project").to_string(), project_color(id, false), per_day, total.hours)
+            })
+            .collect();
+        projects.sort_by(|a, b| b.3.total_cmp(&a.3).then_with(|| a.0.cmp(&b.0)));
+        
+        let mut tags: Vec<_> = report.tags.iter().map(|(tag, hours)| (tag.to_string(), *hours)).collect();
+        if report.untagged_hours > 0.0 {
+            tags.push(("(untagged)".to_string(), report.untagged_hours));
+        }
+        tags.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        
+        let today = datetime::today(tz);
+        let mut cumulative = Vec::new();
+        let mut target = Vec::new();
+        let (mut worked, mut targeted) = (0.0, 0.0);
+        for day in &days {
+            targeted += targets::day_target(data, *day);
+            target.push(targeted);
+            if *day <= today {
+                worked += report.days.get(day).map_or(0.0, |hours| hours.values().sum());
+                cumulative.push(worked);
+            }
+        }
+        
+        Self {
+            days,
+            projects: projects.into_iter().map(|(name, color, per_day, _)| (name, color, per_day)).collect(),
+            tags,
+            cumulative,
+            target: data.settings.targets.days.iter().any(Option::is_some).then_some(target),
+        }
+    }
+    
+    fn total_hours(&self) -> f64 {
+        self.projects.iter().flat_map(|(_, _, per_day)| per_day).sum()
+    }
+    
+    /// Write every chart's numbers to one CSV file, one row per value.
+    fn export(&self, range: &Period) -> anyhow::Result<String> {
+        let slug: String = range.name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let filename = format!("timecard_charts_{}.csv", slug);
+        let mut wtr = Writer::from_path(&filename)?;
+        
+        wtr.write_record(["Chart", "Label", "Series", "Hours"])?;
+        for (name, _, per_day) in &self.projects {
+            for (day, hours) in self.days.iter().zip(per_day).filter(|(_, hours)| **hours > 0.0) {
+                wtr.write_record(["Hours per day", &day.format("%Y-%m-%d").to_string(), name, &format!("{:.2}", hours)])?;
+            }
+        }
+        for (name, _, per_day) in &self.projects {
+            wtr.write_record(["Projects", name, "", &format!("{:.2}", per_day.iter().sum::<f64>())])?;
+        }
+        for (tag, hours) in &self.tags {
+            wtr.write_record(["Tags", tag, "", &format!("{:.2}", hours)])?;
+        }
+        for (index, day) in self.days.iter().enumerate() {
+            let day = day.format("%Y-%m-%d").to_string();
+            if let Some(worked) = self.cumulative.get(index) {
+                wtr.write_record(["Cumulative", &day, "Worked", &format!("{:.2}", worked)])?;
+            }
+            if let Some(target) = &self.target {
+                wtr.write_record(["Cumulative", &day, "Target", &format!("{:.2}", target[index])])?;
+            }
+        }
+        wtr.flush()?;
+        
+        Ok(filename)
+    }
+}
+
+/// Charts of a period: hours per day stacked by project, the share of each
+/// project, hours per tag and hours worked against the day targets.
+pub struct Charts {
+    /// Period expression, used unless a range is given
+    pub period: String,
+    from: String,
+    to: String,
+    /// Where the chart data was last exported to, or why it couldn't be
+    exported: Option<Result<String, String>>,
+}
+
+impl Charts {
+    pub fn new() -> Self {
+        Self {
+            period: "month".to_string(),
+            from: String::new(),
+            to: String::new(),
+            exported: None,
+        }
+    }
+    
+    /// The period picked, read like `report --period` or `--from`/`--to`.
+    fn range(&self, tz: &Tz) -> anyhow::Result<Period> {
+        let from = Some(self.from.trim()).filter(|from| !from.is_empty());
+        let to = Some(self.to.trim()).filter(|to| !to.is_empty());
+        if from.is_some() || to.is_some() {
+            period::parse_range(from, to, tz)
+        } else {
+            period::parse_period(&self.period, tz)
+        }
+    }
+    
+    pub fn show(&mut self, ui: &mut egui::Ui, data: &TimeCardData, tz: &Tz) {
+        ui.horizontal(|ui| {
+            ui.label("Period:");
+            egui::ComboBox::from_id_source("chart_period")
+                .selected_text(&self.period)
+                .show_ui(ui, |ui| {
+                    for (value, label) in PERIOD_PRESETS {
+                        ui.selectable_value(&mut self.period, value.to_string(), *label);
+                    }
+                });
+            ui.text_edit_singleline(&mut self.period)
+                .on_hover_text(period::PERIOD_HELP);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Or from:");
+            ui.add(egui::TextEdit::singleline(&mut self.from).desired_width(100.0));
+            ui.label("to:");
+            ui.add(egui::TextEdit::singleline(&mut self.to).desired_width(100.0))
+                .on_hover_text("Leave both empty to use the period; either side accepts a period expression");
+        });
+        
+        let range = match self.range(tz) {
+            Ok(range) => range,
+            Err(e) => {
+                ui.colored_label(Color32::RED, e.to_string());
+                return;
+            }
+        };
+        let charts = ChartData::new(data, &range, tz);
+        
+        ui.horizontal(|ui| {
+            ui.strong(&range.name);
+            ui.label(format!("{:.2} hours", charts.total_hours()));
+            if ui.button("📄 Export CSV").clicked() {
+                self.exported = Some(charts.export(&range).map_err(|e| format!("{:#}", e)));
+            }
+            match &self.exported {
+                Some(Ok(filename)) => {
+                    ui.label(format!("Exported to: {}", filename));
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(Color32::RED, e);
+                }
+                None => {}
+            }
+        });
+        ui.separator();
+        
+        if charts.total_hours() <= 0.0 {
+            ui.label("No finished entries in this period.");
+            return;
+        }
+        egui::ScrollArea::vertical().id_source("charts").show(ui, |ui| {
+            ui.strong("Hours per day");
+            show_daily(ui, &charts);
+            ui.add_space(10.0);
+            
+            ui.columns(2, |columns| {
+                columns[0].strong("Projects");
+                show_projects(&mut columns[0], &charts);
+                columns[1].strong("Tags");
+                show_tags(&mut columns[1], &charts);
+            });
+            ui.add_space(10.0);
+            
+            ui.strong("Cumulative hours");
+            show_cumulative(ui, &charts);
+        });
+    }
+}
+
+/// Label of the day at a plot position, if it is one.
+fn day_label(days: &[NaiveDate], x: f64) -> String {
+    let index = x.round();
+    if (x - index).abs() > 0.01 || index < 0.0 {
+        return String::new();
+    }
+    days.get(index as usize).map(|day| day.format("%m-%d").to_string()).unwrap_or_default()
+}
+
+fn show_daily(ui: &mut egui::Ui, charts: &ChartData) {
+    let mut stack: Vec<BarChart> = Vec::new();
+    for (name, color, per_day) in &charts.projects {
+        let bars = per_day.iter().enumerate().map(|(index, hours)| Bar::new(index as f64, *hours)).collect();
+        let (name_for_bar, days) = (name.clone(), charts.days.clone());
+        let chart = BarChart::new(bars)
+            .name(name)
+            .color(*color)
+            .width(0.7)
+            .element_formatter(Box::new(move |bar, _| {
+                let day = days.get(bar.argument as usize).map(|day| day.format("%a %Y-%m-%d").to_string()).unwrap_or_default();
+                format!("{}\n{}: {:.2}h", day, name_for_bar, bar.value)
+            }))
+            .stack_on(&stack.iter().collect::<Vec<_>>());
+        stack.push(chart);
+    }
+    
+    let days = charts.days.clone();
+    Plot::new("chart_daily")
+        .height(PLOT_HEIGHT)
+        .legend(Legend::default())
+        .allow_scroll(false)
+        .include_y(0.0)
+        .x_axis_formatter(move |x, _, _| day_label(&days, x))
+        .y_axis_formatter(|y, _, _| format!("{}h", y))
+        .show(ui, |plot_ui| {
+            for chart in stack {
+                plot_ui.bar_chart(chart);
+            }
+        });
+}
+
+/// A donut of each project's share, with a legend beside it.
+fn show_projects(ui: &mut egui::Ui, charts: &ChartData) {
+    let totals: Vec<_> = charts.projects
+        .iter()
+        .map(|(name, color, per_day)| (name, *color, per_day.iter().sum::<f64>()))
+        .collect();
+    let total: f64 = totals.iter().map(|(_, _, hours)| hours).sum();
+    
+    ui.horizontal(|ui| {
+        let (rect, response) = ui.allocate_exact_size(Vec2::splat(DONUT_SIZE), Sense::hover());
+        let center = rect.center();
+        let outer = DONUT_SIZE / 2.0 - 4.0;
+        let thickness = outer * 0.45;
+        let radius = outer - thickness / 2.0;
+        
+        // Slices start at the top and go clockwise
+        let angle_of = |fraction: f64| fraction as f32 * TAU - TAU / 4.0;
+        let mut start = 0.0;
+        let mut slices = Vec::new();
+        for (name, color, hours) in &totals {
+            let end = start + hours / total;
+            let segments = ((end - start) * 96.0).ceil().max(2.0) as usize;
+            let points: Vec<Pos2> = (0..=segments)
+                .map(|step| {
+                    let angle = angle_of(start + (end - start) * step as f64 / segments as f64);
+                    center + Vec2::angled(angle) * radius
+                })
+                .collect();
+            ui.painter().add(Shape::line(points, Stroke::new(thickness, *color)));
+            slices.push((start, end, name, hours));
+            start = end;
+        }
+        ui.painter().text(
+            center,
+            egui::Align2::CENTER_CENTER,
+            format!("{:.1}h", total),
+            egui::FontId::proportional(16.0),
+            ui.visuals().strong_text_color(),
+        );
+        
+        if let Some(pos) = response.hover_pos() {
+            let offset = pos - center;
+            if (offset.length() - radius).abs() <= thickness / 2.0 {
+                let fraction = ((offset.angle() + TAU / 4.0).rem_euclid(TAU) / TAU) as f64;
+                if let Some((_, _, name, hours)) = slices.iter().find(|(start, end, _, _)| fraction >= *start && fraction < *end) {
+                    egui::show_tooltip_at_pointer(ui.ctx(), ui.id().with("donut"), |ui| {
+                        ui.label(format!("{}: {:.2}h ({:.0}%)", name, hours, *hours / total * 100.0));
+                    });
+                }
+            }
+        }
+        
+        ui.vertical(|ui| {
+            for (name, color, hours) in &totals {
+                ui.horizontal(|ui| {
+                    let (swatch, _) = ui.allocate_exact_size(Vec2::splat(10.0), Sense::hover());
+                    ui.painter().rect_filled(swatch, 2.0, *color);
+                    ui.label(format!("{} {:.2}h ({:.0}%)", name, hours, hours / total * 100.0));
+                });
+            }
+        });
+    });
+}
+
+fn show_tags(ui: &mut egui::Ui, charts: &ChartData) {
+    if charts.tags.is_empty() {
+        ui.label("No tags.");
+        return;
+    }
+    // Most hours at the top
+    let count = charts.tags.len();
+    let bars = charts.tags
+        .iter()
+        .enumerate()
+        .map(|(index, (tag, hours))| Bar::new((count - 1 - index) as f64, *hours).name(tag))
+        .collect();
+    let tags: Vec<String> = charts.tags.iter().map(|(tag, _)| tag.clone()).collect();
+    Plot::new("chart_tags")
+        .height(DONUT_SIZE)
+        .allow_scroll(false)
+        .include_x(0.0)
+        .x_axis_formatter(|x, _, _| format!("{}h", x))
+        .y_axis_formatter(move |y, _, _| {
+            let index = y.round();
+            if (y - index).abs() > 0.01 || index < 0.0 || index as usize >= tags.len() {
+                return String::new();
+            }
+            tags[tags.len() - 1 - index as usize].clone()
+        })
+        .y_axis_width(6)
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(
+                BarChart::new(bars)
+                    .horizontal()
+                    .width(0.6)
+                    .element_formatter(Box::new(|bar, _| format!("{}: {:.2}h", bar.name, bar.value))),
+            );
+        });
+}
+
+fn show_cumulative(ui: &mut egui::Ui, charts: &ChartData) {
+    let worked: PlotPoints = charts.cumulative.iter().enumerate().map(|(index, hours)| [index as f64, *hours]).collect();
+    let days = charts.days.clone();
+    let label_days = charts.days.clone();
+    Plot::new("chart_cumulative")
+        .height(PLOT_HEIGHT)
+        .legend(Legend::default())
+        .allow_scroll(false)
+        .include_y(0.0)
+        .x_axis_formatter(move |x, _, _| day_label(&days, x))
+        .y_axis_formatter(|y, _, _| format!("{}h", y))
+        .label_formatter(move |name, point| {
+            let day = label_days
+                .get(point.x.round().max(0.0) as usize)
+                .map(|day| day.format("%a %Y-%m-%d").to_string())
+                .unwrap_or_default();
+            match name {
+                "" => day,
+                name => format!("{}\n{}: {:.2}h", day, name, point.y),
+            }
+        })
+        .show(ui, |plot_ui| {
+            if let Some(target) = &charts.target {
+                let target: PlotPoints = target.iter().enumerate().map(|(index, hours)| [index as f64, *hours]).collect();
+                plot_ui.line(Line::new(target).name("Target").style(egui_plot::LineStyle::dashed_loose()).color(Color32::GRAY));
+            }
+            plot_ui.line(Line::new(worked).name("Worked").width(2.0));
+        });
+    
+    let Some(target) = &charts.target else {
+        ui.label("Set day targets with `timecard target set` to compare against them.");
+        return;
+    };
+    let Some(last) = charts.cumulative.len().checked_sub(1) else {
+        return;
+    };
+    let behind = target[last] - charts.cumulative[last];
+    let day = charts.days[last].format("%Y-%m-%d");
+    if behind > 0.0 {
+        ui.label(format!("{:.2} hours behind target as of {}", behind, day));
+    } else {
+        ui.label(format!("{:.2} hours ahead of target as of {}", -behind, day));
+    }
+}
@@ -0,0 +1,535 @@
+use super::PERIOD_PRESETS;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use timecard::{
+    datetime,
+    models::{self, TagFilter, TimeCardData, TimeEntry},
+    period,
+    tracker::{EntryChanges, NewEntry},
+};
+use uuid::Uuid;
+
+/// A change asked for in the entry table or calendar. The app carries it out
+/// under the data file lock and hands back an [`UndoStep`].
+pub enum EntryAction {
+    Add(NewEntry),
+    Edit { id: Uuid, changes: EntryChanges },
+    Delete(Vec<Uuid>),
+    /// Move entries to a project, or to none
+    SetProject { ids: Vec<Uuid>, project: Option<String> },
+    Merge(Vec<Uuid>),
+    Undo,
+}
+
+/// How to take back the last change.
+pub struct UndoStep {
+    /// What the change was, e.g. "delete of 3 entries"
+    pub label: String,
+    /// Entries to write back as they were
+    pub restore: Vec<TimeEntry>,
+    /// Entries the change created
+    pub remove: Vec<Uuid>,
+}
+
+impl UndoStep {
+    pub fn new(label: String) -> Self {
+        Self { label, restore: Vec::new(), remove: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortBy {
+    Start,
+    Hours,
+    Project,
+    Description,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProjectFilter {
+    All,
+    NoProject,
+    Project(Uuid),
+}
+
+const PROJECT: usize = 0;
+const DESCRIPTION: usize = 1;
+const TAGS: usize = 2;
+const START: usize = 3;
+const END: usize = 4;
+
+/// A row being edited in place, as text.
+struct RowEdit {
+    id: Uuid,
+    original: [String; 5],
+    fields: [String; 5],
+    original_billable: bool,
+    billable: bool,
+}
+
+impl RowEdit {
+    fn new(entry: &TimeEntry, data: &TimeCardData, tz: &Tz) -> Self {
+        let local = |time: DateTime<Utc>| time.with_timezone(tz).format("%Y-%m-%d %H:%M").to_string();
+        let fields = [
+            data.project_name(entry.project_id).unwrap_or_default().to_string(),
+            entry.description.clone().unwrap_or_default(),
+            entry.tags.iter().map(|tag| format!("+{}", tag)).collect::<Vec<_>>().join(" "),
+            local(entry.start_time),
+            entry.end_time.map(local).unwrap_or_default(),
+        ];
+        Self {
+            id: entry.id,
+            original: fields.clone(),
+            fields,
+            original_billable: entry.billable,
+            billable: entry.billable,
+        }
+    }
+    
+    fn changed(&self, field: usize) -> Option<&str> {
+        Some(self.fields[field].trim()).filter(|value| *value != self.original[field])
+    }
+    
+    fn parse_time(&self, field: usize, tz: &Tz) -> anyhow::Result<Option<DateTime<Utc>>> {
+        self.changed(field).map(|time| datetime::parse_datetime(time, tz)).transpose()
+    }
+    
+    /// Why the row can't be saved as it is, per field.
+    fn problem(&self, field: usize, tz: &Tz) -> Option<String> {
+        match field {
+            TAGS => parse_tags(&self.fields[TAGS])
+                .iter()
+                .find(|tag| models::normalize_tag(tag).is_none())
+                .map(|tag| format!("Invalid tag: {}", tag)),
+            START | END => {
+                if let Err(e) = self.parse_time(field, tz) {
+                    return Some(e.to_string());
+                }
+                let start = self.parse_time(START, tz).ok()??;
+                let end = self.parse_time(END, tz).ok()??;
+                (end <= start).then(|| "End time must be after start time".to_string())
+            }
+            _ => None,
+        }
+    }
+    
+    /// The changes the row asks for. Times are only parsed when edited, so
+    /// an untouched start keeps its seconds.
+    fn changes(&self, tz: &Tz) -> anyhow::Result<EntryChanges> {
+        let mut changes = EntryChanges::default();
+        match self.changed(PROJECT) {
+            Some("") => changes.clear_project = true,
+            Some(name) => changes.project = Some(name.to_string()),
+            None => {}
+        }
+        changes.description = self.changed(DESCRIPTION).map(str::to_string);
+        if self.changed(TAGS).is_some() {
+            let before = parse_tags(&self.original[TAGS]);
+            let after = parse_tags(&self.fields[TAGS]);
+            changes.add_tags = after.difference(&before).cloned().collect();
+            changes.remove_tags = before.difference(&after).cloned().collect();
+        }
+        if self.billable != self.original_billable {
+            changes.billable = Some(self.billable);
+        }
+        changes.start = self.parse_time(START, tz)?;
+        changes.end = self.parse_time(END, tz)?;
+        Ok(changes)
+    }
+}
+
+fn parse_tags(tags: &str) -> BTreeSet<String> {
+    tags.split_whitespace().map(|tag| tag.trim_start_matches('+').to_string()).collect()
+}
+
+/// Sortable, filterable table of all entries with in-place editing, bulk
+/// changes and undo of the last change.
+pub struct EntryEditor {
+    /// Period expression; empty shows all entries
+    pub period: String,
+    project: ProjectFilter,
+    tag: String,
+    text: String,
+    sort_by: SortBy,
+    descending: bool,
+    selected: BTreeSet<Uuid>,
+    editing: Option<RowEdit>,
+    bulk_project: String,
+    undo: Option<UndoStep>,
+}
+
+impl EntryEditor {
+    pub fn new() -> Self {
+        Self {
+            period: String::new(),
+            project: ProjectFilter::All,
+            tag: String::new(),
+            text: String::new(),
+            sort_by: SortBy::Start,
+            descending: true,
+            selected: BTreeSet::new(),
+            editing: None,
+            bulk_project: String::new(),
+            undo: None,
+        }
+    }
+    
+    /// Record a carried out change, so it can be undone.
+    pub fn applied(&mut self, step: UndoStep) {
+        if !step.restore.is_empty() || !step.remove.is_empty() {
+            self.undo = Some(step);
+        }
+        self.editing = None;
+        self.selected.clear();
+    }
+    
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo.as_ref().map(|step| step.label.as_str())
+    }
+    
+    pub fn take_undo(&mut self) -> Option<UndoStep> {
+        self.undo.take()
+    }
+    
+    pub fn set_undo(&mut self, step: UndoStep) {
+        self.undo = Some(step);
+    }
+    
+    /// Entries passing the filters, in table order.
+    fn rows<'a>(&self, data: &'a TimeCardData, tz: &Tz) -> anyhow::Result<Vec<&'a TimeEntry>> {
+        let range = match self.period.trim() {
+            "" => None,
+            expr => Some(period::parse_period(expr, tz)?),
+        };
+        let tag_filter = match self.tag.trim() {
+            "" => TagFilter::default(),
+            tag => TagFilter::new(&[tag.trim_start_matches('+').to_string()], &[])?,
+        };
+        let text = self.text.trim().to_lowercase();
+        
+        let mut rows: Vec<_> = data.time_entries
+            .iter()
+            .filter(|entry| range.as_ref().is_none_or(|range| entry.start_time >= range.start && entry.start_time < range.end))
+            .filter(|entry| match self.project {
+                ProjectFilter::All => true,
+                ProjectFilter::NoProject => entry.project_id.is_none(),
+                ProjectFilter::Project(id) => entry.project_id == Some(id),
+            })
+            .filter(|entry| tag_filter.matches(entry))
+            .filter(|entry| {
+                text.is_empty()
+                    || entry.description.as_deref().is_some_and(|desc| desc.to_lowercase().contains(&text))
+                    || data.project_name(entry.project_id).is_some_and(|name| name.to_lowercase().contains(&text))
+            })
+            .collect();
+        
+        let now = Utc::now();
+        rows.sort_by(|a, b| {
+            let order = match self.sort_by {
+                SortBy::Start => a.start_time.cmp(&b.start_time),
+                SortBy::Hours => a.net_duration_until(now).cmp(&b.net_duration_until(now)),
+                SortBy::Project => compare_text(data.project_name(a.project_id), data.project_name(b.project_id)),
+                SortBy::Description => compare_text(a.description.as_deref(), b.description.as_deref()),
+            };
+            let order = order.then_with(|| a.start_time.cmp(&b.start_time));
+            if self.descending { order.reverse() } else { order }
+        });
+        Ok(rows)
+    }
+    
+    pub fn show(&mut self, ui: &mut egui::Ui, data: &TimeCardData, tz: &Tz) -> Option<EntryAction> {
+        let mut action = None;
+        self.selected.retain(|id| data.time_entries.iter().any(|entry| entry.id == *id));
+        
+        self.show_filters(ui, data);
+        
+        let rows = match self.rows(data, tz) {
+            Ok(rows) => rows,
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, e.to_string());
+                Vec::new()
+            }
+        };
+        
+        ui.horizontal(|ui| {
+            let hours = models::sum_hours(rows.iter().filter_map(|entry| entry.hours()));
+            ui.label(format!("{} entries, {:.2} hours", rows.len(), hours));
+            if let Some(undo) = &self.undo {
+                if ui.button(format!("↶ Undo {}", undo.label)).clicked() {
+                    action = Some(EntryAction::Undo);
+                }
+            }
+        });
+        
+        if !self.selected.is_empty() {
+            ui.horizontal(|ui| {
+                let ids: Vec<_> = self.selected.iter().copied().collect();
+                ui.label(format!("{} selected:", ids.len()));
+                ui.add(egui::TextEdit::singleline(&mut self.bulk_project).hint_text("Project").desired_width(120.0));
+                if ui.button("Set project").on_hover_text("Leave empty to remove the project").clicked() {
+                    let project = Some(self.bulk_project.trim().to_string()).filter(|name| !name.is_empty());
+                    action = Some(EntryAction::SetProject { ids: ids.clone(), project });
+                }
+                if ui.add_enabled(ids.len() > 1, egui::Button::new("Merge"))
+                    .on_hover_text("Join entries that follow each other; the gaps become breaks")
+                    .clicked()
+                {
+                    action = Some(EntryAction::Merge(ids.clone()));
+                }
+                if ui.button("🗑 Delete").clicked() {
+                    action = Some(EntryAction::Delete(ids));
+                }
+                if ui.button("Clear selection").clicked() {
+                    self.selected.clear();
+                }
+            });
+        }
+        
+        ui.separator();
+        if let Some(table_action) = self.show_table(ui, data, tz, &rows) {
+            action = Some(table_action);
+        }
+        action
+    }
+    
+    fn show_filters(&mut self, ui: &mut egui::Ui, data: &TimeCardData) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Period:");
+            let selected_text = if self.period.is_empty() { "All time" } else { self.period.as_str() };
+            egui::ComboBox::from_id_source("entry_period")
+                .selected_text(selected_text.to_string())
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.period, String::new(), "All time");
+                    for (value, label) in PERIOD_PRESETS {
+                        ui.selectable_value(&mut self.period, value.to_string(), *label);
+                    }
+                });
+            ui.add(egui::TextEdit::singleline(&mut self.period).desired_width(100.0))
+                .on_hover_text(period::PERIOD_HELP);
+            
+            ui.label("Project:");
+            let project_text = match self.project {
+                ProjectFilter::All => "All".to_string(),
+                ProjectFilter::NoProject => "No Project".to_string(),
+                ProjectFilter::Project(id) => data.project_name(Some(id)).unwrap_or("?").to_string(),
+            };
+            egui::ComboBox::from_id_source("entry_project")
+                .selected_text(project_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.project, ProjectFilter::All, "All");
+                    ui.selectable_value(&mut self.project, ProjectFilter::NoProject, "No Project");
+                    let mut projects: Vec<_> = data.projects.iter().collect();
+                    projects.sort_by_key(|project| project.name.to_lowercase());
+                    for project in projects {
+                        ui.selectable_value(&mut self.project, ProjectFilter::Project(project.id), &project.name);
+                    }
+                });
+            
+            ui.label("Tag:");
+            ui.add(egui::TextEdit::singleline(&mut self.tag).desired_width(80.0));
+            ui.label("Search:");
+            ui.add(egui::TextEdit::singleline(&mut self.text).desired_width(120.0));
+            
+            if ui.button("Clear filters").clicked() {
+                self.period.clear();
+                self.project = ProjectFilter::All;
+                self.tag.clear();
+                self.text.clear();
+            }
+        });
+    }
+    
+    fn show_table(&mut self, ui: &mut egui::Ui, data: &TimeCardData, tz: &Tz, rows: &[&TimeEntry]) -> Option<EntryAction> {
+        let mut action = None;
+        let now = Utc::now();
+        
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::exact(20.0))
+            .column(Column::initial(130.0))
+            .column(Column::initial(130.0))
+            .column(Column::initial(50.0))
+            .column(Column::initial(120.0))
+            .column(Column::initial(220.0).at_least(80.0))
+            .column(Column::initial(110.0))
+            .column(Column::exact(30.0))
+            .column(Column::remainder().at_least(60.0))
+            .min_scrolled_height(0.0)
+            .header(22.0, |mut header| {
+                header.col(|ui| {
+                    let mut all = !rows.is_empty() && rows.iter().all(|entry| self.selected.contains(&entry.id));
+                    if ui.checkbox(&mut all, "").on_hover_text("Select all shown entries").changed() {
+                        for entry in rows {
+                            if all {
+                                self.selected.insert(entry.id);
+                            } else {
+                                self.selected.remove(&entry.id);
+                            }
+                        }
+                    }
+                });
+                header.col(|ui| self.sort_button(ui, "Start", SortBy::Start));
+                header.col(|ui| {
+                    ui.strong("End");
+                });
+                header.col(|ui| self.sort_button(ui, "Hours", SortBy::Hours));
+                header.col(|ui| self.sort_button(ui, "Project", SortBy::Project));
+                header.col(|ui| self.sort_button(ui, "Description", SortBy::Description));
+                header.col(|ui| {
+                    ui.strong("Tags");
+                });
+                header.col(|ui| {
+                    ui.strong("💰").on_hover_text("Billable");
+                });
+                header.col(|_| {});
+            })
+            .body(|body| {
+                body.rows(24.0, rows.len(), |index, mut row| {
+                    let entry = rows[index];
+                    let editing = self.editing.as_mut().filter(|edit| edit.id == entry.id);
+                    
+                    row.col(|ui| {
+                        let mut selected = self.selected.contains(&entry.id);
+                        if ui.checkbox(&mut selected, "").changed() {
+                            if selected {
+                                self.selected.insert(entry.id);
+                            } else {
+                                self.selected.remove(&entry.id);
+                            }
+                        }
+                    });
+                    
+                    if let Some(edit) = editing {
+                        for field in [START, END] {
+                            row.col(|ui| text_field(ui, edit, field, tz));
+                        }
+                        row.col(|ui| {
+                            ui.label(format!("{:.2}", hours_of(entry, now)));
+                        });
+                        for field in [PROJECT, DESCRIPTION, TAGS] {
+                            row.col(|ui| text_field(ui, edit, field, tz));
+                        }
+                        row.col(|ui| {
+                            ui.checkbox(&mut edit.billable, "");
+                        });
+                        let mut stop_editing = false;
+                        row.col(|ui| {
+                            let problem = [START, END, TAGS].into_iter().find_map(|field| edit.problem(field, tz));
+                            let save = ui.add_enabled(problem.is_none(), egui::Button::new("💾"))
+                                .on_hover_text("Save")
+                                .on_disabled_hover_text(problem.unwrap_or_default());
+                            if save.clicked() {
+                                // Already validated, so this only fails on a race with the clock
+                                if let Ok(changes) = edit.changes(tz) {
+                                    if changes.is_empty() {
+                                        stop_editing = true;
+                                    } else {
+                                        action = Some(EntryAction::Edit { id: entry.id, changes });
+                                    }
+                                }
+                            } else if ui.button("✖").on_hover_text("Cancel").clicked() {
+                                stop_editing = true;
+                            }
+                        });
+                        if stop_editing {
+                            self.editing = None;
+                        }
+                        return;
+                    }
+                    
+                    let start = entry.start_time.with_timezone(tz);
+                    row.col(|ui| {
+                        ui.label(start.format("%Y-%m-%d %H:%M").to_string());
+                    });
+                    row.col(|ui| {
+                        match entry.end_time.map(|end| end.with_timezone(tz)) {
+                            Some(end) if end.date_naive() == start.date_naive() => ui.label(end.format("%H:%M").to_string()),
+                            Some(end) => ui.label(end.format("%Y-%m-%d %H:%M").to_string()),
+                            None => ui.colored_label(egui::Color32::GREEN, "running"),
+                        };
+                    });
+                    row.col(|ui| {
+                        ui.label(format!("{:.2}", hours_of(entry, now)));
+                    });
+                    row.col(|ui| {
+                        ui.label(data.project_name(entry.project_id).unwrap_or("No Project"));
+                    });
+                    row.col(|ui| {
+                        ui.label(entry.description.as_deref().unwrap_or_default());
+                    });
+                    row.col(|ui| {
+                        ui.label(entry.format_tags());
+                    });
+                    row.col(|ui| {
+                        ui.label(if entry.billable { "✔" } else { "" });
+                    });
+                    row.col(|ui| {
+                        let invoice = entry.invoice_id
+                            .and_then(|id| data.invoices.iter().find(|invoice| invoice.id == id));
+                        if let Some(invoice) = invoice {
+                            ui.label("🔒").on_hover_text(format!("Billed on invoice {}", invoice.number));
+                            return;
+                        }
+                        if ui.button("✏").on_hover_text("Edit").clicked() {
+                            self.editing = Some(RowEdit::new(entry, data, tz));
+                        }
+                        if ui.button("🗑").on_hover_text("Delete").clicked() {
+                            action = Some(EntryAction::Delete(vec![entry.id]));
+                        }
+                    });
+                });
+            });
+        
+        action
+    }
+    
+    fn sort_button(&mut self, ui: &mut egui::Ui, label: &str, sort_by: SortBy) {
+        let arrow = match (self.sort_by == sort_by, self.descending) {
+            (true, true) => " ⏷",
+            (true, false) => " ⏶",
+            (false, _) => "",
+        };
+        if ui.add(egui::Button::new(egui::RichText::new(format!("{}{}", label, arrow)).strong()).frame(false)).clicked() {
+            if self.sort_by == sort_by {
+                self.descending = !self.descending;
+            } else {
+                self.sort_by = sort_by;
+                self.descending = sort_by == SortBy::Start || sort_by == SortBy::Hours;
+            }
+        }
+    }
+}
+
+/// One field of the row being edited, red with the reason when invalid.
+fn text_field(ui: &mut egui::Ui, edit: &mut RowEdit, field: usize, tz: &Tz) {
+    let problem = edit.problem(field, tz);
+    let mut text_edit = egui::TextEdit::singleline(&mut edit.fields[field]).desired_width(f32::INFINITY);
+    if problem.is_some() {
+        text_edit = text_edit.text_color(egui::Color32::RED);
+    }
+    let response = ui.add(text_edit);
+    if let Some(problem) = problem {
+        response.on_hover_text(problem);
+    }
+}
+
+/// Worked hours, up to now for the active entry.
+fn hours_of(entry: &TimeEntry, now: DateTime<Utc>) -> f64 {
+    entry.net_duration_until(now).num_seconds() as f64 / 3600.0
+}
+
+/// Case-insensitive order with missing values last.
+fn compare_text(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
@@ -0,0 +1,511 @@
+use calendar::Calendar;
+use charts::Charts;
+use chrono::Utc;
+use chrono_tz::Tz;
+use entries::{EntryAction, EntryEditor, UndoStep};
+use eframe::egui;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration as StdDuration, Instant, SystemTime};
+use timecard::{
+    datetime,
+    models::{self, TimeCardData, TimeEntry},
+    period,
+    storage::lock,
+    targets::{self, BudgetLevel, Progress, TargetStatus},
+    tracker::{Activity, EntryChanges, NewEntry, TimeTracker},
+    TimeCardError,
+};
+
+mod calendar;
+mod charts;
+mod entries;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Tracker,
+    Entries,
+    Calendar,
+    Charts,
+}
+
+pub struct TimeCardApp {
+    tracker: TimeTracker,
+    data: TimeCardData,
+    tz: Tz,
+    current_project: String,
+    current_description: String,
+    selected_period: String,
+    show_add_manual: bool,
+    manual_start: String,
+    manual_end: String,
+    manual_project: String,
+    manual_description: String,
+    disk_stamp: Option<(SystemTime, u64)>,
+    last_disk_check: Instant,
+    /// Why the last action failed, shown until dismissed or the next success
+    error: Option<String>,
+    view: View,
+    entry_editor: EntryEditor,
+    calendar: Calendar,
+    charts: Charts,
+}
+
+/// Period shortcuts offered in the report pickers of the GUI and TUI. In the
+/// GUI, any expression accepted by the CLI can also be typed in.
+pub(crate) const PERIOD_PRESETS: &[(&str, &str)] = &[
+    ("today", "Today"),
+    ("yesterday", "Yesterday"),
+    ("week", "This Week"),
+    ("last-week", "Last Week"),
+    ("month", "This Month"),
+    ("last-month", "Last Month"),
+    ("last-7-days", "Last 7 Days"),
+    ("last-30-days", "Last 30 Days"),
+    ("quarter", "This Quarter"),
+    ("ytd", "Year to Date"),
+    ("last-year", "Last Year"),
+];
+
+/// How often the data file is checked for changes made by other processes.
+pub(crate) const DISK_CHECK_INTERVAL: StdDuration = StdDuration::from_secs(1);
+
+impl TimeCardApp {
+    pub fn new(mut tracker: TimeTracker) -> Self {
+        let data = tracker.load().unwrap_or_default();
+        let tz = data.settings.timezone().unwrap_or_else(|_| datetime::system_timezone());
+        let disk_stamp = read_disk_stamp(tracker.store().path());
+        
+        Self {
+            tracker,
+            data,
+            tz,
+            current_project: String::new(),
+            current_description: String::new(),
+            selected_period: "today".to_string(),
+            show_add_manual: false,
+            manual_start: String::new(),
+            manual_end: String::new(),
+            manual_project: String::new(),
+            manual_description: String::new(),
+            disk_stamp,
+            last_disk_check: Instant::now(),
+            error: None,
+            view: View::Tracker,
+            entry_editor: EntryEditor::new(),
+            calendar: Calendar::new(),
+            charts: Charts::new(),
+        }
+    }
+    
+    fn reload(&mut self) {
+        match self.tracker.load() {
+            Ok(data) => {
+                self.tz = data.settings.timezone().unwrap_or_else(|_| datetime::system_timezone());
+                self.data = data;
+                self.disk_stamp = read_disk_stamp(self.tracker.store().path());
+            }
+            Err(e) => self.error = Some(format!("Error reloading data: {:#}", e)),
+        }
+    }
+    
+    /// Reload if another process (e.g. the CLI) changed the data file since
+    /// we last read or wrote it.
+    fn reload_if_changed(&mut self) {
+        if read_disk_stamp(self.tracker.store().path()) != self.disk_stamp {
+            self.reload();
+        }
+    }
+    
+    /// Run a change under the data file lock, backing the file up before
+    /// the session's first change, then show its result or why it failed.
+    fn with_lock(&mut self, action: impl FnOnce(&mut Self) -> anyhow::Result<()>) {
+        let _lock = match lock::lock_exclusive(self.tracker.store().path()) {
+            Ok(lock) => lock,
+            Err(e) => {
+                self.error = Some(format!("{:#}", e));
+                return;
+            }
+        };
+        
+        self.error = self.tracker.back_up_once().and_then(|_| action(self)).err().map(|e| format!("{:#}", e));
+        self.reload();
+    }
+    
+    fn clock_in(&mut self) -> anyhow::Result<()> {
+        self.tracker.clock_in(activity(&self.current_project, &self.current_description), None)?;
+        
+        // Clear input fields
+        self.current_project.clear();
+        self.current_description.clear();
+        Ok(())
+    }
+    
+    fn clock_out(&mut self) -> anyhow::Result<()> {
+        let description = Some(self.current_description.clone()).filter(|desc| !desc.is_empty());
+        self.tracker.clock_out(description, None)?;
+        self.current_description.clear();
+        Ok(())
+    }
+    
+    fn toggle_pause(&mut self) -> anyhow::Result<()> {
+        match self.tracker.active_entry()? {
+            Some(entry) if entry.is_paused() => self.tracker.resume(None)?,
+            _ => self.tracker.pause(None)?,
+        };
+        Ok(())
+    }
+    
+    fn add_manual_entry(&mut self) -> anyhow::Result<()> {
+        if self.manual_start.trim().is_empty() || self.manual_end.trim().is_empty() {
+            return Err(TimeCardError::InvalidInput("Enter a start and an end time".to_string()).into());
+        }
+        
+        // Parse datetime strings as local wall-clock time
+        let start = datetime::parse_datetime(&self.manual_start, &self.tz)?;
+        let end = datetime::parse_datetime(&self.manual_end, &self.tz)?;
+        
+        self.tracker.add(NewEntry {
+            activity: activity(&self.manual_project, &self.manual_description),
+            start,
+            end,
+        })?;
+        
+        // Clear manual entry fields
+        self.manual_start.clear();
+        self.manual_end.clear();
+        self.manual_project.clear();
+        self.manual_description.clear();
+        self.show_add_manual = false;
+        Ok(())
+    }
+    
+    /// Carry out a change from the entry table or calendar, keeping what it
+    /// takes to undo it. Bulk changes are saved together, so they are done
+    /// for all entries or none.
+    fn apply_entry_action(&mut self, action: EntryAction) -> anyhow::Result<()> {
+        let (step, result) = match action {
+            EntryAction::Edit { id, changes } => {
+                let mut step = UndoStep::new(format!("edit of {}", &id.to_string()[..8]));
+                let result = self.tracker.edit(&id.to_string(), changes).map(|edited| step.restore.push(edited.before));
+                (step, result)
+            }
+            EntryAction::Add(new_entry) => {
+                let mut step = UndoStep::new(plural("add", 1));
+                let result = self.tracker.add(new_entry).map(|added| step.remove.push(added.entry.id));
+                (step, result)
+            }
+            EntryAction::Delete(ids) => {
+                let mut step = UndoStep::new(plural("delete", ids.len()));
+                let result = self.tracker.delete_all(&ids).map(|deleted| step.restore = deleted);
+                (step, result)
+            }
+            EntryAction::SetProject { ids, project } => {
+                let mut step = UndoStep::new(plural("project change", ids.len()));
+                let changes = EntryChanges {
+                    clear_project: project.is_none(),
+                    project,
+                    ..EntryChanges::default()
+                };
+                let result = self.tracker.edit_all(&ids, changes).map(|edited| {
+                    step.restore = edited.into_iter().map(|edited| edited.before).collect();
+                });
+                (step, result)
+            }
+            EntryAction::Merge(ids) => {
+                let mut step = UndoStep::new(plural("merge", ids.len()));
+                let result = self.tracker.merge(&ids).map(|merged| step.restore = merged.before);
+                (step, result)
+            }
+            EntryAction::Undo => {
+                let Some(step) = self.entry_editor.take_undo() else {
+                    return Ok(());
+                };
+                if let Err(e) = self.tracker.restore(&step.restore, &step.remove) {
+                    self.entry_editor.set_undo(step);
+                    return Err(e);
+                }
+                return Ok(());
+            }
+        };
+        if result.is_ok() {
+            self.entry_editor.applied(step);
+        }
+        result
+    }
+    
+    fn get_period_entries(&self) -> anyhow::Result<Vec<&TimeEntry>> {
+        let range = period::parse_period(&self.selected_period, &self.tz)?;
+        Ok(self.data.get_entries_by_period(range.start, range.end))
+    }
+    
+    fn show_tracker(&mut self, ui: &mut egui::Ui) {
+        // Status section
+        ui.group(|ui| {
+            ui.heading("📊 Status");
+            
+            if let Some(active_entry) = self.data.get_active_entry() {
+                let now = Utc::now();
+                let hours = active_entry.net_duration_until(now).num_seconds() as f64 / 3600.0;
+                let breaks = active_entry.break_duration_until(now).num_seconds() as f64 / 3600.0;
+                let paused = active_entry.is_paused();
+                
+                if paused {
+                    ui.label("⏸️ Clocked In, On a Break");
+                } else {
+                    ui.label("🟢 Currently Clocked In");
+                }
+                ui.label(format!("Started: {}", active_entry.start_time.with_timezone(&self.tz).format("%Y-%m-%d %H:%M:%S")));
+                ui.label(format!("Duration: {:.2} hours", hours));
+                if breaks > 0.0 {
+                    ui.label(format!("Breaks: {:.2} hours", breaks));
+                }
+                
+                if let Some(proj) = self.data.project_name(active_entry.project_id) {
+                    ui.label(format!("Project: {}", proj));
+                }
+                if let Some(desc) = &active_entry.description {
+                    ui.label(format!("Description: {}", desc));
+                }
+                if !active_entry.tags.is_empty() {
+                    ui.label(format!("Tags: {}", active_entry.format_tags()));
+                }
+                
+                ui.add_space(5.0);
+                ui.text_edit_singleline(&mut self.current_description);
+                ui.horizontal(|ui| {
+                    if ui.button(if paused { "▶️ Resume" } else { "⏸️ Pause" }).clicked() {
+                        self.with_lock(Self::toggle_pause);
+                    }
+                    if ui.button("🛑 Clock Out").clicked() {
+                        self.with_lock(Self::clock_out);
+                    }
+                });
+            } else {
+                ui.label("🔴 Not Clocked In");
+                ui.add_space(5.0);
+                
+                ui.label("Project:");
+                ui.text_edit_singleline(&mut self.current_project);
+                ui.label("Description (+tag to tag):");
+                ui.text_edit_singleline(&mut self.current_description);
+                
+                if ui.button("🟢 Clock In").clicked() {
+                    self.with_lock(Self::clock_in);
+                }
+            }
+        });
+        
+        ui.add_space(10.0);
+        
+        // Quick stats
+        ui.group(|ui| {
+            ui.heading("📈 Quick Stats");
+            
+            let today = datetime::today(&self.tz);
+            let today_entries = self.data.get_entries_by_date(today, &self.tz);
+            let today_hours = models::sum_hours(today_entries.iter().filter_map(|entry| entry.hours()));
+            
+            ui.label(format!("Today: {:.2} hours ({} entries)", today_hours, today_entries.len()));
+            
+            let total_hours = self.data.total_hours();
+            ui.label(format!("Total: {:.2} hours ({} entries)", total_hours, self.data.time_entries.len()));
+        });
+        
+        ui.add_space(10.0);
+        
+        // Targets section
+        match targets::target_status(&self.data, &self.tz, Utc::now()) {
+            Ok(status) if status.today.is_some() || status.week.is_some() || !status.budgets.is_empty() => {
+                ui.group(|ui| {
+                    ui.heading("🎯 Targets");
+                    show_target_status(ui, &status, &self.tz);
+                });
+                ui.add_space(10.0);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, e.to_string());
+            }
+        }
+        
+        // Reports section
+        ui.group(|ui| {
+            ui.heading("📊 Reports");
+            
+            ui.horizontal(|ui| {
+                ui.label("Period:");
+                egui::ComboBox::from_id_source("period")
+                    .selected_text(&self.selected_period)
+                    .show_ui(ui, |ui| {
+                        for (value, label) in PERIOD_PRESETS {
+                            ui.selectable_value(&mut self.selected_period, value.to_string(), *label);
+                        }
+                    });
+                ui.text_edit_singleline(&mut self.selected_period)
+                    .on_hover_text(period::PERIOD_HELP);
+            });
+            
+            let period_entries = match self.get_period_entries() {
+                Ok(entries) => entries,
+                Err(e) => {
+                    ui.colored_label(egui::Color32::RED, e.to_string());
+                    Vec::new()
+                }
+            };
+            let period_hours = models::sum_hours(period_entries.iter().filter_map(|entry| entry.hours()));
+            
+            ui.label(format!("Total Hours: {:.2}", period_hours));
+            ui.label(format!("Entries: {}", period_entries.len()));
+            
+            ui.horizontal(|ui| {
+                if ui.button("📋 Edit these entries").clicked() {
+                    self.entry_editor.period = self.selected_period.clone();
+                    self.view = View::Entries;
+                }
+                if ui.button("📈 Chart this period").clicked() {
+                    self.charts.period = self.selected_period.clone();
+                    self.view = View::Charts;
+                }
+            });
+        });
+        
+        ui.add_space(10.0);
+        
+        // Manual entry section
+        ui.group(|ui| {
+            ui.heading("➕ Manual Entry");
+            
+            if ui.button("Add Manual Entry").clicked() {
+                self.show_add_manual = !self.show_add_manual;
+            }
+            
+            if self.show_add_manual {
+                ui.add_space(5.0);
+                
+                ui.label("Start Time:");
+                ui.text_edit_singleline(&mut self.manual_start);
+                ui.label("End Time:");
+                ui.text_edit_singleline(&mut self.manual_end);
+                ui.label("Project:");
+                ui.text_edit_singleline(&mut self.manual_project);
+                ui.label("Description (+tag to tag):");
+                ui.text_edit_singleline(&mut self.manual_description);
+                
+                if ui.button("Add Entry").clicked() {
+                    self.with_lock(Self::add_manual_entry);
+                }
+            }
+        });
+    }
+}
+
+/// Progress bars for the targets that are set, with budgets coloured by how
+/// much of them is used.
+fn show_target_status(ui: &mut egui::Ui, status: &TargetStatus, tz: &Tz) {
+    if let Some(today) = &status.today {
+        let finish = status.finish_at
+            .map(|at| format!(", done at {}", at.with_timezone(tz).format("%H:%M")))
+            .unwrap_or_default();
+        ui.label("Today:");
+        ui.add(egui::ProgressBar::new(today.fraction().min(1.0) as f32).text(format!("{}{}", progress_text(today), finish)));
+    }
+    if let Some(week) = &status.week {
+        ui.label("Week:");
+        ui.add(egui::ProgressBar::new(week.fraction().min(1.0) as f32).text(progress_text(week)));
+    }
+    for budget in &status.budgets {
+        let (icon, fill) = match budget.level() {
+            BudgetLevel::Within => ("", ui.visuals().selection.bg_fill),
+            BudgetLevel::Warning => ("⚠️ ", egui::Color32::from_rgb(200, 150, 30)),
+            BudgetLevel::Exceeded => ("❌ ", egui::Color32::from_rgb(200, 60, 60)),
+        };
+        ui.label(format!("{}{}:", icon, budget.project.name));
+        ui.add(
+            egui::ProgressBar::new(budget.progress.fraction().min(1.0) as f32)
+                .fill(fill)
+                .text(progress_text(&budget.progress)),
+        );
+    }
+}
+
+fn progress_text(progress: &Progress) -> String {
+    if progress.worked > progress.target {
+        format!("{:.2} of {:.2} hours, {:.2} over", progress.worked, progress.target, progress.worked - progress.target)
+    } else {
+        format!("{:.2} of {:.2} hours, {:.2} left", progress.worked, progress.target, progress.remaining())
+    }
+}
+
+/// What the project and description fields ask to work on.
+fn activity(project: &str, description: &str) -> Activity {
+    Activity {
+        project: Some(project.to_string()).filter(|name| !name.trim().is_empty()),
+        description: Some(description.to_string()).filter(|desc| !desc.is_empty()),
+        tags: Vec::new(),
+        billable: true,
+    }
+}
+
+pub(crate) fn plural(change: &str, count: usize) -> String {
+    match count {
+        1 => format!("{} of 1 entry", change),
+        _ => format!("{} of {} entries", change, count),
+    }
+}
+
+pub(crate) fn read_disk_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+impl eframe::App for TimeCardApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if self.last_disk_check.elapsed() >= DISK_CHECK_INTERVAL {
+            self.reload_if_changed();
+            self.last_disk_check = Instant::now();
+        }
+        ctx.request_repaint_after(DISK_CHECK_INTERVAL);
+        
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("🕐 TimeCard - Time Tracking");
+            ui.add_space(10.0);
+            
+            if let Some(error) = &self.error {
+                let mut dismissed = false;
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+                    dismissed = ui.small_button("Dismiss").clicked();
+                });
+                if dismissed {
+                    self.error = None;
+                }
+                ui.add_space(10.0);
+            }
+            
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.view, View::Tracker, "🕐 Tracker");
+                ui.selectable_value(&mut self.view, View::Entries, "📋 Entries");
+                ui.selectable_value(&mut self.view, View::Calendar, "📅 Calendar");
+                ui.selectable_value(&mut self.view, View::Charts, "📈 Charts");
+            });
+            ui.separator();
+            
+            match self.view {
+                View::Tracker => self.show_tracker(ui),
+                View::Entries => {
+                    if let Some(action) = self.entry_editor.show(ui, &self.data, &self.tz) {
+                        self.with_lock(|app| app.apply_entry_action(action));
+                    }
+                }
+                View::Calendar => {
+                    let undo = self.entry_editor.undo_label();
+                    if let Some(action) = self.calendar.show(ui, &self.data, &self.tz, undo) {
+                        self.with_lock(|app| app.apply_entry_action(action));
+                    }
+                }
+                View::Charts => self.charts.show(ui, &self.data, &self.tz),
+            }
+        });
+    }
+}
@@ -0,0 +1,373 @@
+use crate::{
+    billing,
+    error::TimeCardError,
+    models::{Client, Invoice, InvoiceLine, Rate, RateScope, TimeCardData},
+    period::Period,
+};
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum InvoiceFormat {
+    /// Fixed-width plain text
+    Text,
+    Markdown,
+    /// Standalone HTML page
+    Html,
+}
+
+pub struct InvoiceOptions {
+    /// Tax rate in percent.
+    pub tax_rate: f64,
+    /// Minutes each entry is rounded up to; 0 keeps exact time.
+    pub rounding: u32,
+    pub issued_on: NaiveDate,
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Billed hours of an entry, rounded up to whole `rounding` minutes.
+fn billed_hours(seconds: i64, rounding: u32) -> f64 {
+    if rounding == 0 {
+        return seconds as f64 / 3600.0;
+    }
+    let step = rounding as i64 * 60;
+    let rounded = (seconds + step - 1) / step * step;
+    rounded as f64 / 3600.0
+}
+
+/// Group a client's uninvoiced billable entries in `range` into line items,
+/// one per project and rate. The invoice isn't numbered yet.
+pub fn build_invoice(
+    data: &TimeCardData,
+    client: &Client,
+    range: &Period,
+    options: &InvoiceOptions,
+    tz: &Tz,
+) -> anyhow::Result<Invoice> {
+    let entries: Vec<_> = data
+        .get_entries_by_period(range.start, range.end)
+        .into_iter()
+        .filter(|entry| entry.billable && !entry.is_active() && entry.invoice_id.is_none())
+        .filter(|entry| data.client_for_project(entry.project_id).map(|c| c.id) == Some(client.id))
+        .collect();
+    
+    if entries.is_empty() {
+        return Err(TimeCardError::InvalidInput(format!(
+            "No uninvoiced billable time for {} in {}",
+            client.name,
+            range.name
+        )).into());
+    }
+    
+    let unrated: Vec<_> = entries
+        .iter()
+        .filter(|entry| billing::rate_for(data, entry, tz).is_none())
+        .map(|entry| entry.short_id())
+        .collect();
+    if !unrated.is_empty() {
+        return Err(TimeCardError::InvalidInput(format!(
+            "{} entries have no rate: {}. Set one with `timecard rate set`.",
+            unrated.len(),
+            unrated.join(", ")
+        )).into());
+    }
+    
+    // Line items keyed by description and rate, in a stable order
+    let mut lines: BTreeMap<(String, Uuid), (f64, &Rate)> = BTreeMap::new();
+    for entry in &entries {
+        let rate = billing::rate_for(data, entry, tz).unwrap();
+        let project = data.project_name(entry.project_id).unwrap_or("No Project");
+        let description = match &rate.scope {
+            RateScope::Tag(tag) => format!("{} (+{})", project, tag),
+            _ => project.to_string(),
+        };
+        let seconds = entry.duration().map(|d| d.num_seconds()).unwrap_or(0);
+        lines.entry((description, rate.id)).or_insert((0.0, rate)).0 += billed_hours(seconds, options.rounding);
+    }
+    
+    let currencies: BTreeSet<_> = lines.values().map(|(_, rate)| rate.currency.as_str()).collect();
+    if currencies.len() > 1 {
+        return Err(TimeCardError::InvalidInput(format!(
+            "Rates for {} use several currencies ({}); an invoice needs a single currency",
+            client.name,
+            currencies.into_iter().collect::<Vec<_>>().join(", ")
+        )).into());
+    }
+    let currency = currencies.into_iter().next().unwrap().to_string();
+    
+    let lines: Vec<_> = lines
+        .into_iter()
+        .map(|((description, _), (hours, rate))| InvoiceLine {
+            description,
+            hours,
+            hourly_rate: rate.hourly_rate,
+            amount: round_cents(hours * rate.hourly_rate),
+        })
+        .collect();
+    let subtotal = round_cents(lines.iter().map(|line| line.amount).sum());
+    let tax = round_cents(subtotal * options.tax_rate / 100.0);
+    
+    let now = Utc::now();
+    Ok(Invoice {
+        id: Uuid::new_v4(),
+        sequence: 0,
+        number: String::new(),
+        client_id: client.id,
+        client_name: client.name.clone(),
+        period_start: range.first_day,
+        period_end: range.last_day,
+        issued_on: options.issued_on,
+        currency,
+        lines,
+        subtotal,
+        tax_rate: options.tax_rate,
+        tax,
+        total: round_cents(subtotal + tax),
+        entry_ids: entries.iter().map(|entry| entry.id).collect(),
+        void: false,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+pub fn render(invoice: &Invoice, format: InvoiceFormat) -> String {
+    match format {
+        InvoiceFormat::Text => render_text(invoice),
+        InvoiceFormat::Markdown => render_markdown(invoice),
+        InvoiceFormat::Html => render_html(invoice),
+    }
+}
+
+fn title(invoice: &Invoice) -> String {
+    if invoice.number.is_empty() {
+        "Invoice (draft)".to_string()
+    } else if invoice.void {
+        format!("Invoice {} (VOID)", invoice.number)
+    } else {
+        format!("Invoice {}", invoice.number)
+    }
+}
+
+fn period(invoice: &Invoice) -> String {
+    format!("{} to {}", invoice.period_start.format("%Y-%m-%d"), invoice.period_end.format("%Y-%m-%d"))
+}
+
+fn tax_label(invoice: &Invoice) -> String {
+    format!("Tax ({}%)", invoice.tax_rate)
+}
+
+fn render_text(invoice: &Invoice) -> String {
+    let mut out = String::new();
+    let money = |amount: f64| format!("{:.2}", amount);
+    
+    writeln!(out, "{}", title(invoice).to_uppercase()).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "Client:  {}", invoice.client_name).unwrap();
+    writeln!(out, "Issued:  {}", invoice.issued_on.format("%Y-%m-%d")).unwrap();
+    writeln!(out, "Period:  {}", period(invoice)).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "{:<40} {:>8} {:>10} {:>12}", "Description", "Hours", "Rate", "Amount").unwrap();
+    writeln!(out, "{}", "-".repeat(73)).unwrap();
+    for line in &invoice.lines {
+        writeln!(out, "{:<40} {:>8.2} {:>10} {:>12}",
+            line.description, line.hours, money(line.hourly_rate), money(line.amount)
+        ).unwrap();
+    }
+    writeln!(out, "{}", "-".repeat(73)).unwrap();
+    writeln!(out, "{:>60} {:>12}", "Subtotal", money(invoice.subtotal)).unwrap();
+    if invoice.tax_rate != 0.0 {
+        writeln!(out, "{:>60} {:>12}", tax_label(invoice), money(invoice.tax)).unwrap();
+    }
+    writeln!(out, "{:>60} {:>12}", format!("Total ({})", invoice.currency), money(invoice.total)).unwrap();
+    out
+}
+
+fn render_markdown(invoice: &Invoice) -> String {
+    let mut out = String::new();
+    let money = |amount: f64| billing::format_amount(amount, &invoice.currency);
+    
+    writeln!(out, "# {}", title(invoice)).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "- **Client:** {}", invoice.client_name).unwrap();
+    writeln!(out, "- **Issued:** {}", invoice.issued_on.format("%Y-%m-%d")).unwrap();
+    writeln!(out, "- **Period:** {}", period(invoice)).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "| Description | Hours | Rate | Amount |").unwrap();
+    writeln!(out, "|---|---:|---:|---:|").unwrap();
+    for line in &invoice.lines {
+        writeln!(out, "| {} | {:.2} | {} | {} |",
+            line.description.replace('|', "\\|"), line.hours, money(line.hourly_rate), money(line.amount)
+        ).unwrap();
+    }
+    writeln!(out, "| | | **Subtotal** | {} |", money(invoice.subtotal)).unwrap();
+    if invoice.tax_rate != 0.0 {
+        writeln!(out, "| | | **{}** | {} |", tax_label(invoice), money(invoice.tax)).unwrap();
+    }
+    writeln!(out, "| | | **Total** | **{}** |", money(invoice.total)).unwrap();
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(invoice: &Invoice) -> String {
+    let mut out = String::new();
+    let money = |amount: f64| billing::format_amount(amount, &invoice.currency);
+    let title = escape_html(&title(invoice));
+    
+    writeln!(out, "<!DOCTYPE html>").unwrap();
+    writeln!(out, "<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>", title).unwrap();
+    writeln!(out, "<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ padding: 0.4em 0.6em; border-bottom: 1px solid #ddd; }}
+td.num, th.num {{ text-align: right; }}
+tr.total td {{ font-weight: bold; }}
+</style>\n</head>\n<body>").unwrap();
+    writeln!(out, "<h1>{}</h1>", title).unwrap();
+    writeln!(out, "<p>Client: {}<br>Issued: {}<br>Period: {}</p>",
+        escape_html(&invoice.client_name),
+        invoice.issued_on.format("%Y-%m-%d"),
+        period(invoice)
+    ).unwrap();
+    writeln!(out, "<table>").unwrap();
+    writeln!(out, "<tr><th>Description</th><th class=\"num\">Hours</th><th class=\"num\">Rate</th><th class=\"num\">Amount</th></tr>").unwrap();
+    for line in &invoice.lines {
+        writeln!(out, "<tr><td>{}</td><td class=\"num\">{:.2}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            escape_html(&line.description), line.hours, money(line.hourly_rate), money(line.amount)
+        ).unwrap();
+    }
+    writeln!(out, "<tr><td colspan=\"3\" class=\"num\">Subtotal</td><td class=\"num\">{}</td></tr>", money(invoice.subtotal)).unwrap();
+    if invoice.tax_rate != 0.0 {
+        writeln!(out, "<tr><td colspan=\"3\" class=\"num\">{}</td><td class=\"num\">{}</td></tr>", tax_label(invoice), money(invoice.tax)).unwrap();
+    }
+    writeln!(out, "<tr class=\"total\"><td colspan=\"3\" class=\"num\">Total</td><td class=\"num\">{}</td></tr>", money(invoice.total)).unwrap();
+    writeln!(out, "</table>\n</body>\n</html>").unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Project, TimeEntry};
+    use chrono::{DateTime, TimeZone};
+    
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 9, day, hour, minute, 0).unwrap()
+    }
+    
+    fn add_entry(data: &mut TimeCardData, project: &Project, start: DateTime<Utc>, end: DateTime<Utc>, tag: Option<&str>) -> Uuid {
+        let mut entry = TimeEntry::new(Some(project.id), None);
+        entry.start_time = start;
+        entry.end_time = Some(end);
+        entry.tags.extend(tag.map(str::to_string));
+        let id = entry.id;
+        data.add_time_entry(entry);
+        id
+    }
+    
+    /// Acme at 100 an hour, and 150 for `urgent` work, with a project "Web".
+    fn acme() -> (TimeCardData, Client, Project) {
+        let mut data = TimeCardData::default();
+        let client = Client::new("Acme".to_string(), Some("EUR".to_string()));
+        let mut project = Project::new("Web".to_string(), None);
+        project.client_id = Some(client.id);
+        let from = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        data.rates.push(Rate::new(RateScope::Client(client.id), 100.0, "EUR".to_string(), from));
+        data.rates.push(Rate::new(RateScope::Tag("urgent".to_string()), 150.0, "EUR".to_string(), from));
+        data.clients.push(client.clone());
+        data.projects.push(project.clone());
+        (data, client, project)
+    }
+    
+    fn options(tax_rate: f64, rounding: u32) -> InvoiceOptions {
+        InvoiceOptions {
+            tax_rate,
+            rounding,
+            issued_on: NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(),
+        }
+    }
+    
+    fn september() -> Period {
+        let first = NaiveDate::from_ymd_opt(2025, 9, 1).unwrap();
+        let last = NaiveDate::from_ymd_opt(2025, 9, 30).unwrap();
+        Period::new("September 2025", first, last, &Tz::UTC)
+    }
+    
+    #[test]
+    fn billed_hours_round_up_to_whole_steps() {
+        assert_eq!(billed_hours(3600, 0), 1.0);
+        assert_eq!(billed_hours(1, 15), 0.25);
+        assert_eq!(billed_hours(900, 15), 0.25);
+        assert_eq!(billed_hours(901, 15), 0.5);
+        assert_eq!(billed_hours(0, 15), 0.0);
+    }
+    
+    #[test]
+    fn lines_round_each_entry_and_add_tax() {
+        let (mut data, client, project) = acme();
+        add_entry(&mut data, &project, at(2, 9, 0), at(2, 9, 50), None);
+        add_entry(&mut data, &project, at(3, 10, 0), at(3, 10, 20), None);
+        add_entry(&mut data, &project, at(4, 11, 0), at(4, 11, 7), Some("urgent"));
+        
+        let invoice = build_invoice(&data, &client, &september(), &options(19.0, 15), &Tz::UTC).unwrap();
+        
+        assert_eq!(invoice.currency, "EUR");
+        let lines: Vec<_> = invoice.lines.iter().map(|line| (line.description.as_str(), line.hours, line.amount)).collect();
+        assert_eq!(lines, [("Web", 1.5, 150.0), ("Web (+urgent)", 0.25, 37.5)]);
+        assert_eq!(invoice.subtotal, 187.5);
+        assert_eq!(invoice.tax, 35.63);
+        assert_eq!(invoice.total, 223.13);
+        assert_eq!(invoice.entry_ids.len(), 3);
+    }
+    
+    #[test]
+    fn exact_time_is_rounded_to_cents() {
+        let (mut data, client, project) = acme();
+        add_entry(&mut data, &project, at(2, 9, 0), at(2, 9, 50), None);
+        
+        let invoice = build_invoice(&data, &client, &september(), &options(0.0, 0), &Tz::UTC).unwrap();
+        
+        assert_eq!(invoice.subtotal, 83.33);
+        assert_eq!(invoice.tax, 0.0);
+        assert_eq!(invoice.total, 83.33);
+    }
+    
+    #[test]
+    fn skips_non_billable_invoiced_and_out_of_range_entries() {
+        let (mut data, client, project) = acme();
+        let billed = add_entry(&mut data, &project, at(2, 9, 0), at(2, 10, 0), None);
+        let free = add_entry(&mut data, &project, at(3, 9, 0), at(3, 10, 0), None);
+        add_entry(&mut data, &project, Utc.with_ymd_and_hms(2025, 10, 1, 9, 0, 0).unwrap(), Utc.with_ymd_and_hms(2025, 10, 1, 10, 0, 0).unwrap(), None);
+        for entry in &mut data.time_entries {
+            if entry.id == billed {
+                entry.invoice_id = Some(Uuid::new_v4());
+            } else if entry.id == free {
+                entry.billable = false;
+            }
+        }
+        
+        let error = build_invoice(&data, &client, &september(), &options(0.0, 0), &Tz::UTC).unwrap_err();
+        assert!(error.to_string().contains("No uninvoiced billable time"));
+    }
+    
+    #[test]
+    fn entries_without_a_rate_are_refused() {
+        let (mut data, client, project) = acme();
+        data.rates.retain(|rate| !matches!(rate.scope, RateScope::Client(_)));
+        let id = add_entry(&mut data, &project, at(2, 9, 0), at(2, 10, 0), None);
+        
+        let error = build_invoice(&data, &client, &september(), &options(0.0, 0), &Tz::UTC).unwrap_err();
+        assert!(error.to_string().contains(&id.to_string()[..8]));
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{
+    error::TimeCardError,
+    models::{Leave, TimeCardData},
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use chrono_tz::Tz;
+
+/// Most days one calendar event may cover, so a broken DTEND can't fill
+/// years with leave.
+const MAX_EVENT_DAYS: i64 = 366;
+
+/// Hours a day of leave stands for: its own hours, or the day's target for
+/// a whole day off.
+pub fn leave_hours(data: &TimeCardData, leave: &Leave) -> f64 {
+    leave.hours.unwrap_or_else(|| data.settings.targets.date(leave.date).unwrap_or(0.0))
+}
+
+/// Hours of `target` that leave on `date` excuses, at most the whole
+/// target.
+pub fn excused_hours(data: &TimeCardData, date: NaiveDate, target: f64) -> f64 {
+    let excused: f64 = data.leave_on(date)
+        .filter(|leave| leave.kind.excuses_target())
+        .map(|leave| leave.hours.unwrap_or(target))
+        .sum();
+    excused.min(target)
+}
+
+/// Leave on the days `first..=last`, by date.
+pub fn leave_between(data: &TimeCardData, first: NaiveDate, last: NaiveDate) -> Vec<&Leave> {
+    let mut leave: Vec<_> = data.leave
+        .iter()
+        .filter(|leave| (first..=last).contains(&leave.date))
+        .collect();
+    leave.sort_by_key(|leave| (leave.date, leave.kind));
+    leave
+}
+
+/// An all-day event read from an iCalendar file.
+#[derive(Debug, Clone)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub summary: Option<String>,
+}
+
+/// The days covered by the events of an iCalendar (`.ics`) file, e.g. a
+/// published list of public holidays. Events spanning several days give one
+/// day each, up to a year; timed events count for the local day in `tz`
+/// they start. Recurrence rules aren't expanded, so a yearly event only
+/// gives its first date.
+pub fn parse_ics(text: &str, tz: &Tz) -> anyhow::Result<Vec<CalendarDay>> {
+    let mut days = Vec::new();
+    let mut event: Option<(Option<IcsDate>, Option<IcsDate>, Option<String>)> = None;
+    
+    for line in unfold(text) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Parameters like `;VALUE=DATE` follow the property name
+        let property = name.split(';').next().unwrap_or(name).to_uppercase();
+        
+        match (property.as_str(), value.trim()) {
+            ("BEGIN", "VEVENT") => event = Some((None, None, None)),
+            ("END", "VEVENT") => {
+                let Some((start, end, summary)) = event.take() else {
+                    continue;
+                };
+                let (start, last) = match (start, end) {
+                    (None, _) => return Err(invalid_ics("an event has no DTSTART")),
+                    // DTEND of an all-day event is exclusive
+                    (Some(IcsDate::Day(start)), Some(IcsDate::Day(end))) => (start, (end - Duration::days(1)).max(start)),
+                    (Some(start), _) => (start.date(), start.date()),
+                };
+                if (last - start).num_days() >= MAX_EVENT_DAYS {
+                    return Err(invalid_ics(format!("an event from {} to {} spans more than a year", start, last)));
+                }
+                for date in start.iter_days().take_while(|date| *date <= last) {
+                    days.push(CalendarDay { date, summary: summary.clone() });
+                }
+            }
+            ("DTSTART", value) => {
+                if let Some(event) = &mut event {
+                    event.0 = Some(ics_date(value, tz)?);
+                }
+            }
+            ("DTEND", value) => {
+                if let Some(event) = &mut event {
+                    event.1 = Some(ics_date(value, tz)?);
+                }
+            }
+            ("SUMMARY", value) => {
+                if let Some(event) = &mut event {
+                    event.2 = Some(unescape(value)).filter(|summary| !summary.is_empty());
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    if days.is_empty() {
+        return Err(invalid_ics("it contains no events"));
+    }
+    days.sort_by_key(|day| day.date);
+    Ok(days)
+}
+
+/// Lines with folded continuations (starting with a space or tab) joined
+/// back on.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// A `DATE` value, or the local day of a `DATE-TIME` value.
+#[derive(Debug, Clone, Copy)]
+enum IcsDate {
+    Day(NaiveDate),
+    Time(NaiveDate),
+}
+
+impl IcsDate {
+    fn date(self) -> NaiveDate {
+        match self {
+            IcsDate::Day(date) | IcsDate::Time(date) => date,
+        }
+    }
+}
+
+/// Read a `DATE` (`20251225`) or `DATE-TIME` value. UTC times
+/// (`20251225T090000Z`) are converted to `tz`; other times are already
+/// local, so their date is taken as written.
+fn ics_date(value: &str, tz: &Tz) -> anyhow::Result<IcsDate> {
+    let invalid = || invalid_ics(format!("invalid date {}", value));
+    if let Some(utc) = value.strip_suffix(['Z', 'z']) {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        return Ok(IcsDate::Time(time.and_utc().with_timezone(tz).date_naive()));
+    }
+    let date = value.get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(invalid)?;
+    Ok(if value.len() > 8 { IcsDate::Time(date) } else { IcsDate::Day(date) })
+}
+
+fn unescape(value: &str) -> String {
+    let mut text = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => text.push(' '),
+                Some(escaped) => text.push(escaped),
+                None => {}
+            },
+            c => text.push(c),
+        }
+    }
+    text.trim().to_string()
+}
+
+fn invalid_ics(reason: impl std::fmt::Display) -> anyhow::Error {
+    TimeCardError::InvalidInput(format!("Not a usable iCalendar file: {}", reason)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+    
+    fn calendar(events: &[&str]) -> String {
+        let events: String = events
+            .iter()
+            .map(|event| format!("BEGIN:VEVENT\r\n{}\r\nEND:VEVENT\r\n", event.replace('\n', "\r\n")))
+            .collect();
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", events)
+    }
+    
+    fn dates(days: &[CalendarDay]) -> Vec<NaiveDate> {
+        days.iter().map(|day| day.date).collect()
+    }
+    
+    #[test]
+    fn all_day_events_end_the_day_before_dtend() {
+        let text = calendar(&[
+            "DTSTART;VALUE=DATE:20251225\nDTEND;VALUE=DATE:20251227\nSUMMARY:Christmas",
+            "DTSTART;VALUE=DATE:20251003\nSUMMARY:German Unity\\, Day",
+        ]);
+        
+        let days = parse_ics(&text, &Tz::UTC).unwrap();
+        
+        assert_eq!(dates(&days), [date(2025, 10, 3), date(2025, 12, 25), date(2025, 12, 26)]);
+        assert_eq!(days[0].summary.as_deref(), Some("German Unity, Day"));
+        assert_eq!(days[1].summary.as_deref(), Some("Christmas"));
+    }
+    
+    #[test]
+    fn folded_lines_are_joined() {
+        let text = calendar(&["DTSTART;VALUE=DATE:20250501\nSUMMARY:Labour\n  Day"]);
+        
+        let days = parse_ics(&text, &Tz::UTC).unwrap();
+        
+        assert_eq!(days[0].summary.as_deref(), Some("Labour Day"));
+    }
+    
+    #[test]
+    fn utc_times_count_for_the_local_day() {
+        let text = calendar(&["DTSTART:20251224T230000Z\nDTEND:20251225T230000Z\nSUMMARY:Christmas"]);
+        
+        let berlin: Tz = "Europe/Berlin".parse().unwrap();
+        assert_eq!(dates(&parse_ics(&text, &berlin).unwrap()), [date(2025, 12, 25)]);
+        let new_york: Tz = "America/New_York".parse().unwrap();
+        assert_eq!(dates(&parse_ics(&text, &new_york).unwrap()), [date(2025, 12, 24)]);
+    }
+    
+    #[test]
+    fn floating_times_keep_their_date() {
+        let text = calendar(&["DTSTART;TZID=Europe/Berlin:20251224T230000\nSUMMARY:Eve"]);
+        
+        let new_york: Tz = "America/New_York".parse().unwrap();
+        assert_eq!(dates(&parse_ics(&text, &new_york).unwrap()), [date(2025, 12, 24)]);
+    }
+    
+    #[test]
+    fn year_long_events_are_refused() {
+        let text = calendar(&["DTSTART;VALUE=DATE:20250101\nDTEND;VALUE=DATE:20260103"]);
+        assert!(parse_ics(&text, &Tz::UTC).is_err());
+        
+        let text = calendar(&["DTSTART;VALUE=DATE:20250101\nDTEND;VALUE=DATE:20260102"]);
+        assert_eq!(parse_ics(&text, &Tz::UTC).unwrap().len(), MAX_EVENT_DAYS as usize);
+    }
+    
+    #[test]
+    fn unusable_files_are_refused() {
+        assert!(parse_ics("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n", &Tz::UTC).is_err());
+        assert!(parse_ics(&calendar(&["SUMMARY:No start"]), &Tz::UTC).is_err());
+        assert!(parse_ics(&calendar(&["DTSTART;VALUE=DATE:2025-12-25"]), &Tz::UTC).is_err());
+    }
+}
@@ -0,0 +1,21 @@
+//! Time tracking behind the `timecard` command line and GUI: the data
+//! model, storage backends, period parsing, report totals, leave, progress
+//! against hour targets, the flex-time balance and the [`TimeTracker`]
+//! service that clocks in and out and edits entries.
+
+pub mod balance;
+pub mod billing;
+pub mod datetime;
+pub mod doctor;
+pub mod error;
+pub mod invoice;
+pub mod leave;
+pub mod models;
+pub mod period;
+pub mod report;
+pub mod storage;
+pub mod targets;
+pub mod tracker;
+
+pub use error::TimeCardError;
+pub use tracker::TimeTracker;
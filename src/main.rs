@@ -1,15 +1,25 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
 use colored::*;
 use std::path::PathBuf;
+use std::process::ExitCode;
 
-mod models;
-mod storage;
 mod commands;
 mod gui;
+mod output;
+mod tui;
 
-use commands::{clock, report, status};
+use commands::{client, clock, config, edit, leave, project, rate, report, status, target};
 use gui::TimeCardApp;
+use output::OutputFormat;
 use egui::ViewportBuilder;
+use timecard::{
+    doctor::{self, FixStrategy},
+    invoice::InvoiceFormat,
+    models::{LeaveKind, TagFilter},
+    storage::{self, Backend},
+    tracker::{Activity, EntryChanges, TimeTracker},
+    TimeCardError,
+};
 
 #[derive(Parser)]
 #[command(name = "timecard")]
@@ -21,6 +31,14 @@ struct Cli {
     
     #[arg(short, long, default_value = "timecard.json")]
     data_file: PathBuf,
+    
+    /// Storage backend (default: guessed from the data file extension)
+    #[arg(short, long, value_enum)]
+    backend: Option<Backend>,
+    
+    /// Output format of status, list, report, balance, in, out and add
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    output: OutputFormat,
 }
 
 #[derive(Subcommand)]
@@ -32,12 +50,72 @@ enum Commands {
         
         #[arg(short, long)]
         description: Option<String>,
+        
+        /// Tag the entry (repeatable); `+tag` words in the description work too
+        #[arg(short, long)]
+        tag: Vec<String>,
+        
+        /// Don't bill this entry
+        #[arg(long)]
+        non_billable: bool,
+        
+        /// When it happened instead of now, e.g. 09:15, -15m, "20 minutes ago"
+        #[arg(long, allow_hyphen_values = true)]
+        at: Option<String>,
     },
     
     /// Clock out to stop tracking time
     Out {
         #[arg(short, long)]
         description: Option<String>,
+        
+        /// When it happened instead of now, e.g. 09:15, -15m, "20 minutes ago"
+        #[arg(long, allow_hyphen_values = true)]
+        at: Option<String>,
+    },
+    
+    /// Stop the active entry and start another at the same moment
+    Switch {
+        #[arg(short, long)]
+        project: Option<String>,
+        
+        #[arg(short, long)]
+        description: Option<String>,
+        
+        /// Tag the entry (repeatable); `+tag` words in the description work too
+        #[arg(short, long)]
+        tag: Vec<String>,
+        
+        /// Don't bill this entry
+        #[arg(long)]
+        non_billable: bool,
+        
+        /// When it happened instead of now, e.g. 09:15, -15m, "20 minutes ago"
+        #[arg(long, allow_hyphen_values = true)]
+        at: Option<String>,
+    },
+    
+    /// Start a new entry like the last one, or like a given entry
+    Continue {
+        /// Entry id or unique prefix, as shown by `list`
+        id: Option<String>,
+    },
+    
+    /// Discard the active entry
+    Cancel,
+    
+    /// Start a break in the active entry
+    Pause {
+        /// When the break started instead of now
+        #[arg(long, allow_hyphen_values = true)]
+        at: Option<String>,
+    },
+    
+    /// End the current break
+    Resume {
+        /// When the break ended instead of now
+        #[arg(long, allow_hyphen_values = true)]
+        at: Option<String>,
     },
     
     /// Show current status
@@ -45,12 +123,30 @@ enum Commands {
     
     /// Generate reports
     Report {
+        /// Period expression, e.g. today, last-week, last-7-days, 2025-09,
+        /// 2025-W36, q3, ytd, last-year, "since 2025-01-01"
         #[arg(short, long, default_value = "today")]
         period: String,
         
-        #[arg(short, long)]
+        /// Start of a custom range (date or period expression)
+        #[arg(long, conflicts_with = "period")]
+        from: Option<String>,
+        
+        /// End of a custom range (date or period expression)
+        #[arg(long, conflicts_with = "period")]
+        to: Option<String>,
+        
+        #[arg(long)]
         project: Option<String>,
         
+        /// Only entries with this tag (repeatable, all must match)
+        #[arg(short, long)]
+        tag: Vec<String>,
+        
+        /// Skip entries with this tag (repeatable)
+        #[arg(long)]
+        not_tag: Vec<String>,
+        
         #[arg(short, long)]
         csv: bool,
     },
@@ -62,6 +158,14 @@ enum Commands {
         
         #[arg(short, long)]
         limit: Option<usize>,
+        
+        /// Only entries with this tag (repeatable, all must match)
+        #[arg(short, long)]
+        tag: Vec<String>,
+        
+        /// Skip entries with this tag (repeatable)
+        #[arg(long)]
+        not_tag: Vec<String>,
     },
     
     /// Add manual time entry
@@ -72,52 +176,758 @@ enum Commands {
         #[arg(short, long)]
         description: Option<String>,
         
-        #[arg(short, long)]
+        #[arg(short, long, allow_hyphen_values = true)]
         start: String,
         
-        #[arg(short, long)]
+        #[arg(short, long, allow_hyphen_values = true)]
         end: String,
+        
+        /// Tag the entry (repeatable); `+tag` words in the description work too
+        #[arg(short, long)]
+        tag: Vec<String>,
+        
+        /// Don't bill this entry
+        #[arg(long)]
+        non_billable: bool,
+    },
+    
+    /// Edit an existing time entry
+    Edit {
+        /// Entry id or unique prefix, as shown by `list`
+        id: String,
+        
+        #[arg(short, long, conflicts_with = "no_project")]
+        project: Option<String>,
+        
+        /// Remove the entry's project
+        #[arg(long)]
+        no_project: bool,
+        
+        /// New description (empty string clears it)
+        #[arg(short, long)]
+        description: Option<String>,
+        
+        /// Add a tag (repeatable)
+        #[arg(short, long)]
+        tag: Vec<String>,
+        
+        /// Remove a tag (repeatable)
+        #[arg(long)]
+        untag: Vec<String>,
+        
+        /// Mark the entry billable or not
+        #[arg(long)]
+        billable: Option<bool>,
+        
+        #[arg(short, long, allow_hyphen_values = true)]
+        start: Option<String>,
+        
+        #[arg(short, long, allow_hyphen_values = true)]
+        end: Option<String>,
+    },
+    
+    /// Delete a time entry
+    Delete {
+        /// Entry id or unique prefix, as shown by `list`
+        id: String,
+    },
+    
+    /// Manage projects
+    Project {
+        #[command(subcommand)]
+        action: ProjectCommands,
+    },
+    
+    /// Manage clients
+    Client {
+        #[command(subcommand)]
+        action: ClientCommands,
+    },
+    
+    /// Manage hourly rates
+    Rate {
+        #[command(subcommand)]
+        action: RateCommands,
+    },
+    
+    /// Set hours to work per day and week and project budgets, or show
+    /// progress against them
+    Target {
+        #[command(subcommand)]
+        action: Option<TargetCommands>,
+    },
+    
+    /// Record vacation, sick days, public holidays and comp time
+    Leave {
+        #[command(subcommand)]
+        action: LeaveCommands,
+    },
+    
+    /// Show the flex-time balance: hours worked above or below the day
+    /// targets, month by month
+    Balance {
+        /// Show the day-by-day statement of the month a period expression
+        /// falls in, e.g. last-month or 2025-09
+        #[arg(short, long)]
+        month: Option<String>,
+    },
+    
+    /// Create invoices from billable time, or manage existing ones
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Invoice {
+        #[command(flatten)]
+        create: Option<InvoiceArgs>,
+        
+        #[command(subcommand)]
+        action: Option<InvoiceCommands>,
+    },
+    
+    /// View or change settings
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigCommands>,
+    },
+    
+    /// Check the data for overlaps and other inconsistencies
+    Doctor {
+        /// Repair problems with these strategies (comma-separated or repeated)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        fix: Vec<FixStrategy>,
+        
+        /// When stray active entries ended, for --fix close
+        #[arg(long, allow_hyphen_values = true)]
+        at: Option<String>,
+        
+        /// Report entries longer than this many hours
+        #[arg(long, default_value_t = doctor::DEFAULT_MAX_HOURS, value_parser = clap::value_parser!(i64).range(1..))]
+        max_hours: i64,
+    },
+    
+    /// Copy all data into a new file using another storage backend
+    Migrate {
+        #[arg(long, value_enum)]
+        to: Backend,
+        
+        /// Target file (default: data file with the backend's extension)
+        #[arg(short, long)]
+        out_file: Option<PathBuf>,
+    },
+    
+    /// Manage backups of the data file
+    Backup {
+        #[command(subcommand)]
+        action: BackupCommands,
     },
     
     /// Launch GUI interface
     Gui,
+    
+    /// Interactive terminal interface, e.g. over SSH
+    Tui,
+}
+
+impl Commands {
+    /// Commands that may change the data file and get an automatic backup
+    /// first.
+    fn is_mutating(&self) -> bool {
+        match self {
+            Commands::In { .. }
+            | Commands::Out { .. }
+            | Commands::Switch { .. }
+            | Commands::Continue { .. }
+            | Commands::Cancel
+            | Commands::Pause { .. }
+            | Commands::Resume { .. }
+            | Commands::Add { .. }
+            | Commands::Edit { .. }
+            | Commands::Delete { .. } => true,
+            Commands::Project { action } => !matches!(action, ProjectCommands::List { .. }),
+            Commands::Client { action } => !matches!(action, ClientCommands::List),
+            Commands::Rate { action } => !matches!(action, RateCommands::List),
+            Commands::Leave { action } => !matches!(action, LeaveCommands::List { .. }),
+            Commands::Target { action } => !matches!(action, None | Some(TargetCommands::Show)),
+            Commands::Invoice { create, action } => match action {
+                None => create.as_ref().is_some_and(|create| !create.dry_run),
+                Some(action) => matches!(action, InvoiceCommands::Void { .. }),
+            },
+            Commands::Config { action } => !matches!(action, None | Some(ConfigCommands::Show)),
+            Commands::Doctor { fix, .. } => !fix.is_empty(),
+            Commands::Backup { action } => matches!(action, BackupCommands::Restore { .. }),
+            Commands::Status
+            | Commands::Balance { .. }
+            | Commands::Report { .. }
+            | Commands::List { .. }
+            | Commands::Migrate { .. } => false,
+            // Sessions back up before their first change instead
+            Commands::Gui | Commands::Tui => false,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum BackupCommands {
+    /// List available backups, newest first
+    List,
+    
+    /// Create a backup now
+    Create,
+    
+    /// Replace the data with a backup
+    Restore {
+        /// Backup number from `backup list` (1 = newest) or file name
+        backup: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Show current settings
+    Show,
+    
+    /// Change a setting
+    Set {
+        key: String,
+        
+        value: String,
+    },
+    
+    /// Reset a setting to its default
+    Unset {
+        key: String,
+    },
+}
+
+#[derive(clap::Args)]
+struct InvoiceArgs {
+    /// Client to bill
+    #[arg(short, long)]
+    client: String,
+    
+    /// Period expression, as for `report`
+    #[arg(short, long, default_value = "last-month")]
+    period: String,
+    
+    /// Start of a custom range (date or period expression)
+    #[arg(long, conflicts_with = "period")]
+    from: Option<String>,
+    
+    /// End of a custom range (date or period expression)
+    #[arg(long, conflicts_with = "period")]
+    to: Option<String>,
+    
+    /// Tax rate in percent (default: the tax-rate setting)
+    #[arg(long)]
+    tax: Option<f64>,
+    
+    /// Round each entry up to this many minutes (default: the invoice-rounding setting)
+    #[arg(long)]
+    round: Option<u32>,
+    
+    #[arg(short, long, value_enum, default_value = "text")]
+    format: InvoiceFormat,
+    
+    /// Write the invoice to a file instead of the terminal
+    #[arg(short, long)]
+    out_file: Option<PathBuf>,
+    
+    /// Preview the invoice without numbering it or marking entries
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Subcommand)]
+enum InvoiceCommands {
+    /// List invoices
+    List,
+    
+    /// Render an existing invoice
+    Show {
+        /// Invoice number, e.g. INV-0001
+        number: String,
+        
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: InvoiceFormat,
+        
+        /// Write the invoice to a file instead of the terminal
+        #[arg(short, long)]
+        out_file: Option<PathBuf>,
+    },
+    
+    /// Cancel an invoice so its entries can be billed again
+    Void {
+        number: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ClientCommands {
+    /// Add a new client
+    Add {
+        name: String,
+        
+        /// Currency of the client's rates (default: the currency setting)
+        #[arg(short, long)]
+        currency: Option<String>,
+    },
+    
+    /// List clients and their projects
+    List,
+    
+    /// Rename a client
+    Rename {
+        name: String,
+        
+        new_name: String,
+    },
+    
+    /// Delete a client and its rates
+    Delete {
+        name: String,
+        
+        /// Delete even if projects belong to it, unassigning them
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum RateCommands {
+    /// Set an hourly rate for a client, project or tag
+    #[command(group(ArgGroup::new("target").required(true)))]
+    Set {
+        /// Amount per hour
+        amount: f64,
+        
+        #[arg(long, group = "target")]
+        client: Option<String>,
+        
+        #[arg(long, group = "target")]
+        project: Option<String>,
+        
+        #[arg(long, group = "target")]
+        tag: Option<String>,
+        
+        /// Currency (default: the client's currency)
+        #[arg(short, long)]
+        currency: Option<String>,
+        
+        /// Date the rate takes effect (default: today)
+        #[arg(short, long)]
+        from: Option<String>,
+    },
+    
+    /// List rates
+    List,
+    
+    /// Remove a rate
+    Remove {
+        /// Rate id or unique prefix, as shown by `rate list`
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum LeaveCommands {
+    /// Take a day off, some hours off, or several days off
+    Add {
+        /// Day off, or the first of several
+        date: String,
+        
+        /// Last day off; days without a target in between are skipped
+        #[arg(long)]
+        to: Option<String>,
+        
+        #[arg(short = 't', long = "type", value_enum)]
+        kind: LeaveKind,
+        
+        /// Hours off each day instead of the whole day
+        #[arg(long)]
+        hours: Option<f64>,
+        
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+    
+    /// List leave
+    List {
+        /// Period expression, e.g. year, last-month or 2025
+        #[arg(short, long, default_value = "year")]
+        period: String,
+    },
+    
+    /// Remove a day of leave
+    Remove {
+        /// Leave id or unique prefix, as shown by `leave list`
+        id: String,
+    },
+    
+    /// Record the days of an iCalendar (.ics) file's events, e.g. public
+    /// holidays
+    Import {
+        file: PathBuf,
+        
+        #[arg(short = 't', long = "type", value_enum, default_value = "holiday")]
+        kind: LeaveKind,
+    },
 }
 
-fn main() -> anyhow::Result<()> {
+#[derive(Subcommand)]
+enum TargetCommands {
+    /// Show targets and progress against them
+    Show,
+    
+    /// Set hours for days of the week, the week or a project
+    #[command(group(ArgGroup::new("target").required(true)))]
+    Set {
+        hours: f64,
+        
+        /// Days of the week, e.g. mon,tue, friday, weekdays, weekend or all
+        #[arg(long, group = "target")]
+        day: Option<String>,
+        
+        /// Hours per week (default: the sum of the day targets)
+        #[arg(long, group = "target")]
+        week: bool,
+        
+        /// Hours budgeted for a project in total
+        #[arg(long, group = "target")]
+        project: Option<String>,
+    },
+    
+    /// Remove a target
+    #[command(group(ArgGroup::new("target").required(true)))]
+    Unset {
+        #[arg(long, group = "target")]
+        day: Option<String>,
+        
+        #[arg(long, group = "target")]
+        week: bool,
+        
+        #[arg(long, group = "target")]
+        project: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProjectCommands {
+    /// Add a new project
+    Add {
+        name: String,
+        
+        #[arg(short, long)]
+        description: Option<String>,
+        
+        /// Client the project is billed to
+        #[arg(short, long)]
+        client: Option<String>,
+    },
+    
+    /// List projects
+    List {
+        /// Include archived projects
+        #[arg(short, long)]
+        all: bool,
+    },
+    
+    /// Rename a project
+    Rename {
+        name: String,
+        
+        new_name: String,
+    },
+    
+    /// Archive a project so it can no longer be clocked into
+    Archive {
+        name: String,
+        
+        /// Restore an archived project instead
+        #[arg(short, long)]
+        undo: bool,
+    },
+    
+    /// Assign a project to a client
+    SetClient {
+        name: String,
+        
+        /// Client name; omit to unassign
+        client: Option<String>,
+    },
+    
+    /// Delete a project
+    Delete {
+        name: String,
+        
+        /// Delete even if time entries reference it, unassigning them
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+/// Exit codes: 0 on success, 2 for usage errors, the code of the
+/// `TimeCardError` for known failures and 1 for anything else.
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{} {:#}", "Error:".red(), e);
+            ExitCode::from(TimeCardError::of(&e).map_or(1, TimeCardError::exit_code))
+        }
+    }
+}
+
+fn run() -> anyhow::Result<()> {
     let cli = Cli::parse();
     
-    println!("{}", "🕐 TimeCard - Simple Time Tracking".green().bold());
-    println!();
+    // Machine output is for scripts: no banner, no colors
+    if cli.output.is_machine() {
+        colored::control::set_override(false);
+    } else {
+        println!("{}", "🕐 TimeCard - Simple Time Tracking".green().bold());
+        println!();
+    }
+    
+    // Opening upgrades an older file under its own exclusive lock, so it
+    // comes before the command's lock
+    let mut tracker = TimeTracker::open(&cli.data_file, cli.backend)?;
+    
+    // The GUI and TUI are long-lived and lock around each of their own changes
+    let _lock = match &cli.command {
+        Commands::Gui | Commands::Tui => None,
+        command if command.is_mutating() => Some(storage::lock::lock_exclusive(&cli.data_file)?),
+        _ => Some(storage::lock::lock_shared(&cli.data_file)?),
+    };
+    
+    // Restoring backs up on its own, after picking the backup to restore
+    if cli.command.is_mutating() && !matches!(cli.command, Commands::Backup { .. }) {
+        tracker.back_up_once()?;
+    }
     
     match cli.command {
-        Commands::In { project, description } => {
-            clock::clock_in(&cli.data_file, project, description)?;
+        Commands::In { project, description, tag, non_billable, at } => {
+            let activity = Activity { project, description, tags: tag, billable: !non_billable };
+            clock::clock_in(&mut tracker, activity, at.as_deref(), cli.output)?;
         }
-        Commands::Out { description } => {
-            clock::clock_out(&cli.data_file, description)?;
+        Commands::Out { description, at } => {
+            clock::clock_out(&mut tracker, description, at.as_deref(), cli.output)?;
+        }
+        Commands::Switch { project, description, tag, non_billable, at } => {
+            let activity = Activity { project, description, tags: tag, billable: !non_billable };
+            clock::switch(&mut tracker, activity, at.as_deref())?;
+        }
+        Commands::Continue { id } => {
+            clock::continue_entry(&mut tracker, id.as_deref())?;
+        }
+        Commands::Cancel => {
+            clock::cancel(&mut tracker)?;
+        }
+        Commands::Pause { at } => {
+            clock::pause(&mut tracker, at.as_deref())?;
+        }
+        Commands::Resume { at } => {
+            clock::resume(&mut tracker, at.as_deref())?;
         }
         Commands::Status => {
-            status::show_status(&cli.data_file)?;
+            status::show_status(tracker.store(), cli.output)?;
+        }
+        Commands::Report { period, from, to, project, tag, not_tag, csv } => {
+            let request = report::ReportRequest {
+                period,
+                from,
+                to,
+                project,
+                tag_filter: TagFilter::new(&tag, &not_tag)?,
+                csv,
+            };
+            report::generate_report(tracker.store(), request, cli.output)?;
+        }
+        Commands::List { project, limit, tag, not_tag } => {
+            commands::list::list_entries(
+                tracker.store(),
+                project.as_deref(),
+                &TagFilter::new(&tag, &not_tag)?,
+                limit,
+                cli.output,
+            )?;
         }
-        Commands::Report { period, project, csv } => {
-            report::generate_report(&cli.data_file, &period, project.as_deref(), csv)?;
+        Commands::Add { project, description, start, end, tag, non_billable } => {
+            let activity = Activity { project, description, tags: tag, billable: !non_billable };
+            commands::add::add_entry(&mut tracker, activity, &start, &end, cli.output)?;
         }
-        Commands::List { project, limit } => {
-            commands::list::list_entries(&cli.data_file, project.as_deref(), limit)?;
+        Commands::Edit { id, project, no_project, description, tag, untag, billable, start, end } => {
+            let request = edit::EditRequest {
+                changes: EntryChanges {
+                    project,
+                    clear_project: no_project,
+                    description,
+                    add_tags: tag,
+                    remove_tags: untag,
+                    billable,
+                    ..EntryChanges::default()
+                },
+                start,
+                end,
+            };
+            edit::edit_entry(&mut tracker, &id, request)?;
         }
-        Commands::Add { project, description, start, end } => {
-            commands::add::add_entry(&cli.data_file, project, description, &start, &end)?;
+        Commands::Delete { id } => {
+            edit::delete_entry(&mut tracker, &id)?;
         }
+        Commands::Project { action } => match action {
+            ProjectCommands::Add { name, description, client } => {
+                project::add_project(tracker.store(), &name, description, client.as_deref())?;
+            }
+            ProjectCommands::List { all } => {
+                project::list_projects(tracker.store(), all)?;
+            }
+            ProjectCommands::Rename { name, new_name } => {
+                project::rename_project(tracker.store(), &name, &new_name)?;
+            }
+            ProjectCommands::Archive { name, undo } => {
+                project::archive_project(tracker.store(), &name, !undo)?;
+            }
+            ProjectCommands::SetClient { name, client } => {
+                project::set_project_client(tracker.store(), &name, client.as_deref())?;
+            }
+            ProjectCommands::Delete { name, force } => {
+                project::delete_project(tracker.store(), &name, force)?;
+            }
+        },
+        Commands::Client { action } => match action {
+            ClientCommands::Add { name, currency } => {
+                client::add_client(tracker.store(), &name, currency.as_deref())?;
+            }
+            ClientCommands::List => {
+                client::list_clients(tracker.store())?;
+            }
+            ClientCommands::Rename { name, new_name } => {
+                client::rename_client(tracker.store(), &name, &new_name)?;
+            }
+            ClientCommands::Delete { name, force } => {
+                client::delete_client(tracker.store(), &name, force)?;
+            }
+        },
+        Commands::Rate { action } => match action {
+            RateCommands::Set { amount, client, project, tag, currency, from } => {
+                let target = match (client, project, tag) {
+                    (Some(client), _, _) => rate::RateTarget::Client(client),
+                    (_, Some(project), _) => rate::RateTarget::Project(project),
+                    (_, _, Some(tag)) => rate::RateTarget::Tag(tag),
+                    _ => unreachable!("clap requires one rate target"),
+                };
+                rate::set_rate(tracker.store(), target, amount, currency.as_deref(), from.as_deref())?;
+            }
+            RateCommands::List => {
+                rate::list_rates(tracker.store())?;
+            }
+            RateCommands::Remove { id } => {
+                rate::remove_rate(tracker.store(), &id)?;
+            }
+        },
+        Commands::Target { action } => match action.unwrap_or(TargetCommands::Show) {
+            TargetCommands::Show => {
+                target::show_targets(tracker.store())?;
+            }
+            TargetCommands::Set { hours, day, week, project } => {
+                target::set_target(tracker.store(), target_scope(day, week, project), Some(hours))?;
+            }
+            TargetCommands::Unset { day, week, project } => {
+                target::set_target(tracker.store(), target_scope(day, week, project), None)?;
+            }
+        },
+        Commands::Leave { action } => match action {
+            LeaveCommands::Add { date, to, kind, hours, description } => {
+                let request = leave::LeaveRequest { kind, from: date, to, hours, description };
+                leave::add_leave(tracker.store(), request)?;
+            }
+            LeaveCommands::List { period } => {
+                leave::list_leave(tracker.store(), &period)?;
+            }
+            LeaveCommands::Remove { id } => {
+                leave::remove_leave(tracker.store(), &id)?;
+            }
+            LeaveCommands::Import { file, kind } => {
+                leave::import_leave(tracker.store(), &file, kind)?;
+            }
+        },
+        Commands::Balance { month } => {
+            commands::balance::show_balance(tracker.store(), month.as_deref(), cli.output)?;
+        }
+        Commands::Invoice { create, action } => match (action, create) {
+            (None, Some(create)) => {
+                let request = commands::invoice::InvoiceRequest {
+                    client: create.client,
+                    period: create.period,
+                    from: create.from,
+                    to: create.to,
+                    tax_rate: create.tax,
+                    rounding: create.round,
+                    format: create.format,
+                    out_file: create.out_file,
+                    dry_run: create.dry_run,
+                };
+                commands::invoice::create_invoice(tracker.store(), request)?;
+            }
+            (None, None) => unreachable!("clap requires --client without a subcommand"),
+            (Some(InvoiceCommands::List), _) => {
+                commands::invoice::list_invoices(tracker.store())?;
+            }
+            (Some(InvoiceCommands::Show { number, format, out_file }), _) => {
+                commands::invoice::show_invoice(tracker.store(), &number, format, out_file)?;
+            }
+            (Some(InvoiceCommands::Void { number }), _) => {
+                commands::invoice::void_invoice(tracker.store(), &number)?;
+            }
+        },
+        Commands::Config { action } => match action.unwrap_or(ConfigCommands::Show) {
+            ConfigCommands::Show => {
+                config::show_config(tracker.store())?;
+            }
+            ConfigCommands::Set { key, value } => {
+                config::set_config(tracker.store(), &key, Some(&value))?;
+            }
+            ConfigCommands::Unset { key } => {
+                config::set_config(tracker.store(), &key, None)?;
+            }
+        },
+        Commands::Doctor { fix, at, max_hours } => {
+            commands::doctor::run_doctor(tracker.store(), &fix, at.as_deref(), max_hours)?;
+        }
+        Commands::Migrate { to, out_file } => {
+            commands::migrate::migrate(tracker.store(), to, out_file)?;
+        }
+        Commands::Backup { action } => match action {
+            BackupCommands::List => {
+                commands::backup::list_backups(tracker.store())?;
+            }
+            BackupCommands::Create => {
+                commands::backup::create_backup(tracker.store())?;
+            }
+            BackupCommands::Restore { backup } => {
+                commands::backup::restore_backup(tracker.store(), &backup)?;
+            }
+        },
         Commands::Gui => {
-            launch_gui(&cli.data_file)?;
+            launch_gui(tracker)?;
+        }
+        Commands::Tui => {
+            tui::run(tracker)?;
         }
     }
     
     Ok(())
 }
 
-fn launch_gui(data_file: &std::path::Path) -> anyhow::Result<()> {
-    let data_file = data_file.to_path_buf();
+/// What `target set` or `target unset` was pointed at.
+fn target_scope(day: Option<String>, week: bool, project: Option<String>) -> target::TargetScope {
+    match (day, project) {
+        (Some(day), _) => target::TargetScope::Days(day),
+        (_, Some(project)) => target::TargetScope::Project(project),
+        _ if week => target::TargetScope::Week,
+        _ => unreachable!("clap requires one target"),
+    }
+}
+
+fn launch_gui(tracker: TimeTracker) -> anyhow::Result<()> {
     let options = eframe::NativeOptions {
         viewport: ViewportBuilder::default()
             .with_inner_size([800.0, 600.0]),
@@ -127,7 +937,7 @@ fn launch_gui(data_file: &std::path::Path) -> anyhow::Result<()> {
     eframe::run_native(
         "TimeCard",
         options,
-        Box::new(move |_cc| Box::new(TimeCardApp::new(data_file.clone()))),
+        Box::new(move |_cc| Box::new(TimeCardApp::new(tracker))),
     ).map_err(|e| anyhow::anyhow!("GUI error: {}", e))?;
     
     Ok(())
@@ -1,43 +1,312 @@
-use chrono::{DateTime, Utc, Datelike};
+use crate::{datetime, error::TimeCardError, storage::{backup, migrations}};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeEntry {
     pub id: Uuid,
-    pub project: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    /// The part of `tags` that came from `+tag` words in the description,
+    /// replaced whenever the description changes.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub description_tags: BTreeSet<String>,
+    #[serde(default = "default_billable")]
+    pub billable: bool,
+    /// Invoice this entry was billed on, so it isn't billed twice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoice_id: Option<Uuid>,
     pub start_time: DateTime<Utc>,
     pub end_time: Option<DateTime<Utc>>,
+    /// Breaks taken within the entry, oldest first. Only the last one of an
+    /// active entry may still be open.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pauses: Vec<Pause>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pause {
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+}
+
 impl TimeEntry {
-    pub fn new(project: Option<String>, description: Option<String>) -> Self {
+    pub fn new(project_id: Option<Uuid>, description: Option<String>) -> Self {
         let now = Utc::now();
-        Self {
+        let mut entry = Self {
             id: Uuid::new_v4(),
-            project,
-            description,
+            project_id,
+            description: None,
+            tags: BTreeSet::new(),
+            description_tags: BTreeSet::new(),
+            billable: true,
+            invoice_id: None,
             start_time: now,
             end_time: None,
+            pauses: Vec::new(),
             created_at: now,
             updated_at: now,
+        };
+        entry.set_description(description);
+        entry
+    }
+    
+    /// Set the description, moving any `+tag` words in it into the tags.
+    /// Tags the previous description brought in are dropped first, while
+    /// tags given on their own stay.
+    pub fn set_description(&mut self, description: Option<String>) {
+        let (description, tags) = match description {
+            Some(description) => split_tags(&description),
+            None => (None, BTreeSet::new()),
+        };
+        self.description = description;
+        for tag in std::mem::take(&mut self.description_tags) {
+            self.tags.remove(&tag);
+        }
+        for tag in tags {
+            if self.tags.insert(tag.clone()) {
+                self.description_tags.insert(tag);
+            }
         }
     }
     
+    /// Add tags given on their own, which a later description change keeps.
+    pub fn add_tags<'a>(&mut self, tags: impl IntoIterator<Item = &'a String>) -> anyhow::Result<()> {
+        for tag in tags {
+            let tag = normalize_tag(tag).ok_or_else(|| TimeCardError::InvalidInput(format!("Invalid tag: {}", tag)))?;
+            self.description_tags.remove(&tag);
+            self.tags.insert(tag);
+        }
+        Ok(())
+    }
+    
+    pub fn remove_tag(&mut self, tag: &str) {
+        self.tags.remove(tag);
+        self.description_tags.remove(tag);
+    }
+    
+    /// Tags as `+tag` words, for display.
+    pub fn format_tags(&self) -> String {
+        self.tags.iter().map(|tag| format!("+{}", tag)).collect::<Vec<_>>().join(" ")
+    }
+    
+    /// Worked time of a finished entry, breaks excluded.
     pub fn duration(&self) -> Option<chrono::Duration> {
+        self.end_time.map(|end| self.net_duration_until(end))
+    }
+    
+    /// Time from start to end, breaks included.
+    pub fn gross_duration(&self) -> Option<chrono::Duration> {
         self.end_time.map(|end| end - self.start_time)
     }
     
+    /// Time spent in breaks up to `until`, which is `now` for active entries.
+    pub fn break_duration_until(&self, until: DateTime<Utc>) -> chrono::Duration {
+        self.pauses
+            .iter()
+            .map(|pause| pause.end.unwrap_or(until).min(until) - pause.start.min(until))
+            .sum()
+    }
+    
+    /// Worked time up to `until`, for entries that may still be running.
+    pub fn net_duration_until(&self, until: DateTime<Utc>) -> chrono::Duration {
+        let end = self.end_time.unwrap_or(until).min(until);
+        (end - self.start_time) - self.break_duration_until(end)
+    }
+    
+    pub fn break_duration(&self) -> chrono::Duration {
+        self.break_duration_until(self.end_time.unwrap_or_else(Utc::now))
+    }
+    
     pub fn is_active(&self) -> bool {
         self.end_time.is_none()
     }
     
+    pub fn is_paused(&self) -> bool {
+        self.is_active() && self.pauses.last().is_some_and(|pause| pause.end.is_none())
+    }
+    
+    pub fn pause(&mut self, at: DateTime<Utc>) -> anyhow::Result<()> {
+        if !self.is_active() {
+            return Err(TimeCardError::InvalidInput("Only the active entry can be paused".to_string()).into());
+        }
+        if self.is_paused() {
+            return Err(TimeCardError::AlreadyPaused.into());
+        }
+        let earliest = self.pauses.last().and_then(|pause| pause.end).unwrap_or(self.start_time);
+        if at < earliest {
+            return Err(TimeCardError::InvalidDatetime(
+                "A pause can't start before the entry or the previous pause ended".to_string()
+            ).into());
+        }
+        self.pauses.push(Pause { start: at, end: None });
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    
+    pub fn resume(&mut self, at: DateTime<Utc>) -> anyhow::Result<()> {
+        if !self.is_paused() {
+            return Err(TimeCardError::NotPaused.into());
+        }
+        let pause = self.pauses.last_mut().unwrap();
+        if at < pause.start {
+            return Err(TimeCardError::InvalidDatetime("Can't resume before the pause started".to_string()).into());
+        }
+        pause.end = Some(at);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    
+    /// Finish the entry at `at`, ending a pause still in progress.
+    pub fn stop(&mut self, at: DateTime<Utc>) {
+        if let Some(pause) = self.pauses.last_mut().filter(|pause| pause.end.is_none()) {
+            pause.end = Some(at.max(pause.start));
+        }
+        self.end_time = Some(at);
+        self.updated_at = Utc::now();
+    }
+    
+    /// Check that every pause lies within the entry, for edits that move
+    /// its start or end.
+    pub fn check_pauses(&self) -> anyhow::Result<()> {
+        for pause in &self.pauses {
+            let pause_end = pause.end.or(self.end_time);
+            if pause.start < self.start_time
+                || self.end_time.is_some_and(|end| pause_end.unwrap_or(end) > end)
+            {
+                return Err(TimeCardError::InvalidDatetime(
+                    "The entry has breaks outside the new start and end times".to_string()
+                ).into());
+            }
+        }
+        Ok(())
+    }
+    
+    /// Move the entry and its pauses by `by`, keeping their lengths.
+    pub fn shift(&mut self, by: Duration) {
+        self.start_time += by;
+        self.end_time = self.end_time.map(|end| end + by);
+        for pause in &mut self.pauses {
+            pause.start += by;
+            pause.end = pause.end.map(|end| end + by);
+        }
+    }
+    
     pub fn hours(&self) -> Option<f64> {
         self.duration().map(|d| d.num_seconds() as f64 / 3600.0)
     }
+    
+    /// Abbreviated id shown in listings and accepted by `edit`/`delete`.
+    pub fn short_id(&self) -> String {
+        self.id.to_string()[..8].to_string()
+    }
+}
+
+fn default_billable() -> bool {
+    true
+}
+
+/// Id of the one item whose id starts with `prefix`, as typed from the short
+/// ids in listings. `kind` and `plural` name the items in errors.
+fn find_by_id_prefix<T>(
+    items: &[T],
+    id_of: impl Fn(&T) -> Uuid,
+    prefix: &str,
+    kind: &str,
+    plural: &str,
+) -> anyhow::Result<Uuid> {
+    let prefix = prefix.trim().to_lowercase();
+    if prefix.len() < 4 {
+        return Err(TimeCardError::InvalidInput(format!("Id prefix must be at least 4 characters: {}", prefix)).into());
+    }
+    
+    let matches: Vec<Uuid> = items
+        .iter()
+        .map(id_of)
+        .filter(|id| id.to_string().starts_with(&prefix))
+        .collect();
+    
+    match matches.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(TimeCardError::NotFound(format!("No {} matches id: {}", kind, prefix)).into()),
+        _ => Err(TimeCardError::InvalidInput(format!(
+            "Ambiguous id {} matches {} {}: {}",
+            prefix,
+            matches.len(),
+            plural,
+            matches.iter().map(|id| id.to_string()[..8].to_string()).collect::<Vec<_>>().join(", ")
+        )).into()),
+    }
+}
+
+/// Add up hours. Unlike `Iterator::sum`, no hours at all give 0.0 rather
+/// than -0.0, which would print as "-0.00".
+pub fn sum_hours(hours: impl IntoIterator<Item = f64>) -> f64 {
+    hours.into_iter().fold(0.0, |total, hours| total + hours)
+}
+
+/// Canonical form of a tag: lowercase, without a leading `+`. Tags may
+/// contain letters, digits, `-`, `_`, `:` and `/`.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('+').to_lowercase();
+    let valid = !tag.is_empty()
+        && tag.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '/'));
+    valid.then_some(tag)
+}
+
+/// Split `+tag` words out of a description. Returns the remaining text (if
+/// any) and the tags found.
+pub fn split_tags(description: &str) -> (Option<String>, BTreeSet<String>) {
+    let mut tags = BTreeSet::new();
+    let mut words = Vec::new();
+    for word in description.split_whitespace() {
+        match word.strip_prefix('+').and_then(normalize_tag) {
+            Some(tag) => {
+                tags.insert(tag);
+            }
+            None => words.push(word),
+        }
+    }
+    let text = words.join(" ");
+    ((!text.is_empty()).then_some(text), tags)
+}
+
+/// Entries must carry every `include` tag and none of the `exclude` tags.
+#[derive(Debug, Clone, Default)]
+pub struct TagFilter {
+    pub include: BTreeSet<String>,
+    pub exclude: BTreeSet<String>,
+}
+
+impl TagFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> anyhow::Result<Self> {
+        let normalize = |tags: &[String]| -> anyhow::Result<BTreeSet<String>> {
+            let tags = tags.iter()
+                .map(|tag| normalize_tag(tag).ok_or_else(|| TimeCardError::InvalidInput(format!("Invalid tag: {}", tag))))
+                .collect::<Result<_, _>>()?;
+            Ok(tags)
+        };
+        Ok(Self {
+            include: normalize(include)?,
+            exclude: normalize(exclude)?,
+        })
+    }
+    
+    pub fn matches(&self, entry: &TimeEntry) -> bool {
+        self.include.is_subset(&entry.tags) && self.exclude.is_disjoint(&entry.tags)
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +314,13 @@ pub struct Project {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    #[serde(default)]
+    pub client_id: Option<Uuid>,
+    #[serde(default)]
+    pub archived: bool,
+    /// Hours budgeted for the whole project. Unset means no budget.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_hours: Option<f64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -56,16 +332,301 @@ impl Project {
             id: Uuid::new_v4(),
             name,
             description,
+            client_id: None,
+            archived: false,
+            budget_hours: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+    
+    /// Project names are unique ignoring case and surrounding whitespace.
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.name.to_lowercase() == name.trim().to_lowercase()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Client {
+    pub id: Uuid,
+    pub name: String,
+    /// Currency for this client's rates. Unset means the default currency.
+    pub currency: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Client {
+    pub fn new(name: String, currency: Option<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            currency,
             created_at: now,
             updated_at: now,
         }
     }
+    
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.name.to_lowercase() == name.trim().to_lowercase()
+    }
+}
+
+/// What an hourly rate applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "value")]
+pub enum RateScope {
+    Client(Uuid),
+    Project(Uuid),
+    Tag(String),
+}
+
+/// An hourly rate that applies from `effective_from` until a later rate for
+/// the same scope takes over, so raising a rate doesn't change past reports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rate {
+    pub id: Uuid,
+    pub scope: RateScope,
+    pub hourly_rate: f64,
+    pub currency: String,
+    pub effective_from: NaiveDate,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Rate {
+    pub fn new(scope: RateScope, hourly_rate: f64, currency: String, effective_from: NaiveDate) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            scope,
+            hourly_rate,
+            currency,
+            effective_from,
+            created_at: Utc::now(),
+        }
+    }
+    
+    pub fn short_id(&self) -> String {
+        self.id.to_string()[..8].to_string()
+    }
+}
+
+/// Why a day, or part of it, isn't worked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaveKind {
+    Vacation,
+    Sick,
+    /// Public holiday
+    Holiday,
+    /// Time off taken from the flex balance
+    CompTime,
+}
+
+impl LeaveKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            LeaveKind::Vacation => "vacation",
+            LeaveKind::Sick => "sick",
+            LeaveKind::Holiday => "holiday",
+            LeaveKind::CompTime => "comp-time",
+        }
+    }
+    
+    /// Whether the hours off are excused from the day's target. Comp time
+    /// isn't: it is paid for with hours worked earlier.
+    pub fn excuses_target(self) -> bool {
+        self != LeaveKind::CompTime
+    }
+}
+
+/// A day off, or some hours off on a day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Leave {
+    pub id: Uuid,
+    pub date: NaiveDate,
+    pub kind: LeaveKind,
+    /// Hours off. Unset means the whole day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hours: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Leave {
+    pub fn new(date: NaiveDate, kind: LeaveKind, hours: Option<f64>, description: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            date,
+            kind,
+            hours,
+            description,
+            created_at: Utc::now(),
+        }
+    }
+    
+    pub fn short_id(&self) -> String {
+        self.id.to_string()[..8].to_string()
+    }
+}
+
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+pub const DEFAULT_INVOICE_PREFIX: &str = "INV-";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceLine {
+    pub description: String,
+    pub hours: f64,
+    pub hourly_rate: f64,
+    pub amount: f64,
+}
+
+/// A numbered invoice for a client's billable time. Amounts are fixed when
+/// the invoice is created, so later rate changes don't alter it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invoice {
+    pub id: Uuid,
+    /// Sequence number the displayed number is built from.
+    pub sequence: u32,
+    pub number: String,
+    pub client_id: Uuid,
+    pub client_name: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub issued_on: NaiveDate,
+    pub currency: String,
+    pub lines: Vec<InvoiceLine>,
+    pub subtotal: f64,
+    /// Tax rate in percent.
+    pub tax_rate: f64,
+    pub tax: f64,
+    pub total: f64,
+    pub entry_ids: Vec<Uuid>,
+    /// Voided invoices keep their number but release their entries.
+    #[serde(default)]
+    pub void: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Settings {
+    /// IANA timezone used to read and display local times. Unset means the
+    /// system timezone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Number of automatic backups to keep. Unset means the default; zero
+    /// disables backups.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_retention: Option<usize>,
+    /// Currency for rates of clients without their own currency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// Tax rate in percent added to invoices. Unset means no tax.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tax_rate: Option<f64>,
+    /// Minutes each invoiced entry is rounded up to. Unset means exact time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoice_rounding: Option<u32>,
+    /// Text in front of invoice numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoice_prefix: Option<String>,
+    /// Contracted hours per day and week.
+    #[serde(default, skip_serializing_if = "HourTargets::is_empty")]
+    pub targets: HourTargets,
+    /// First day of the flex-time balance. Unset means the day of the first
+    /// entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_start: Option<NaiveDate>,
+    /// Most flex hours carried into the next month. Unset means no cap.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_cap: Option<f64>,
+}
+
+/// Hours to work per day of the week and per week. Unset targets aren't
+/// tracked.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HourTargets {
+    /// Hours per day of the week, Monday first
+    #[serde(default)]
+    pub days: [Option<f64>; 7],
+    /// Hours per week. Unset means the sum of the day targets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub week: Option<f64>,
+}
+
+impl HourTargets {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+    
+    pub fn day(&self, weekday: Weekday) -> Option<f64> {
+        self.days[weekday.num_days_from_monday() as usize]
+    }
+    
+    pub fn set_day(&mut self, weekday: Weekday, hours: Option<f64>) {
+        self.days[weekday.num_days_from_monday() as usize] = hours;
+    }
+    
+    /// Hours of a date, by its day of the week.
+    pub fn date(&self, date: NaiveDate) -> Option<f64> {
+        self.day(date.weekday())
+    }
+    
+    pub fn week(&self) -> Option<f64> {
+        self.week.or_else(|| {
+            self.days.iter().any(Option::is_some).then(|| sum_hours(self.days.iter().flatten().copied()))
+        })
+    }
+}
+
+impl Settings {
+    pub fn currency(&self) -> &str {
+        self.currency.as_deref().unwrap_or(DEFAULT_CURRENCY)
+    }
+    
+    pub fn tax_rate(&self) -> f64 {
+        self.tax_rate.unwrap_or(0.0)
+    }
+    
+    pub fn invoice_rounding(&self) -> u32 {
+        self.invoice_rounding.unwrap_or(0)
+    }
+    
+    pub fn invoice_prefix(&self) -> &str {
+        self.invoice_prefix.as_deref().unwrap_or(DEFAULT_INVOICE_PREFIX)
+    }
+    
+    pub fn backup_retention(&self) -> usize {
+        self.backup_retention.unwrap_or(backup::DEFAULT_RETENTION)
+    }
+    
+    pub fn timezone(&self) -> anyhow::Result<Tz> {
+        match &self.timezone {
+            Some(name) => datetime::parse_timezone(name),
+            None => Ok(datetime::system_timezone()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeCardData {
+    #[serde(default)]
+    pub schema_version: u32,
     pub time_entries: Vec<TimeEntry>,
     pub projects: Vec<Project>,
+    #[serde(default)]
+    pub clients: Vec<Client>,
+    #[serde(default)]
+    pub rates: Vec<Rate>,
+    #[serde(default)]
+    pub invoices: Vec<Invoice>,
+    #[serde(default)]
+    pub leave: Vec<Leave>,
+    #[serde(default)]
+    pub settings: Settings,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -74,8 +635,14 @@ impl Default for TimeCardData {
     fn default() -> Self {
         let now = Utc::now();
         Self {
+            schema_version: migrations::CURRENT_SCHEMA_VERSION,
             time_entries: Vec::new(),
             projects: Vec::new(),
+            clients: Vec::new(),
+            rates: Vec::new(),
+            invoices: Vec::new(),
+            leave: Vec::new(),
+            settings: Settings::default(),
             created_at: now,
             updated_at: now,
         }
@@ -92,18 +659,29 @@ impl TimeCardData {
         self.time_entries.iter().find(|entry| entry.is_active())
     }
     
-    pub fn get_entries_by_project(&self, project: &str) -> Vec<&TimeEntry> {
+    pub fn get_entries_by_project(&self, project_id: Uuid) -> Vec<&TimeEntry> {
         self.time_entries
             .iter()
-            .filter(|entry| entry.project.as_deref() == Some(project))
+            .filter(|entry| entry.project_id == Some(project_id))
             .collect()
     }
     
-    pub fn get_entries_by_date(&self, date: chrono::NaiveDate) -> Vec<&TimeEntry> {
-        self.time_entries
-            .iter()
-            .filter(|entry| entry.start_time.date_naive() == date)
-            .collect()
+    /// Entries starting on the given local calendar day in `tz`.
+    pub fn get_entries_by_date(&self, date: chrono::NaiveDate, tz: &Tz) -> Vec<&TimeEntry> {
+        self.get_entries_by_dates(date, date, tz).remove(&date).unwrap_or_default()
+    }
+    
+    /// Entries by the local day they start on, for the days `first..=last`.
+    /// Days without entries are left out.
+    pub fn get_entries_by_dates(&self, first: NaiveDate, last: NaiveDate, tz: &Tz) -> BTreeMap<NaiveDate, Vec<&TimeEntry>> {
+        let mut days: BTreeMap<NaiveDate, Vec<&TimeEntry>> = BTreeMap::new();
+        for entry in &self.time_entries {
+            let date = entry.start_time.with_timezone(tz).date_naive();
+            if (first..=last).contains(&date) {
+                days.entry(date).or_default().push(entry);
+            }
+        }
+        days
     }
     
     pub fn get_entries_by_period(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<&TimeEntry> {
@@ -114,23 +692,333 @@ impl TimeCardData {
     }
     
     pub fn total_hours(&self) -> f64 {
-        self.time_entries
-            .iter()
-            .filter_map(|entry| entry.hours())
-            .sum()
+        sum_hours(self.time_entries.iter().filter_map(|entry| entry.hours()))
     }
     
-    pub fn total_hours_by_project(&self, project: &str) -> f64 {
-        self.get_entries_by_project(project)
-            .iter()
-            .filter_map(|entry| entry.hours())
-            .sum()
+    pub fn total_hours_by_project(&self, project_id: Uuid) -> f64 {
+        sum_hours(self.get_entries_by_project(project_id).iter().filter_map(|entry| entry.hours()))
     }
     
     pub fn total_hours_by_period(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> f64 {
-        self.get_entries_by_period(start, end)
+        sum_hours(self.get_entries_by_period(start, end).iter().filter_map(|entry| entry.hours()))
+    }
+    
+    /// Resolve a (possibly abbreviated) entry id. The prefix must match
+    /// exactly one entry, like git's short commit hashes.
+    pub fn find_entry_by_prefix(&self, prefix: &str) -> anyhow::Result<Uuid> {
+        find_by_id_prefix(&self.time_entries, |entry| entry.id, prefix, "time entry", "entries")
+    }
+    
+    pub fn get_entry_mut(&mut self, id: Uuid) -> Option<&mut TimeEntry> {
+        self.time_entries.iter_mut().find(|entry| entry.id == id)
+    }
+    
+    pub fn remove_time_entry(&mut self, id: Uuid) -> Option<TimeEntry> {
+        let index = self.time_entries.iter().position(|entry| entry.id == id)?;
+        self.updated_at = Utc::now();
+        Some(self.time_entries.remove(index))
+    }
+    
+    pub fn get_project(&self, id: Uuid) -> Option<&Project> {
+        self.projects.iter().find(|project| project.id == id)
+    }
+    
+    pub fn find_project(&self, name: &str) -> Option<&Project> {
+        self.projects.iter().find(|project| project.matches_name(name))
+    }
+    
+    /// Display name for an entry's project reference.
+    pub fn project_name(&self, project_id: Option<Uuid>) -> Option<&str> {
+        project_id
+            .and_then(|id| self.get_project(id))
+            .map(|project| project.name.as_str())
+    }
+    
+    pub fn add_project(&mut self, name: &str, description: Option<String>) -> anyhow::Result<Uuid> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(TimeCardError::InvalidInput("Project name cannot be empty".to_string()).into());
+        }
+        if let Some(existing) = self.find_project(name) {
+            return Err(TimeCardError::InvalidInput(format!("Project already exists: {}", existing.name)).into());
+        }
+        
+        let project = Project::new(name.to_string(), description);
+        let id = project.id;
+        self.projects.push(project);
+        self.updated_at = Utc::now();
+        Ok(id)
+    }
+    
+    /// Look up a project by name for a new entry. Unknown names are created
+    /// when `auto_create` is set; archived projects are rejected.
+    /// Returns the project id and whether it was newly created.
+    pub fn resolve_project(&mut self, name: &str, auto_create: bool) -> anyhow::Result<(Uuid, bool)> {
+        match self.find_project(name) {
+            Some(project) if project.archived => Err(TimeCardError::InvalidInput(format!(
+                "Project is archived: {}. Unarchive it first.",
+                project.name
+            )).into()),
+            Some(project) => Ok((project.id, false)),
+            None if auto_create => Ok((self.add_project(name, None)?, true)),
+            None => Err(TimeCardError::NotFound(format!("Unknown project: {}", name)).into()),
+        }
+    }
+    
+    pub fn rename_project(&mut self, name: &str, new_name: &str) -> anyhow::Result<&Project> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(TimeCardError::InvalidInput("Project name cannot be empty".to_string()).into());
+        }
+        
+        let id = self.find_project(name)
+            .map(|project| project.id)
+            .ok_or_else(|| TimeCardError::NotFound(format!("Unknown project: {}", name)))?;
+        if let Some(existing) = self.find_project(new_name) {
+            if existing.id != id {
+                return Err(TimeCardError::InvalidInput(format!("Project already exists: {}", existing.name)).into());
+            }
+        }
+        
+        let now = Utc::now();
+        self.updated_at = now;
+        let project = self.projects.iter_mut().find(|project| project.id == id).unwrap();
+        project.name = new_name.to_string();
+        project.updated_at = now;
+        Ok(project)
+    }
+    
+    pub fn set_project_archived(&mut self, name: &str, archived: bool) -> anyhow::Result<&Project> {
+        let now = Utc::now();
+        self.updated_at = now;
+        let project = self.projects
+            .iter_mut()
+            .find(|project| project.matches_name(name))
+            .ok_or_else(|| TimeCardError::NotFound(format!("Unknown project: {}", name)))?;
+        project.archived = archived;
+        project.updated_at = now;
+        Ok(project)
+    }
+    
+    /// Remove a project and its rates. Entries still referencing it are only
+    /// detached when `force` is set, and none may be billed on an invoice.
+    /// Returns the removed project's id and the ids of the detached entries.
+    pub fn delete_project(&mut self, name: &str, force: bool) -> anyhow::Result<(Uuid, Vec<Uuid>)> {
+        let project = self.find_project(name)
+            .ok_or_else(|| TimeCardError::NotFound(format!("Unknown project: {}", name)))?;
+        let id = project.id;
+        let referenced = self.get_entries_by_project(id).len();
+        if referenced > 0 && !force {
+            return Err(TimeCardError::InvalidInput(format!(
+                "Project {} has {} time entries. Use --force to delete it and unassign them.",
+                project.name,
+                referenced
+            )).into());
+        }
+        // Billed entries keep their project
+        let billed = self.get_entries_by_project(id).iter().find_map(|entry| entry.invoice_id);
+        if let Some(invoice) = billed.and_then(|invoice_id| self.invoices.iter().find(|invoice| invoice.id == invoice_id)) {
+            return Err(TimeCardError::Invoiced { number: invoice.number.clone() }.into());
+        }
+        
+        let now = Utc::now();
+        let mut detached = Vec::new();
+        for entry in self.time_entries.iter_mut().filter(|entry| entry.project_id == Some(id)) {
+            entry.project_id = None;
+            entry.updated_at = now;
+            detached.push(entry.id);
+        }
+        self.rates.retain(|rate| rate.scope != RateScope::Project(id));
+        self.projects.retain(|project| project.id != id);
+        self.updated_at = now;
+        Ok((id, detached))
+    }
+    
+    pub fn get_client(&self, id: Uuid) -> Option<&Client> {
+        self.clients.iter().find(|client| client.id == id)
+    }
+    
+    pub fn find_client(&self, name: &str) -> Option<&Client> {
+        self.clients.iter().find(|client| client.matches_name(name))
+    }
+    
+    pub fn require_client(&self, name: &str) -> anyhow::Result<&Client> {
+        self.find_client(name)
+            .ok_or_else(|| TimeCardError::NotFound(format!("Unknown client: {}", name)).into())
+    }
+    
+    /// Client owning the project an entry is booked on.
+    pub fn client_for_project(&self, project_id: Option<Uuid>) -> Option<&Client> {
+        project_id
+            .and_then(|id| self.get_project(id))
+            .and_then(|project| project.client_id)
+            .and_then(|id| self.get_client(id))
+    }
+    
+    /// Currency for rates scoped to a client, or to one of its projects.
+    pub fn client_currency(&self, client: Option<&Client>) -> String {
+        client
+            .and_then(|client| client.currency.clone())
+            .unwrap_or_else(|| self.settings.currency().to_string())
+    }
+    
+    pub fn add_client(&mut self, name: &str, currency: Option<String>) -> anyhow::Result<Uuid> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(TimeCardError::InvalidInput("Client name cannot be empty".to_string()).into());
+        }
+        if let Some(existing) = self.find_client(name) {
+            return Err(TimeCardError::InvalidInput(format!("Client already exists: {}", existing.name)).into());
+        }
+        
+        let client = Client::new(name.to_string(), currency);
+        let id = client.id;
+        self.clients.push(client);
+        self.updated_at = Utc::now();
+        Ok(id)
+    }
+    
+    pub fn rename_client(&mut self, name: &str, new_name: &str) -> anyhow::Result<&Client> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(TimeCardError::InvalidInput("Client name cannot be empty".to_string()).into());
+        }
+        
+        let id = self.require_client(name)?.id;
+        if let Some(existing) = self.find_client(new_name) {
+            if existing.id != id {
+                return Err(TimeCardError::InvalidInput(format!("Client already exists: {}", existing.name)).into());
+            }
+        }
+        
+        let now = Utc::now();
+        self.updated_at = now;
+        let client = self.clients.iter_mut().find(|client| client.id == id).unwrap();
+        client.name = new_name.to_string();
+        client.updated_at = now;
+        Ok(client)
+    }
+    
+    /// Remove a client. Its projects are unassigned and its rates dropped,
+    /// which needs `force` if there are any. Returns the ids of the
+    /// unassigned projects.
+    pub fn delete_client(&mut self, name: &str, force: bool) -> anyhow::Result<Vec<Uuid>> {
+        let client = self.require_client(name)?;
+        let id = client.id;
+        let projects = self.projects.iter().filter(|project| project.client_id == Some(id)).count();
+        if projects > 0 && !force {
+            return Err(TimeCardError::InvalidInput(format!(
+                "Client {} has {} projects. Use --force to delete it and unassign them.",
+                client.name,
+                projects
+            )).into());
+        }
+        
+        let now = Utc::now();
+        let mut detached = Vec::new();
+        for project in self.projects.iter_mut().filter(|project| project.client_id == Some(id)) {
+            project.client_id = None;
+            project.updated_at = now;
+            detached.push(project.id);
+        }
+        self.rates.retain(|rate| rate.scope != RateScope::Client(id));
+        self.clients.retain(|client| client.id != id);
+        self.updated_at = now;
+        Ok(detached)
+    }
+    
+    /// Assign a project to a client, or unassign it with `None`.
+    pub fn set_project_client(&mut self, name: &str, client: Option<&str>) -> anyhow::Result<&Project> {
+        let client_id = client
+            .map(|client| self.require_client(client).map(|client| client.id))
+            .transpose()?;
+        let id = self.find_project(name)
+            .map(|project| project.id)
+            .ok_or_else(|| TimeCardError::NotFound(format!("Unknown project: {}", name)))?;
+        
+        let now = Utc::now();
+        self.updated_at = now;
+        let project = self.projects.iter_mut().find(|project| project.id == id).unwrap();
+        project.client_id = client_id;
+        project.updated_at = now;
+        Ok(project)
+    }
+    
+    pub fn find_rate_by_prefix(&self, prefix: &str) -> anyhow::Result<Uuid> {
+        find_by_id_prefix(&self.rates, |rate| rate.id, prefix, "rate", "rates")
+    }
+    
+    pub fn find_leave_by_prefix(&self, prefix: &str) -> anyhow::Result<Uuid> {
+        find_by_id_prefix(&self.leave, |leave| leave.id, prefix, "leave", "days")
+    }
+    
+    /// Leave taken on a date.
+    pub fn leave_on(&self, date: NaiveDate) -> impl Iterator<Item = &Leave> {
+        self.leave.iter().filter(move |leave| leave.date == date)
+    }
+    
+    /// Human-readable name of what a rate applies to.
+    pub fn rate_scope_name(&self, scope: &RateScope) -> String {
+        match scope {
+            RateScope::Client(id) => format!(
+                "client {}",
+                self.get_client(*id).map(|client| client.name.as_str()).unwrap_or("(deleted)")
+            ),
+            RateScope::Project(id) => format!(
+                "project {}",
+                self.project_name(Some(*id)).unwrap_or("(deleted)")
+            ),
+            RateScope::Tag(tag) => format!("tag +{}", tag),
+        }
+    }
+    
+    /// Look up an invoice by its number, ignoring case.
+    pub fn find_invoice(&self, number: &str) -> anyhow::Result<&Invoice> {
+        let number = number.trim().to_lowercase();
+        self.invoices
             .iter()
-            .filter_map(|entry| entry.hours())
-            .sum()
+            .find(|invoice| invoice.number.to_lowercase() == number)
+            .ok_or_else(|| TimeCardError::NotFound(format!("Unknown invoice: {}", number)).into())
+    }
+    
+    pub fn next_invoice_sequence(&self) -> u32 {
+        self.invoices.iter().map(|invoice| invoice.sequence).max().unwrap_or(0) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn tags(entry: &TimeEntry) -> Vec<&str> {
+        entry.tags.iter().map(String::as_str).collect()
+    }
+    
+    #[test]
+    fn description_tags_move_into_the_tags() {
+        let entry = TimeEntry::new(None, Some("Sprint +Review planning +meeting".to_string()));
+        assert_eq!(entry.description.as_deref(), Some("Sprint planning"));
+        assert_eq!(tags(&entry), ["meeting", "review"]);
+    }
+    
+    #[test]
+    fn new_descriptions_replace_the_tags_of_the_old_one() {
+        let mut entry = TimeEntry::new(None, Some("Call +meeting +x".to_string()));
+        entry.add_tags(&["keep".to_string(), "x".to_string()]).unwrap();
+        
+        entry.set_description(Some("Fix +bug".to_string()));
+        assert_eq!(tags(&entry), ["bug", "keep", "x"]);
+        
+        entry.set_description(None);
+        assert_eq!(tags(&entry), ["keep", "x"]);
+        assert!(entry.description.is_none());
+    }
+    
+    #[test]
+    fn removed_tags_stay_removed() {
+        let mut entry = TimeEntry::new(None, Some("Call +meeting".to_string()));
+        entry.remove_tag("meeting");
+        entry.set_description(Some("Call".to_string()));
+        assert!(entry.tags.is_empty());
     }
 }
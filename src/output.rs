@@ -0,0 +1,116 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use timecard::{billing, models::{TimeCardData, TimeEntry}};
+use uuid::Uuid;
+
+/// How commands print their results. The JSON shapes are documented in the
+/// README and only ever gain fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// One pretty-printed JSON document
+    Json,
+    /// One compact JSON object per line
+    Jsonl,
+}
+
+impl OutputFormat {
+    pub fn is_machine(self) -> bool {
+        self != OutputFormat::Text
+    }
+    
+    /// Print a side note: to stdout for people, to stderr in machine mode so
+    /// it doesn't end up in the JSON.
+    pub fn note(self, message: impl std::fmt::Display) {
+        if self.is_machine() {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    }
+    
+    /// Print a whole result as one JSON value.
+    pub fn emit<T: Serialize>(self, value: &T) -> anyhow::Result<()> {
+        match self {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+            _ => println!("{}", serde_json::to_string(value)?),
+        }
+        Ok(())
+    }
+    
+    /// Print a list: a JSON document wrapping the items for `json`, or the
+    /// bare items one per line for `jsonl`.
+    pub fn emit_list<T: Serialize, D: Serialize>(self, items: Vec<T>, document: impl FnOnce(Vec<T>) -> D) -> anyhow::Result<()> {
+        match self {
+            OutputFormat::Jsonl => {
+                for item in &items {
+                    println!("{}", serde_json::to_string(item)?);
+                }
+                Ok(())
+            }
+            _ => self.emit(&document(items)),
+        }
+    }
+}
+
+/// Local time with its UTC offset, e.g. `2025-09-01T09:00:00+02:00`.
+pub fn timestamp(time: DateTime<Utc>, tz: &Tz) -> String {
+    time.with_timezone(tz).to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+/// A time entry as printed in machine mode.
+#[derive(Debug, Serialize)]
+pub struct EntryJson {
+    pub id: Uuid,
+    pub short_id: String,
+    pub project: Option<String>,
+    pub client: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub start: String,
+    pub end: Option<String>,
+    pub active: bool,
+    pub paused: bool,
+    /// Worked time without breaks, up to now for the active entry
+    pub duration_seconds: i64,
+    pub break_seconds: i64,
+    pub hours: f64,
+    pub billable: bool,
+    pub hourly_rate: Option<f64>,
+    pub currency: Option<String>,
+    pub amount: Option<f64>,
+    pub invoice: Option<String>,
+}
+
+impl EntryJson {
+    pub fn new(data: &TimeCardData, entry: &TimeEntry, tz: &Tz) -> Self {
+        let until = entry.end_time.unwrap_or_else(Utc::now);
+        let duration = entry.net_duration_until(until).num_seconds();
+        let amount = billing::entry_amount(data, entry, tz);
+        Self {
+            id: entry.id,
+            short_id: entry.short_id(),
+            project: data.project_name(entry.project_id).map(str::to_string),
+            client: data.client_for_project(entry.project_id).map(|client| client.name.clone()),
+            description: entry.description.clone(),
+            tags: entry.tags.iter().cloned().collect(),
+            start: timestamp(entry.start_time, tz),
+            end: entry.end_time.map(|end| timestamp(end, tz)),
+            active: entry.is_active(),
+            paused: entry.is_paused(),
+            duration_seconds: duration,
+            break_seconds: entry.break_duration_until(until).num_seconds(),
+            hours: duration as f64 / 3600.0,
+            billable: entry.billable,
+            hourly_rate: amount.map(|(_, rate)| rate.hourly_rate),
+            currency: amount.map(|(_, rate)| rate.currency.clone()),
+            amount: amount.map(|(amount, _)| amount),
+            invoice: entry.invoice_id
+                .and_then(|id| data.invoices.iter().find(|invoice| invoice.id == id))
+                .map(|invoice| invoice.number.clone()),
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{datetime, error::TimeCardError};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+
+/// A span of whole local days, resolved to UTC bounds for querying.
+#[derive(Debug, Clone)]
+pub struct Period {
+    pub name: String,
+    pub first_day: NaiveDate,
+    pub last_day: NaiveDate,
+    /// First instant of `first_day`
+    pub start: DateTime<Utc>,
+    /// Last instant of `last_day` (inclusive)
+    pub end: DateTime<Utc>,
+}
+
+impl Period {
+    pub fn new(name: impl Into<String>, first_day: NaiveDate, last_day: NaiveDate, tz: &Tz) -> Self {
+        let (start, end) = datetime::day_range(first_day, last_day, tz);
+        Self {
+            name: name.into(),
+            first_day,
+            last_day,
+            start,
+            end,
+        }
+    }
+}
+
+pub const PERIOD_HELP: &str = "today, yesterday, week, last-week, month, last-month, quarter, \
+last-quarter, year, last-year, ytd, all, last-N-days/weeks/months, q1-q4, 2025, 2025-09, \
+2025-09-15, 2025-W36, 2025-q3, since 2025-01-01";
+
+/// Earliest day `all` reaches back to.
+fn epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+}
+
+fn month_bounds(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)?
+    };
+    Some((first, next - Duration::days(1)))
+}
+
+fn quarter_bounds(year: i32, quarter: u32) -> Option<(NaiveDate, NaiveDate)> {
+    if !(1..=4).contains(&quarter) {
+        return None;
+    }
+    let (first, _) = month_bounds(year, quarter * 3 - 2)?;
+    let (_, last) = month_bounds(year, quarter * 3)?;
+    Some((first, last))
+}
+
+fn year_bounds(year: i32) -> Option<(NaiveDate, NaiveDate)> {
+    Some((NaiveDate::from_ymd_opt(year, 1, 1)?, NaiveDate::from_ymd_opt(year, 12, 31)?))
+}
+
+/// Monday of the week a date falls in.
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Shift a month by `delta` months, returning (year, month).
+fn shift_month(year: i32, month: u32, delta: i32) -> (i32, u32) {
+    let index = year * 12 + month as i32 - 1 + delta;
+    (index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+}
+
+fn quarter_of(date: NaiveDate) -> u32 {
+    (date.month() - 1) / 3 + 1
+}
+
+/// Parse a period expression relative to today in `tz`.
+pub fn parse_period(expr: &str, tz: &Tz) -> anyhow::Result<Period> {
+    let today = datetime::today(tz);
+    parse_period_on(expr, today, tz).ok_or_else(|| {
+        TimeCardError::InvalidPeriod(format!("{}. Use: {}", expr, PERIOD_HELP)).into()
+    })
+}
+
+fn parse_period_on(expr: &str, today: NaiveDate, tz: &Tz) -> Option<Period> {
+    let expr = expr.trim().to_lowercase();
+    let period = |name: String, (first, last): (NaiveDate, NaiveDate)| Period::new(name, first, last, tz);
+    
+    match expr.as_str() {
+        "today" => return Some(period("Today".to_string(), (today, today))),
+        "yesterday" => {
+            let yesterday = today - Duration::days(1);
+            return Some(period("Yesterday".to_string(), (yesterday, yesterday)));
+        }
+        "week" | "this-week" => {
+            let start = week_start(today);
+            return Some(period("This Week".to_string(), (start, start + Duration::days(6))));
+        }
+        "last-week" => {
+            let start = week_start(today) - Duration::days(7);
+            return Some(period("Last Week".to_string(), (start, start + Duration::days(6))));
+        }
+        "month" | "this-month" => {
+            return Some(period("This Month".to_string(), month_bounds(today.year(), today.month())?));
+        }
+        "last-month" => {
+            let (year, month) = shift_month(today.year(), today.month(), -1);
+            return Some(period("Last Month".to_string(), month_bounds(year, month)?));
+        }
+        "quarter" | "this-quarter" => {
+            return Some(period("This Quarter".to_string(), quarter_bounds(today.year(), quarter_of(today))?));
+        }
+        "last-quarter" => {
+            let (year, month) = shift_month(today.year(), today.month(), -3);
+            let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+            return Some(period("Last Quarter".to_string(), quarter_bounds(year, quarter_of(first))?));
+        }
+        "year" | "this-year" => {
+            return Some(period("This Year".to_string(), year_bounds(today.year())?));
+        }
+        "last-year" => {
+            return Some(period("Last Year".to_string(), year_bounds(today.year() - 1)?));
+        }
+        "ytd" => {
+            let (first, _) = year_bounds(today.year())?;
+            return Some(period("Year to Date".to_string(), (first, today)));
+        }
+        "all" => return Some(period("All Time".to_string(), (epoch(), today))),
+        _ => {}
+    }
+    
+    // since 2025-01-01 / since-2025-01-01
+    if let Some(rest) = expr.strip_prefix("since") {
+        let rest = rest.trim_start_matches(|c: char| c == '-' || c.is_whitespace());
+        let from = parse_period_on(rest, today, tz)?;
+        return Some(period(format!("Since {}", from.first_day.format("%Y-%m-%d")), (from.first_day, today)));
+    }
+    
+    // last-7-days, last-2-weeks, last-3-months (ending today)
+    if let Some(rest) = expr.strip_prefix("last-") {
+        let (count, unit) = rest.split_once('-')?;
+        let count: i64 = count.parse().ok().filter(|count| *count > 0)?;
+        let first = match unit {
+            "day" | "days" => today.checked_sub_signed(Duration::try_days(count - 1)?)?,
+            "week" | "weeks" => today.checked_sub_signed(Duration::try_days(count.checked_mul(7)? - 1)?)?,
+            "month" | "months" => {
+                let (year, month) = shift_month(today.year(), today.month(), -(count as i32));
+                let day = today.day().min(month_bounds(year, month)?.1.day());
+                NaiveDate::from_ymd_opt(year, month, day)? + Duration::days(1)
+            }
+            _ => return None,
+        };
+        let unit = match unit.trim_end_matches('s') {
+            "day" => "Days",
+            "week" => "Weeks",
+            _ => "Months",
+        };
+        return Some(period(format!("Last {} {}", count, unit), (first, today)));
+    }
+    
+    // q3 (this year)
+    if let Some(quarter) = expr.strip_prefix('q').and_then(|q| q.parse::<u32>().ok()) {
+        return Some(period(format!("Q{} {}", quarter, today.year()), quarter_bounds(today.year(), quarter)?));
+    }
+    
+    // 2025-09-15
+    if let Ok(date) = NaiveDate::parse_from_str(&expr, "%Y-%m-%d") {
+        return Some(period(date.format("%Y-%m-%d").to_string(), (date, date)));
+    }
+    
+    let (year, rest) = match expr.split_once('-') {
+        Some((year, rest)) => (year.parse::<i32>().ok()?, Some(rest)),
+        None => (expr.parse::<i32>().ok()?, None),
+    };
+    match rest {
+        // 2025
+        None => Some(period(year.to_string(), year_bounds(year)?)),
+        // 2025-W36
+        Some(week) if week.starts_with('w') => {
+            let week: u32 = week[1..].parse().ok()?;
+            let first = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+            Some(period(format!("{}-W{:02}", year, week), (first, first + Duration::days(6))))
+        }
+        // 2025-q3
+        Some(quarter) if quarter.starts_with('q') => {
+            let quarter: u32 = quarter[1..].parse().ok()?;
+            Some(period(format!("Q{} {}", quarter, year), quarter_bounds(year, quarter)?))
+        }
+        // 2025-09
+        Some(month) => {
+            let (first, last) = month_bounds(year, month.parse().ok()?)?;
+            Some(period(first.format("%B %Y").to_string(), (first, last)))
+        }
+    }
+}
+
+/// Period spanning from the start of `from` to the end of `to`. Each side
+/// accepts any period expression, so `--from 2025-09 --to 2025-10` covers
+/// both months. A missing side means the beginning of records or today.
+pub fn parse_range(from: Option<&str>, to: Option<&str>, tz: &Tz) -> anyhow::Result<Period> {
+    let today = datetime::today(tz);
+    let first_day = match from {
+        Some(from) => parse_period(from, tz)?.first_day,
+        None => epoch(),
+    };
+    let last_day = match to {
+        Some(to) => parse_period(to, tz)?.last_day,
+        None => today,
+    };
+    
+    if last_day < first_day {
+        return Err(TimeCardError::InvalidPeriod(format!(
+            "{} is before {}",
+            last_day.format("%Y-%m-%d"),
+            first_day.format("%Y-%m-%d")
+        )).into());
+    }
+    
+    let name = format!("{} to {}", first_day.format("%Y-%m-%d"), last_day.format("%Y-%m-%d"));
+    Ok(Period::new(name, first_day, last_day, tz))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+    
+    fn days(expr: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        parse_period_on(expr, today, &Tz::UTC).map(|period| (period.first_day, period.last_day))
+    }
+    
+    #[test]
+    fn named_periods() {
+        let today = date(2025, 9, 17);
+        assert_eq!(days("today", today), Some((today, today)));
+        assert_eq!(days("week", today), Some((date(2025, 9, 15), date(2025, 9, 21))));
+        assert_eq!(days("last-week", today), Some((date(2025, 9, 8), date(2025, 9, 14))));
+        assert_eq!(days("Month", today), Some((date(2025, 9, 1), date(2025, 9, 30))));
+        assert_eq!(days("quarter", today), Some((date(2025, 7, 1), date(2025, 9, 30))));
+        assert_eq!(days("ytd", today), Some((date(2025, 1, 1), today)));
+    }
+    
+    #[test]
+    fn previous_periods_cross_year_ends() {
+        let today = date(2026, 1, 10);
+        assert_eq!(days("last-month", today), Some((date(2025, 12, 1), date(2025, 12, 31))));
+        assert_eq!(days("last-quarter", today), Some((date(2025, 10, 1), date(2025, 12, 31))));
+        assert_eq!(days("last-year", today), Some((date(2025, 1, 1), date(2025, 12, 31))));
+    }
+    
+    #[test]
+    fn last_n_units_end_today() {
+        let today = date(2025, 9, 17);
+        assert_eq!(days("last-7-days", today), Some((date(2025, 9, 11), today)));
+        assert_eq!(days("last-2-weeks", today), Some((date(2025, 9, 4), today)));
+        assert_eq!(days("last-1-month", today), Some((date(2025, 8, 18), today)));
+    }
+    
+    #[test]
+    fn last_n_months_across_year_ends() {
+        assert_eq!(days("last-3-months", date(2026, 1, 15)), Some((date(2025, 10, 16), date(2026, 1, 15))));
+        assert_eq!(days("last-14-months", date(2026, 2, 1)), Some((date(2024, 12, 2), date(2026, 2, 1))));
+        // No 31st in February, so the range starts after its last day
+        assert_eq!(days("last-1-month", date(2026, 3, 31)), Some((date(2026, 3, 1), date(2026, 3, 31))));
+    }
+    
+    #[test]
+    fn last_n_rejects_zero_and_counts_too_large() {
+        let today = date(2025, 9, 17);
+        assert_eq!(days("last-0-days", today), None);
+        assert_eq!(days("last-9223372036854775807-days", today), None);
+        assert_eq!(days("last-2000000000000-weeks", today), None);
+        assert_eq!(days("last-9999999999-months", today), None);
+        assert_eq!(days("last-3-fortnights", today), None);
+    }
+    
+    #[test]
+    fn calendar_expressions() {
+        let today = date(2025, 9, 17);
+        assert_eq!(days("2024", today), Some((date(2024, 1, 1), date(2024, 12, 31))));
+        assert_eq!(days("2024-02", today), Some((date(2024, 2, 1), date(2024, 2, 29))));
+        assert_eq!(days("2025-09-15", today), Some((date(2025, 9, 15), date(2025, 9, 15))));
+        assert_eq!(days("2025-W36", today), Some((date(2025, 9, 1), date(2025, 9, 7))));
+        assert_eq!(days("2025-q4", today), Some((date(2025, 10, 1), date(2025, 12, 31))));
+        assert_eq!(days("q1", today), Some((date(2025, 1, 1), date(2025, 3, 31))));
+        assert_eq!(days("since 2025-09-01", today), Some((date(2025, 9, 1), today)));
+        assert_eq!(days("2025-13", today), None);
+        assert_eq!(days("q5", today), None);
+        assert_eq!(days("someday", today), None);
+    }
+}
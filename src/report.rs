@@ -0,0 +1,129 @@
+use crate::{
+    billing::{self, Amounts},
+    error::TimeCardError,
+    models::{self, TagFilter, TimeCardData, TimeEntry},
+    period::Period,
+};
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use std::collections::BTreeMap;
+
+/// Hours and money booked on one project.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectTotal {
+    pub hours: f64,
+    pub amounts: Amounts,
+}
+
+/// Totals over a set of entries.
+#[derive(Debug, Clone)]
+pub struct Report<'a> {
+    pub entries: Vec<&'a TimeEntry>,
+    pub total_hours: f64,
+    pub billable_hours: f64,
+    /// Billable hours no rate applies to
+    pub unrated_hours: f64,
+    /// Billable amounts, at the rate in force when each entry started
+    pub amounts: Amounts,
+    /// Per project name; `None` collects entries without a project
+    pub projects: BTreeMap<Option<&'a str>, ProjectTotal>,
+    /// Hours per tag. Entries with several tags count towards each of them.
+    pub tags: BTreeMap<&'a str, f64>,
+    pub untagged_hours: f64,
+    /// Break hours per day, by the day each break started
+    pub breaks: BTreeMap<NaiveDate, f64>,
+    /// Hours per day and project name, by the day each entry started
+    pub days: BTreeMap<NaiveDate, BTreeMap<Option<&'a str>, f64>>,
+}
+
+/// Entries starting within `range`, optionally only those of one project
+/// and matching the tag filter.
+pub fn select_entries<'a>(
+    data: &'a TimeCardData,
+    range: &Period,
+    project: Option<&str>,
+    tag_filter: &TagFilter,
+) -> anyhow::Result<Vec<&'a TimeEntry>> {
+    let project_id = match project {
+        Some(project) => Some(
+            data.find_project(project)
+                .map(|project| project.id)
+                .ok_or_else(|| TimeCardError::NotFound(format!("Unknown project: {}", project)))?,
+        ),
+        None => None,
+    };
+    Ok(data
+        .get_entries_by_period(range.start, range.end)
+        .into_iter()
+        .filter(|entry| project_id.is_none() || entry.project_id == project_id)
+        .filter(|entry| tag_filter.matches(entry))
+        .collect())
+}
+
+impl<'a> Report<'a> {
+    pub fn new(data: &'a TimeCardData, entries: Vec<&'a TimeEntry>, tz: &Tz) -> Self {
+        let total_hours = models::sum_hours(entries.iter().filter_map(|entry| entry.hours()));
+        
+        let mut amounts = Amounts::default();
+        let mut billable_hours = 0.0;
+        let mut unrated_hours = 0.0;
+        let mut projects: BTreeMap<_, ProjectTotal> = BTreeMap::new();
+        let mut tags = BTreeMap::new();
+        let mut untagged_hours = 0.0;
+        let mut breaks = BTreeMap::new();
+        let mut days: BTreeMap<_, BTreeMap<_, f64>> = BTreeMap::new();
+        
+        for entry in &entries {
+            let hours = entry.hours().unwrap_or(0.0);
+            let amount = billing::entry_amount(data, entry, tz);
+            
+            if entry.billable {
+                billable_hours += hours;
+                match amount {
+                    Some((amount, rate)) => amounts.add(&rate.currency, amount),
+                    None => unrated_hours += hours,
+                }
+            }
+            
+            let day = entry.start_time.with_timezone(tz).date_naive();
+            *days.entry(day).or_default().entry(data.project_name(entry.project_id)).or_insert(0.0) += hours;
+            
+            let project = projects.entry(data.project_name(entry.project_id)).or_default();
+            project.hours += hours;
+            if let Some((amount, rate)) = amount {
+                project.amounts.add(&rate.currency, amount);
+            }
+            
+            if entry.tags.is_empty() {
+                untagged_hours += hours;
+            }
+            for tag in &entry.tags {
+                *tags.entry(tag.as_str()).or_insert(0.0) += hours;
+            }
+            
+            let until = entry.end_time.unwrap_or_else(Utc::now);
+            for pause in &entry.pauses {
+                let day = pause.start.with_timezone(tz).date_naive();
+                let duration = pause.end.unwrap_or(until).min(until) - pause.start;
+                *breaks.entry(day).or_insert(0.0) += duration.num_seconds() as f64 / 3600.0;
+            }
+        }
+        
+        Self {
+            entries,
+            total_hours,
+            billable_hours,
+            unrated_hours,
+            amounts,
+            projects,
+            tags,
+            untagged_hours,
+            breaks,
+            days,
+        }
+    }
+    
+    pub fn total_break_hours(&self) -> f64 {
+        models::sum_hours(self.breaks.values().copied())
+    }
+}
@@ -0,0 +1,94 @@
+use crate::error::TimeCardError;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_RETENTION: usize = 10;
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub created_at: DateTime<Utc>,
+    pub size: u64,
+}
+
+impl Backup {
+    pub fn name(&self) -> String {
+        self.path.file_name().unwrap_or_default().to_string_lossy().to_string()
+    }
+}
+
+/// Backups of `data.json` live in `data.json.backups/` next to it.
+pub fn backup_dir(file_path: &Path) -> PathBuf {
+    let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".backups");
+    file_path.with_file_name(name)
+}
+
+/// Copy the data file into a new timestamped backup, then prune the oldest
+/// backups beyond `retention`. A retention of zero disables backups.
+pub fn create_backup(file_path: &Path, retention: usize) -> anyhow::Result<Option<PathBuf>> {
+    if retention == 0 || !file_path.exists() {
+        return Ok(None);
+    }
+    
+    let dir = backup_dir(file_path);
+    fs::create_dir_all(&dir)?;
+    
+    let mut name = Utc::now().format(TIMESTAMP_FORMAT).to_string();
+    if let Some(ext) = file_path.extension() {
+        name.push('.');
+        name.push_str(&ext.to_string_lossy());
+    }
+    let backup_path = dir.join(name);
+    fs::copy(file_path, &backup_path)?;
+    
+    for old in list_backups(file_path)?.into_iter().skip(retention) {
+        fs::remove_file(old.path)?;
+    }
+    
+    Ok(Some(backup_path))
+}
+
+/// Existing backups, newest first.
+pub fn list_backups(file_path: &Path) -> anyhow::Result<Vec<Backup>> {
+    let dir = backup_dir(file_path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    
+    let mut backups = Vec::new();
+    for dir_entry in fs::read_dir(&dir)? {
+        let dir_entry = dir_entry?;
+        let path = dir_entry.path();
+        let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let Ok(created_at) = NaiveDateTime::parse_from_str(stem, TIMESTAMP_FORMAT) else {
+            continue;
+        };
+        backups.push(Backup {
+            created_at: created_at.and_utc(),
+            size: dir_entry.metadata()?.len(),
+            path,
+        });
+    }
+    
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    Ok(backups)
+}
+
+/// Find a backup by its position in `list_backups` (1 = newest) or by file
+/// name.
+pub fn find_backup(file_path: &Path, selector: &str) -> anyhow::Result<Backup> {
+    let backups = list_backups(file_path)?;
+    let found = match selector.parse::<usize>() {
+        Ok(index) if index >= 1 => backups.into_iter().nth(index - 1),
+        _ => backups.into_iter().find(|backup| backup.name() == selector),
+    };
+    found.ok_or_else(|| {
+        TimeCardError::NotFound(format!("No backup found: {}. See `timecard backup list`", selector)).into()
+    })
+}
@@ -0,0 +1,260 @@
+use super::{backup, corrupt, migrations, Storage};
+use crate::models::{Client, Invoice, Leave, Project, Rate, Settings, TimeCardData, TimeEntry};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// The whole document in one JSON file, rewritten on every change.
+pub struct JsonStorage {
+    path: PathBuf,
+}
+
+impl JsonStorage {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+    
+    fn modify(&mut self, f: impl FnOnce(&mut TimeCardData)) -> anyhow::Result<()> {
+        let mut data = load_data(&self.path)?;
+        f(&mut data);
+        data.updated_at = Utc::now();
+        save_data(&self.path, &data)
+    }
+}
+
+impl Storage for JsonStorage {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    fn load(&mut self) -> anyhow::Result<TimeCardData> {
+        load_data(&self.path)
+    }
+    
+    fn save(&mut self, data: &TimeCardData) -> anyhow::Result<()> {
+        save_data(&self.path, data)
+    }
+    
+    fn load_projects(&mut self) -> anyhow::Result<Vec<Project>> {
+        Ok(load_data(&self.path)?.projects)
+    }
+    
+    fn load_settings(&mut self) -> anyhow::Result<Settings> {
+        Ok(load_data(&self.path)?.settings)
+    }
+    
+    fn query_entries(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> anyhow::Result<Vec<TimeEntry>> {
+        let data = load_data(&self.path)?;
+        let mut entries: Vec<_> = data.get_entries_by_period(start, end).into_iter().cloned().collect();
+        entries.sort_by_key(|entry| entry.start_time);
+        Ok(entries)
+    }
+    
+    fn insert_entry(&mut self, entry: &TimeEntry) -> anyhow::Result<()> {
+        self.modify(|data| data.time_entries.push(entry.clone()))
+    }
+    
+    fn update_entry(&mut self, entry: &TimeEntry) -> anyhow::Result<()> {
+        self.modify(|data| {
+            if let Some(existing) = data.get_entry_mut(entry.id) {
+                *existing = entry.clone();
+            }
+        })
+    }
+    
+    fn delete_entry(&mut self, id: Uuid) -> anyhow::Result<()> {
+        self.modify(|data| data.time_entries.retain(|entry| entry.id != id))
+    }
+    
+    fn write_entries(&mut self, entries: &[TimeEntry]) -> anyhow::Result<()> {
+        self.modify(|data| {
+            for entry in entries {
+                match data.get_entry_mut(entry.id) {
+                    Some(existing) => *existing = entry.clone(),
+                    None => data.time_entries.push(entry.clone()),
+                }
+            }
+        })
+    }
+    
+    fn insert_project(&mut self, project: &Project) -> anyhow::Result<()> {
+        self.modify(|data| data.projects.push(project.clone()))
+    }
+    
+    fn update_project(&mut self, project: &Project) -> anyhow::Result<()> {
+        self.modify(|data| {
+            if let Some(existing) = data.projects.iter_mut().find(|p| p.id == project.id) {
+                *existing = project.clone();
+            }
+        })
+    }
+    
+    fn delete_project(&mut self, id: Uuid) -> anyhow::Result<()> {
+        self.modify(|data| data.projects.retain(|project| project.id != id))
+    }
+    
+    fn load_clients(&mut self) -> anyhow::Result<Vec<Client>> {
+        Ok(load_data(&self.path)?.clients)
+    }
+    
+    fn insert_client(&mut self, client: &Client) -> anyhow::Result<()> {
+        self.modify(|data| data.clients.push(client.clone()))
+    }
+    
+    fn update_client(&mut self, client: &Client) -> anyhow::Result<()> {
+        self.modify(|data| {
+            if let Some(existing) = data.clients.iter_mut().find(|c| c.id == client.id) {
+                *existing = client.clone();
+            }
+        })
+    }
+    
+    fn delete_client(&mut self, id: Uuid) -> anyhow::Result<()> {
+        self.modify(|data| data.clients.retain(|client| client.id != id))
+    }
+    
+    fn load_rates(&mut self) -> anyhow::Result<Vec<Rate>> {
+        Ok(load_data(&self.path)?.rates)
+    }
+    
+    fn insert_rate(&mut self, rate: &Rate) -> anyhow::Result<()> {
+        self.modify(|data| data.rates.push(rate.clone()))
+    }
+    
+    fn delete_rate(&mut self, id: Uuid) -> anyhow::Result<()> {
+        self.modify(|data| data.rates.retain(|rate| rate.id != id))
+    }
+    
+    fn load_invoices(&mut self) -> anyhow::Result<Vec<Invoice>> {
+        Ok(load_data(&self.path)?.invoices)
+    }
+    
+    fn insert_invoice(&mut self, invoice: &Invoice) -> anyhow::Result<()> {
+        self.modify(|data| data.invoices.push(invoice.clone()))
+    }
+    
+    fn update_invoice(&mut self, invoice: &Invoice) -> anyhow::Result<()> {
+        self.modify(|data| {
+            if let Some(existing) = data.invoices.iter_mut().find(|i| i.id == invoice.id) {
+                *existing = invoice.clone();
+            }
+        })
+    }
+    
+    fn load_leave(&mut self) -> anyhow::Result<Vec<Leave>> {
+        Ok(load_data(&self.path)?.leave)
+    }
+    
+    fn insert_leave(&mut self, leave: &[Leave]) -> anyhow::Result<()> {
+        self.modify(|data| data.leave.extend_from_slice(leave))
+    }
+    
+    fn delete_leave(&mut self, id: Uuid) -> anyhow::Result<()> {
+        self.modify(|data| data.leave.retain(|leave| leave.id != id))
+    }
+    
+    fn save_settings(&mut self, settings: &Settings) -> anyhow::Result<()> {
+        self.modify(|data| data.settings = settings.clone())
+    }
+    
+    /// Parses the file once rather than once per part, as the default would.
+    fn load_period(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> anyhow::Result<TimeCardData> {
+        let mut data = load_data(&self.path)?;
+        data.time_entries.retain(|entry| entry.start_time >= start && entry.start_time <= end);
+        data.time_entries.sort_by_key(|entry| entry.start_time);
+        Ok(data)
+    }
+}
+
+/// Read the document, migrating an older one in memory only. Files are
+/// upgraded on disk by [`upgrade`] when they're opened.
+pub fn load_data(file_path: &Path) -> anyhow::Result<TimeCardData> {
+    let Some(mut doc) = read_doc(file_path)? else {
+        return Ok(TimeCardData::default());
+    };
+    migrations::migrate(&mut doc)?;
+    Ok(serde_json::from_value(doc).map_err(|e| corrupt(file_path, e))?)
+}
+
+/// Upgrade an older file once, keeping the original as a backup. Callers
+/// hold the exclusive lock, so concurrent readers don't both write it.
+pub fn upgrade(file_path: &Path) -> anyhow::Result<()> {
+    let Some(mut doc) = read_doc(file_path)? else {
+        return Ok(());
+    };
+    if migrations::schema_version(&doc) == migrations::CURRENT_SCHEMA_VERSION {
+        return Ok(());
+    }
+    
+    let retention = doc.get("settings")
+        .and_then(|settings| serde_json::from_value::<Settings>(settings.clone()).ok())
+        .unwrap_or_default()
+        .backup_retention();
+    backup::create_backup(file_path, retention.max(1))?;
+    migrations::migrate(&mut doc)?;
+    
+    let data: TimeCardData = serde_json::from_value(doc).map_err(|e| corrupt(file_path, e))?;
+    save_data(file_path, &data)
+}
+
+/// The raw document, if the file exists, refused if a newer build wrote it.
+fn read_doc(file_path: &Path) -> anyhow::Result<Option<serde_json::Value>> {
+    if !file_path.exists() {
+        return Ok(None);
+    }
+    
+    let content = fs::read_to_string(file_path)?;
+    let doc: serde_json::Value = serde_json::from_str(&content).map_err(|e| corrupt(file_path, e))?;
+    migrations::check_supported(migrations::schema_version(&doc), file_path)?;
+    Ok(Some(doc))
+}
+
+/// Write the document to a temporary file next to the target and rename it
+/// into place, so a crash or full disk never leaves a truncated data file.
+pub fn save_data(file_path: &Path, data: &TimeCardData) -> anyhow::Result<()> {
+    let content = serde_json::to_string_pretty(data)?;
+    
+    // Create parent directory if it doesn't exist
+    if let Some(parent) = file_path.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    
+    let mut tmp_name = file_path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = file_path.with_file_name(tmp_name);
+    
+    let result = (|| -> anyhow::Result<()> {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, file_path)?;
+        sync_parent_dir(file_path)
+    })();
+    
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Persist the rename itself. Directories can't be opened for syncing on
+/// Windows, where the rename is already durable.
+fn sync_parent_dir(file_path: &Path) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        let parent = match file_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = file_path;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::error::TimeCardError;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const LOCK_RETRY: Duration = Duration::from_millis(50);
+
+/// Advisory lock on a data file, released when dropped. The lock is taken
+/// on a separate `.lock` file because the JSON backend replaces the data
+/// file on every save.
+pub struct DataLock {
+    _file: File,
+}
+
+pub fn lock_path(file_path: &Path) -> PathBuf {
+    let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    file_path.with_file_name(name)
+}
+
+/// Lock for a load-modify-save cycle. Waits for other writers and readers.
+pub fn lock_exclusive(file_path: &Path) -> anyhow::Result<DataLock> {
+    acquire(file_path, true)
+}
+
+/// Lock for reading. Only waits for writers.
+pub fn lock_shared(file_path: &Path) -> anyhow::Result<DataLock> {
+    acquire(file_path, false)
+}
+
+fn acquire(file_path: &Path, exclusive: bool) -> anyhow::Result<DataLock> {
+    let path = lock_path(file_path);
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)?;
+    
+    let started = Instant::now();
+    loop {
+        let result = if exclusive { file.try_lock() } else { file.try_lock_shared() };
+        match result {
+            Ok(()) => return Ok(DataLock { _file: file }),
+            Err(TryLockError::WouldBlock) if started.elapsed() < LOCK_TIMEOUT => {
+                thread::sleep(LOCK_RETRY);
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(TimeCardError::Locked(file_path.to_path_buf()).into());
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{error::TimeCardError, models::Project};
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// Version of the document layout written by this build. Bump it together
+/// with a new step in `MIGRATIONS` whenever stored data changes shape in a
+/// way serde defaults can't absorb.
+pub const CURRENT_SCHEMA_VERSION: u32 = 8;
+
+type Migration = fn(&mut Value) -> anyhow::Result<()>;
+
+/// `MIGRATIONS[n]` upgrades a version `n` document to version `n + 1`.
+/// Versions that only added data older files lack still get a step, so an
+/// older build refuses the file instead of dropping what it doesn't know.
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] = [
+    v0_project_registry,
+    // v2: tags on entries
+    added_fields,
+    // v3: clients, rates and the billable flag
+    added_fields,
+    // v4: invoices and the invoice an entry is billed on
+    added_fields,
+    // v5: pauses inside entries
+    added_fields,
+    // v6: hour targets and project budgets
+    added_fields,
+    // v7: leave
+    added_fields,
+    // v8: which tags of an entry came from its description
+    added_fields,
+];
+
+/// Documents written before versioning have no `schema_version` and are
+/// version 0.
+pub fn schema_version(doc: &Value) -> u32 {
+    doc.get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32
+}
+
+pub fn check_supported(version: u32, file_path: &Path) -> anyhow::Result<()> {
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(TimeCardError::UnsupportedSchema {
+            path: file_path.to_path_buf(),
+            version,
+            supported: CURRENT_SCHEMA_VERSION,
+        }.into());
+    }
+    Ok(())
+}
+
+/// Upgrade a document in place, one version at a time. Returns whether any
+/// migration ran.
+pub fn migrate(doc: &mut Value) -> anyhow::Result<bool> {
+    let version = schema_version(doc);
+    for (step, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(doc).map_err(|e| {
+            anyhow::anyhow!("Migrating data from schema version {} failed: {}", step, e)
+        })?;
+        doc["schema_version"] = json!(step + 1);
+    }
+    Ok(version < CURRENT_SCHEMA_VERSION)
+}
+
+/// v0 stored a free-text `project` name on each entry. Move the names into
+/// the project registry, merging names that differ only by case, and point
+/// entries at them by `project_id`.
+fn v0_project_registry(doc: &mut Value) -> anyhow::Result<()> {
+    let mut projects: Vec<Project> = match doc.get("projects") {
+        Some(projects) if !projects.is_null() => serde_json::from_value(projects.clone())?,
+        _ => Vec::new(),
+    };
+    
+    if let Some(entries) = doc.get_mut("time_entries").and_then(Value::as_array_mut) {
+        for entry in entries {
+            let Some(entry) = entry.as_object_mut() else {
+                continue;
+            };
+            let name = match entry.remove("project") {
+                Some(Value::String(name)) if !name.trim().is_empty() => name,
+                _ => continue,
+            };
+            if entry.get("project_id").is_some_and(|id| !id.is_null()) {
+                continue;
+            }
+            
+            let id = match projects.iter().find(|project| project.matches_name(&name)) {
+                Some(project) => project.id,
+                None => {
+                    let project = Project::new(name.trim().to_string(), None);
+                    let id = project.id;
+                    projects.push(project);
+                    id
+                }
+            };
+            entry.insert("project_id".to_string(), json!(id));
+        }
+    }
+    
+    doc["projects"] = serde_json::to_value(projects)?;
+    Ok(())
+}
+
+/// A step for data that serde defaults fill in when it's missing, so the
+/// document needs no change.
+fn added_fields(_doc: &mut Value) -> anyhow::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TimeCardData;
+    
+    /// A file from before versioning, with free-text project names.
+    fn v0_document() -> Value {
+        json!({
+            "time_entries": [
+                {
+                    "id": "6f1c2a4e-1111-4a5b-8c9d-000000000001",
+                    "project": "Website",
+                    "description": "Layout",
+                    "start_time": "2024-01-15T09:00:00Z",
+                    "end_time": "2024-01-15T12:00:00Z",
+                    "created_at": "2024-01-15T09:00:00Z",
+                    "updated_at": "2024-01-15T12:00:00Z"
+                },
+                {
+                    "id": "6f1c2a4e-1111-4a5b-8c9d-000000000002",
+                    "project": " website ",
+                    "description": null,
+                    "start_time": "2024-01-16T09:00:00Z",
+                    "end_time": "2024-01-16T10:00:00Z",
+                    "created_at": "2024-01-16T09:00:00Z",
+                    "updated_at": "2024-01-16T10:00:00Z"
+                },
+                {
+                    "id": "6f1c2a4e-1111-4a5b-8c9d-000000000003",
+                    "project": "",
+                    "description": "Email",
+                    "start_time": "2024-01-17T09:00:00Z",
+                    "end_time": null,
+                    "created_at": "2024-01-17T09:00:00Z",
+                    "updated_at": "2024-01-17T09:00:00Z"
+                }
+            ],
+            "projects": null,
+            "created_at": "2024-01-15T09:00:00Z",
+            "updated_at": "2024-01-17T09:00:00Z"
+        })
+    }
+    
+    #[test]
+    fn migrates_v0_to_the_current_version() {
+        let mut doc = v0_document();
+        assert_eq!(schema_version(&doc), 0);
+        
+        assert!(migrate(&mut doc).unwrap());
+        assert_eq!(schema_version(&doc), CURRENT_SCHEMA_VERSION);
+        
+        let data: TimeCardData = serde_json::from_value(doc).unwrap();
+        assert_eq!(data.projects.len(), 1);
+        assert_eq!(data.projects[0].name, "Website");
+        let project_ids: Vec<_> = data.time_entries.iter().map(|entry| entry.project_id).collect();
+        assert_eq!(project_ids, [Some(data.projects[0].id), Some(data.projects[0].id), None]);
+        assert!(data.time_entries.iter().all(|entry| entry.billable && entry.tags.is_empty()));
+    }
+    
+    #[test]
+    fn keeps_projects_already_in_the_registry() {
+        let mut doc = v0_document();
+        let existing = Project::new("WEBSITE".to_string(), None);
+        doc["projects"] = json!([existing]);
+        
+        migrate(&mut doc).unwrap();
+        
+        let data: TimeCardData = serde_json::from_value(doc).unwrap();
+        assert_eq!(data.projects.len(), 1);
+        assert_eq!(data.time_entries[0].project_id, Some(existing.id));
+    }
+    
+    #[test]
+    fn current_documents_are_left_alone() {
+        let mut doc = serde_json::to_value(TimeCardData::default()).unwrap();
+        let before = doc.clone();
+        
+        assert!(!migrate(&mut doc).unwrap());
+        assert_eq!(doc, before);
+    }
+    
+    #[test]
+    fn newer_versions_are_refused() {
+        let error = check_supported(CURRENT_SCHEMA_VERSION + 1, Path::new("timecard.json")).unwrap_err();
+        assert!(matches!(error.downcast_ref(), Some(TimeCardError::UnsupportedSchema { .. })));
+        assert!(check_supported(CURRENT_SCHEMA_VERSION, Path::new("timecard.json")).is_ok());
+    }
+}
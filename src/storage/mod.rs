@@ -0,0 +1,145 @@
+use crate::{
+    error::TimeCardError,
+    models::{Client, Invoice, Leave, Project, Rate, Settings, TimeCardData, TimeEntry},
+};
+use chrono::{DateTime, Utc};
+use std::fmt::Display;
+use std::path::Path;
+use uuid::Uuid;
+
+pub mod backup;
+mod json;
+pub mod lock;
+pub mod migrations;
+mod sqlite;
+
+pub use json::JsonStorage;
+pub use sqlite::SqliteStorage;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Backend {
+    /// Single pretty-printed JSON document
+    Json,
+    /// Embedded SQLite database
+    Sqlite,
+}
+
+impl Backend {
+    /// Guess the backend from a data file's extension, defaulting to JSON.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("db" | "sqlite" | "sqlite3") => Backend::Sqlite,
+            _ => Backend::Json,
+        }
+    }
+    
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Backend::Json => "json",
+            Backend::Sqlite => "db",
+        }
+    }
+}
+
+/// Persistent home of a `TimeCardData` document. Backends may store it as a
+/// whole or row by row; callers use the granular operations for single
+/// changes so row-based backends don't have to rewrite everything.
+pub trait Storage {
+    fn path(&self) -> &Path;
+    
+    /// Read the whole document.
+    fn load(&mut self) -> anyhow::Result<TimeCardData>;
+    
+    /// Replace the whole document.
+    fn save(&mut self, data: &TimeCardData) -> anyhow::Result<()>;
+    
+    fn load_projects(&mut self) -> anyhow::Result<Vec<Project>>;
+    
+    fn load_settings(&mut self) -> anyhow::Result<Settings>;
+    
+    /// Entries starting within `start..=end`, oldest first.
+    fn query_entries(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> anyhow::Result<Vec<TimeEntry>>;
+    
+    fn insert_entry(&mut self, entry: &TimeEntry) -> anyhow::Result<()>;
+    
+    fn update_entry(&mut self, entry: &TimeEntry) -> anyhow::Result<()>;
+    
+    fn delete_entry(&mut self, id: Uuid) -> anyhow::Result<()>;
+    
+    /// Insert or update several entries in one write, so a crash can't
+    /// leave only some of them changed.
+    fn write_entries(&mut self, entries: &[TimeEntry]) -> anyhow::Result<()>;
+    
+    fn insert_project(&mut self, project: &Project) -> anyhow::Result<()>;
+    
+    fn update_project(&mut self, project: &Project) -> anyhow::Result<()>;
+    
+    fn delete_project(&mut self, id: Uuid) -> anyhow::Result<()>;
+    
+    fn load_clients(&mut self) -> anyhow::Result<Vec<Client>>;
+    
+    fn insert_client(&mut self, client: &Client) -> anyhow::Result<()>;
+    
+    fn update_client(&mut self, client: &Client) -> anyhow::Result<()>;
+    
+    fn delete_client(&mut self, id: Uuid) -> anyhow::Result<()>;
+    
+    fn load_rates(&mut self) -> anyhow::Result<Vec<Rate>>;
+    
+    fn insert_rate(&mut self, rate: &Rate) -> anyhow::Result<()>;
+    
+    fn delete_rate(&mut self, id: Uuid) -> anyhow::Result<()>;
+    
+    fn load_invoices(&mut self) -> anyhow::Result<Vec<Invoice>>;
+    
+    fn insert_invoice(&mut self, invoice: &Invoice) -> anyhow::Result<()>;
+    
+    fn update_invoice(&mut self, invoice: &Invoice) -> anyhow::Result<()>;
+    
+    fn load_leave(&mut self) -> anyhow::Result<Vec<Leave>>;
+    
+    /// Insert several days of leave in one write, e.g. a calendar import.
+    fn insert_leave(&mut self, leave: &[Leave]) -> anyhow::Result<()>;
+    
+    fn delete_leave(&mut self, id: Uuid) -> anyhow::Result<()>;
+    
+    fn save_settings(&mut self, settings: &Settings) -> anyhow::Result<()>;
+    
+    /// Document restricted to entries starting within `start..=end`, for
+    /// reports that shouldn't read years of history.
+    fn load_period(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> anyhow::Result<TimeCardData> {
+        Ok(TimeCardData {
+            time_entries: self.query_entries(start, end)?,
+            projects: self.load_projects()?,
+            clients: self.load_clients()?,
+            rates: self.load_rates()?,
+            invoices: self.load_invoices()?,
+            leave: self.load_leave()?,
+            settings: self.load_settings()?,
+            ..TimeCardData::default()
+        })
+    }
+}
+
+/// Open the data file with an explicit backend, or one guessed from the
+/// file extension.
+/// An older file is upgraded in place under an exclusive lock, so take no
+/// lock on `path` before opening it.
+pub fn open(path: &Path, backend: Option<Backend>) -> anyhow::Result<Box<dyn Storage>> {
+    let _lock = lock::lock_exclusive(path)?;
+    match backend.unwrap_or_else(|| Backend::from_path(path)) {
+        Backend::Json => {
+            json::upgrade(path)?;
+            Ok(Box::new(JsonStorage::new(path)))
+        }
+        Backend::Sqlite => Ok(Box::new(SqliteStorage::open(path)?)),
+    }
+}
+
+/// Error for a data file whose contents can't be read back.
+fn corrupt(path: &Path, reason: impl Display) -> TimeCardError {
+    TimeCardError::StorageCorrupt {
+        path: path.to_path_buf(),
+        reason: reason.to_string(),
+    }
+}
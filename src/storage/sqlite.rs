@@ -0,0 +1,356 @@
+use super::{backup, corrupt, migrations, Storage};
+use crate::models::{Client, Invoice, Leave, Project, Rate, Settings, TimeCardData, TimeEntry};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS metadata (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS projects (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS clients (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS rates (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS invoices (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS leave (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS time_entries (
+        id TEXT PRIMARY KEY,
+        start_time TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS time_entries_start_time ON time_entries (start_time);
+";
+
+/// Rows in an embedded SQLite database. Entries and projects are stored as
+/// JSON documents next to the columns needed for lookups, so model fields
+/// can be added without schema changes.
+pub struct SqliteStorage {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        
+        let mut storage = Self {
+            path: path.to_path_buf(),
+            conn,
+        };
+        if storage.get_meta::<DateTime<Utc>>("created_at")?.is_none() {
+            let now = Utc::now();
+            storage.set_meta("schema_version", &migrations::CURRENT_SCHEMA_VERSION)?;
+            storage.set_meta("created_at", &now)?;
+            storage.set_meta("updated_at", &now)?;
+        }
+        storage.upgrade()?;
+        Ok(storage)
+    }
+    
+    /// Run document migrations over the stored rows if the database was
+    /// written by an older build.
+    fn upgrade(&mut self) -> anyhow::Result<()> {
+        // The first SQLite databases were written at schema version 1 and
+        // didn't record it
+        let version = self.get_meta::<u32>("schema_version")?.unwrap_or(1);
+        migrations::check_supported(version, &self.path)?;
+        if version == migrations::CURRENT_SCHEMA_VERSION {
+            return Ok(());
+        }
+        
+        let settings = self.load_settings()?;
+        backup::create_backup(&self.path, settings.backup_retention().max(1))?;
+        
+        let now = Utc::now();
+        let mut doc = json!({
+            "schema_version": version,
+            "time_entries": self.query_json::<Value>("SELECT data FROM time_entries ORDER BY start_time", [])?,
+            "projects": self.query_json::<Value>("SELECT data FROM projects", [])?,
+            "clients": self.query_json::<Value>("SELECT data FROM clients", [])?,
+            "rates": self.query_json::<Value>("SELECT data FROM rates", [])?,
+            "invoices": self.query_json::<Value>("SELECT data FROM invoices", [])?,
+            "leave": self.query_json::<Value>("SELECT data FROM leave", [])?,
+            "settings": settings,
+            "created_at": self.get_meta::<DateTime<Utc>>("created_at")?.unwrap_or(now),
+            "updated_at": self.get_meta::<DateTime<Utc>>("updated_at")?.unwrap_or(now),
+        });
+        migrations::migrate(&mut doc)?;
+        
+        let data: TimeCardData = serde_json::from_value(doc).map_err(|e| corrupt(&self.path, e))?;
+        self.save(&data)
+    }
+    
+    fn get_meta<T: DeserializeOwned>(&self, key: &str) -> anyhow::Result<Option<T>> {
+        let value: Option<String> = self.conn
+            .query_row("SELECT value FROM metadata WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()?;
+        match value {
+            Some(value) => Ok(Some(serde_json::from_str(&value).map_err(|e| corrupt(&self.path, e))?)),
+            None => Ok(None),
+        }
+    }
+    
+    fn set_meta<T: Serialize>(&self, key: &str, value: &T) -> anyhow::Result<()> {
+        write_meta(&self.conn, key, value)
+    }
+    
+    fn touch(&self) -> anyhow::Result<()> {
+        self.set_meta("updated_at", &Utc::now())
+    }
+    
+    fn query_json<T: DeserializeOwned>(
+        &self,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> anyhow::Result<Vec<T>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(params, |row| row.get::<_, String>(0))?;
+        let mut values = Vec::new();
+        for row in rows {
+            values.push(serde_json::from_str(&row?).map_err(|e| corrupt(&self.path, e))?);
+        }
+        Ok(values)
+    }
+}
+
+/// Fixed-width UTC timestamps so string order matches time order.
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+fn write_entry(conn: &Connection, entry: &TimeEntry) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO time_entries (id, start_time, data) VALUES (?1, ?2, ?3)",
+        params![entry.id.to_string(), timestamp(entry.start_time), serde_json::to_string(entry)?],
+    )?;
+    Ok(())
+}
+
+fn write_meta<T: Serialize>(conn: &Connection, key: &str, value: &T) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
+        params![key, serde_json::to_string(value)?],
+    )?;
+    Ok(())
+}
+
+/// Write a document into one of the `(id, data)` tables.
+fn write_doc<T: Serialize>(conn: &Connection, table: &str, id: Uuid, doc: &T) -> anyhow::Result<()> {
+    conn.execute(
+        &format!("INSERT OR REPLACE INTO {} (id, data) VALUES (?1, ?2)", table),
+        params![id.to_string(), serde_json::to_string(doc)?],
+    )?;
+    Ok(())
+}
+
+fn delete_doc(conn: &Connection, table: &str, id: Uuid) -> anyhow::Result<()> {
+    conn.execute(&format!("DELETE FROM {} WHERE id = ?1", table), params![id.to_string()])?;
+    Ok(())
+}
+
+impl Storage for SqliteStorage {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    fn load(&mut self) -> anyhow::Result<TimeCardData> {
+        let now = Utc::now();
+        Ok(TimeCardData {
+            schema_version: migrations::CURRENT_SCHEMA_VERSION,
+            time_entries: self.query_json("SELECT data FROM time_entries ORDER BY start_time", [])?,
+            projects: self.load_projects()?,
+            clients: self.load_clients()?,
+            rates: self.load_rates()?,
+            invoices: self.load_invoices()?,
+            leave: self.load_leave()?,
+            settings: self.load_settings()?,
+            created_at: self.get_meta("created_at")?.unwrap_or(now),
+            updated_at: self.get_meta("updated_at")?.unwrap_or(now),
+        })
+    }
+    
+    fn save(&mut self, data: &TimeCardData) -> anyhow::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM time_entries", [])?;
+        tx.execute("DELETE FROM projects", [])?;
+        tx.execute("DELETE FROM clients", [])?;
+        tx.execute("DELETE FROM rates", [])?;
+        tx.execute("DELETE FROM invoices", [])?;
+        tx.execute("DELETE FROM leave", [])?;
+        for entry in &data.time_entries {
+            write_entry(&tx, entry)?;
+        }
+        for project in &data.projects {
+            write_doc(&tx, "projects", project.id, project)?;
+        }
+        for client in &data.clients {
+            write_doc(&tx, "clients", client.id, client)?;
+        }
+        for rate in &data.rates {
+            write_doc(&tx, "rates", rate.id, rate)?;
+        }
+        for invoice in &data.invoices {
+            write_doc(&tx, "invoices", invoice.id, invoice)?;
+        }
+        for leave in &data.leave {
+            write_doc(&tx, "leave", leave.id, leave)?;
+        }
+        write_meta(&tx, "schema_version", &migrations::CURRENT_SCHEMA_VERSION)?;
+        write_meta(&tx, "settings", &data.settings)?;
+        write_meta(&tx, "created_at", &data.created_at)?;
+        write_meta(&tx, "updated_at", &data.updated_at)?;
+        tx.commit()?;
+        Ok(())
+    }
+    
+    fn load_projects(&mut self) -> anyhow::Result<Vec<Project>> {
+        self.query_json("SELECT data FROM projects", [])
+    }
+    
+    fn load_settings(&mut self) -> anyhow::Result<Settings> {
+        Ok(self.get_meta("settings")?.unwrap_or_default())
+    }
+    
+    fn query_entries(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> anyhow::Result<Vec<TimeEntry>> {
+        self.query_json(
+            "SELECT data FROM time_entries WHERE start_time >= ?1 AND start_time <= ?2 ORDER BY start_time",
+            params![timestamp(start), timestamp(end)],
+        )
+    }
+    
+    fn insert_entry(&mut self, entry: &TimeEntry) -> anyhow::Result<()> {
+        write_entry(&self.conn, entry)?;
+        self.touch()
+    }
+    
+    fn update_entry(&mut self, entry: &TimeEntry) -> anyhow::Result<()> {
+        write_entry(&self.conn, entry)?;
+        self.touch()
+    }
+    
+    fn delete_entry(&mut self, id: Uuid) -> anyhow::Result<()> {
+        self.conn.execute("DELETE FROM time_entries WHERE id = ?1", params![id.to_string()])?;
+        self.touch()
+    }
+    
+    fn write_entries(&mut self, entries: &[TimeEntry]) -> anyhow::Result<()> {
+        let tx = self.conn.transaction()?;
+        for entry in entries {
+            write_entry(&tx, entry)?;
+        }
+        tx.commit()?;
+        self.touch()
+    }
+    
+    fn insert_project(&mut self, project: &Project) -> anyhow::Result<()> {
+        write_doc(&self.conn, "projects", project.id, project)?;
+        self.touch()
+    }
+    
+    fn update_project(&mut self, project: &Project) -> anyhow::Result<()> {
+        write_doc(&self.conn, "projects", project.id, project)?;
+        self.touch()
+    }
+    
+    fn delete_project(&mut self, id: Uuid) -> anyhow::Result<()> {
+        delete_doc(&self.conn, "projects", id)?;
+        self.touch()
+    }
+    
+    fn load_clients(&mut self) -> anyhow::Result<Vec<Client>> {
+        self.query_json("SELECT data FROM clients", [])
+    }
+    
+    fn insert_client(&mut self, client: &Client) -> anyhow::Result<()> {
+        write_doc(&self.conn, "clients", client.id, client)?;
+        self.touch()
+    }
+    
+    fn update_client(&mut self, client: &Client) -> anyhow::Result<()> {
+        write_doc(&self.conn, "clients", client.id, client)?;
+        self.touch()
+    }
+    
+    fn delete_client(&mut self, id: Uuid) -> anyhow::Result<()> {
+        delete_doc(&self.conn, "clients", id)?;
+        self.touch()
+    }
+    
+    fn load_rates(&mut self) -> anyhow::Result<Vec<Rate>> {
+        self.query_json("SELECT data FROM rates", [])
+    }
+    
+    fn insert_rate(&mut self, rate: &Rate) -> anyhow::Result<()> {
+        write_doc(&self.conn, "rates", rate.id, rate)?;
+        self.touch()
+    }
+    
+    fn delete_rate(&mut self, id: Uuid) -> anyhow::Result<()> {
+        delete_doc(&self.conn, "rates", id)?;
+        self.touch()
+    }
+    
+    fn load_invoices(&mut self) -> anyhow::Result<Vec<Invoice>> {
+        self.query_json("SELECT data FROM invoices", [])
+    }
+    
+    fn insert_invoice(&mut self, invoice: &Invoice) -> anyhow::Result<()> {
+        write_doc(&self.conn, "invoices", invoice.id, invoice)?;
+        self.touch()
+    }
+    
+    fn update_invoice(&mut self, invoice: &Invoice) -> anyhow::Result<()> {
+        write_doc(&self.conn, "invoices", invoice.id, invoice)?;
+        self.touch()
+    }
+    
+    fn load_leave(&mut self) -> anyhow::Result<Vec<Leave>> {
+        self.query_json("SELECT data FROM leave", [])
+    }
+    
+    fn insert_leave(&mut self, leave: &[Leave]) -> anyhow::Result<()> {
+        let tx = self.conn.transaction()?;
+        for day in leave {
+            write_doc(&tx, "leave", day.id, day)?;
+        }
+        tx.commit()?;
+        self.touch()
+    }
+    
+    fn delete_leave(&mut self, id: Uuid) -> anyhow::Result<()> {
+        delete_doc(&self.conn, "leave", id)?;
+        self.touch()
+    }
+    
+    fn save_settings(&mut self, settings: &Settings) -> anyhow::Result<()> {
+        self.set_meta("settings", settings)?;
+        self.touch()
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{
+    datetime,
+    error::TimeCardError,
+    leave,
+    models::{self, Project, TimeCardData},
+    period,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+/// Share of a project budget from which it is reported as nearly used up.
+pub const BUDGET_WARNING: f64 = 0.8;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Hours worked against a target.
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    pub target: f64,
+    pub worked: f64,
+}
+
+impl Progress {
+    pub fn remaining(&self) -> f64 {
+        (self.target - self.worked).max(0.0)
+    }
+    
+    /// Worked share of the target; a zero target counts as met.
+    pub fn fraction(&self) -> f64 {
+        if self.target > 0.0 { self.worked / self.target } else { 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BudgetLevel {
+    Within,
+    /// At least [`BUDGET_WARNING`] of the budget is used
+    Warning,
+    Exceeded,
+}
+
+/// A project's budget and the hours booked on it so far.
+#[derive(Debug, Clone)]
+pub struct Budget<'a> {
+    pub project: &'a Project,
+    pub progress: Progress,
+}
+
+impl Budget<'_> {
+    pub fn level(&self) -> BudgetLevel {
+        level_of(self.progress.fraction())
+    }
+}
+
+/// Progress against the targets as of `now`.
+#[derive(Debug, Clone)]
+pub struct TargetStatus<'a> {
+    pub today: Option<Progress>,
+    pub week: Option<Progress>,
+    /// When today's target is met if the active entry keeps running
+    pub finish_at: Option<DateTime<Utc>>,
+    /// Projects with a budget, by name
+    pub budgets: Vec<Budget<'a>>,
+}
+
+pub fn target_status<'a>(data: &'a TimeCardData, tz: &Tz, now: DateTime<Utc>) -> anyhow::Result<TargetStatus<'a>> {
+    let targets = &data.settings.targets;
+    let today = datetime::today(tz);
+    let week = period::parse_period("week", tz)?;
+    
+    let today_progress = targets.date(today).map(|_| Progress {
+        target: day_target(data, today),
+        worked: worked_hours(data, today, today, tz, now),
+    });
+    let week_progress = week_target(data, week.first_day, week.last_day).map(|target| Progress {
+        target,
+        worked: worked_hours(data, week.first_day, week.last_day, tz, now),
+    });
+    
+    let finish_at = match (today_progress, data.get_active_entry()) {
+        (Some(progress), Some(active)) if !active.is_paused() && progress.remaining() > 0.0 => {
+            Some(now + Duration::seconds((progress.remaining() * 3600.0).round() as i64))
+        }
+        _ => None,
+    };
+    
+    Ok(TargetStatus {
+        today: today_progress,
+        week: week_progress,
+        finish_at,
+        budgets: budgets(data, now),
+    })
+}
+
+/// Hours expected on a date: the target of its day of the week, less the
+/// leave taken that day.
+pub fn day_target(data: &TimeCardData, date: NaiveDate) -> f64 {
+    let target = data.settings.targets.date(date).unwrap_or(0.0);
+    target - leave::excused_hours(data, date, target)
+}
+
+/// Hours expected in the week `first..=last`, less the leave taken in it,
+/// if there is a week or day target.
+pub fn week_target(data: &TimeCardData, first: NaiveDate, last: NaiveDate) -> Option<f64> {
+    let targets = &data.settings.targets;
+    let days = first.iter_days().take_while(|date| *date <= last);
+    match targets.week {
+        Some(week) => {
+            let excused = models::sum_hours(days.map(|date| {
+                leave::excused_hours(data, date, targets.date(date).unwrap_or(0.0))
+            }));
+            Some((week - excused).max(0.0))
+        }
+        None => targets.week().map(|_| models::sum_hours(days.map(|date| day_target(data, date)))),
+    }
+}
+
+/// Net hours of the entries started on the local days `first..=last`,
+/// counting the active one up to `now`.
+pub fn worked_hours(data: &TimeCardData, first: NaiveDate, last: NaiveDate, tz: &Tz, now: DateTime<Utc>) -> f64 {
+    let (start, end) = datetime::day_range(first, last, tz);
+    models::sum_hours(
+        data.get_entries_by_period(start, end)
+            .iter()
+            .map(|entry| entry.net_duration_until(now).num_seconds() as f64 / 3600.0),
+    )
+}
+
+/// Budgets of the projects that have one, by name. Archived projects are
+/// left out.
+pub fn budgets(data: &TimeCardData, now: DateTime<Utc>) -> Vec<Budget<'_>> {
+    let mut budgets: Vec<_> = data.projects
+        .iter()
+        .filter(|project| !project.archived)
+        .filter_map(|project| {
+            Some(Budget {
+                project,
+                progress: Progress {
+                    target: project.budget_hours?,
+                    worked: project_hours(data, project.id, now),
+                },
+            })
+        })
+        .collect();
+    budgets.sort_by_key(|budget| budget.project.name.to_lowercase());
+    budgets
+}
+
+/// The budget of a project if its last `added` hours took it past
+/// [`BUDGET_WARNING`] or past the whole budget.
+pub fn crossed_budget(data: &TimeCardData, project_id: Option<Uuid>, added: f64, now: DateTime<Utc>) -> Option<Budget<'_>> {
+    let project = data.get_project(project_id?)?;
+    let progress = Progress {
+        target: project.budget_hours?,
+        worked: project_hours(data, project.id, now),
+    };
+    let before = Progress { worked: progress.worked - added, ..progress };
+    let budget = Budget { project, progress };
+    (budget.level() > level_of(before.fraction())).then_some(budget)
+}
+
+fn project_hours(data: &TimeCardData, project_id: Uuid, now: DateTime<Utc>) -> f64 {
+    models::sum_hours(
+        data.time_entries
+            .iter()
+            .filter(|entry| entry.project_id == Some(project_id))
+            .map(|entry| entry.net_duration_until(now).num_seconds() as f64 / 3600.0),
+    )
+}
+
+fn level_of(fraction: f64) -> BudgetLevel {
+    if fraction >= 1.0 {
+        BudgetLevel::Exceeded
+    } else if fraction >= BUDGET_WARNING {
+        BudgetLevel::Warning
+    } else {
+        BudgetLevel::Within
+    }
+}
+
+/// Days of the week named in a comma-separated list like `mon,tue`,
+/// `friday`, `weekdays`, `weekend` or `all`.
+pub fn parse_days(input: &str) -> anyhow::Result<Vec<Weekday>> {
+    let mut days = Vec::new();
+    for name in input.split(',').map(|name| name.trim().to_lowercase()) {
+        match name.as_str() {
+            "weekdays" => days.extend(&WEEKDAYS[..5]),
+            "weekend" => days.extend(&WEEKDAYS[5..]),
+            "all" => days.extend(&WEEKDAYS),
+            _ => days.push(name.parse::<Weekday>().map_err(|_| {
+                TimeCardError::InvalidInput(format!(
+                    "Invalid day: {}. Use a day of the week, weekdays, weekend or all",
+                    name
+                ))
+            })?),
+        }
+    }
+    days.sort_by_key(|day| day.num_days_from_monday());
+    days.dedup();
+    Ok(days)
+}
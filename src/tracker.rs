@@ -0,0 +1,762 @@
+use crate::{
+    doctor,
+    error::TimeCardError,
+    models::{self, Pause, Project, TimeCardData, TimeEntry},
+    storage::{self, Backend, Storage},
+};
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use std::path::Path;
+use uuid::Uuid;
+
+/// What to work on: the fields a new entry starts out with.
+#[derive(Debug, Clone)]
+pub struct Activity {
+    /// Project name; unknown projects are created
+    pub project: Option<String>,
+    /// Description; `+tag` words in it become tags
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub billable: bool,
+}
+
+/// A manual entry with both ends known.
+#[derive(Debug, Clone)]
+pub struct NewEntry {
+    pub activity: Activity,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Requested changes to an entry. `None` leaves a field untouched.
+#[derive(Debug, Clone, Default)]
+pub struct EntryChanges {
+    pub project: Option<String>,
+    pub clear_project: bool,
+    pub description: Option<String>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    pub billable: Option<bool>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    /// Move the whole entry, breaks included, by this much
+    pub shift: Option<Duration>,
+}
+
+impl EntryChanges {
+    pub fn is_empty(&self) -> bool {
+        self.project.is_none()
+            && !self.clear_project
+            && self.description.is_none()
+            && self.add_tags.is_empty()
+            && self.remove_tags.is_empty()
+            && self.billable.is_none()
+            && self.start.is_none()
+            && self.end.is_none()
+            && self.shift.is_none()
+    }
+}
+
+/// A newly started entry.
+#[derive(Debug, Clone)]
+pub struct Started {
+    pub entry: TimeEntry,
+    /// The project, if starting the entry created it
+    pub created_project: Option<Project>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Switched {
+    /// The entry that was stopped
+    pub previous: TimeEntry,
+    pub next: TimeEntry,
+    pub created_project: Option<Project>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Added {
+    pub entry: TimeEntry,
+    pub created_project: Option<Project>,
+    /// Existing entries the new one overlaps
+    pub overlaps: Vec<TimeEntry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Edited {
+    pub before: TimeEntry,
+    pub after: TimeEntry,
+    pub created_project: Option<Project>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Merged {
+    /// The earliest entry, now spanning all of them
+    pub entry: TimeEntry,
+    /// All merged entries as they were before, earliest first
+    pub before: Vec<TimeEntry>,
+}
+
+/// The operations on time entries behind the CLI and the GUI. Each call
+/// loads the latest data, checks the change and writes it to the store.
+///
+/// The tracker doesn't lock the data file; front-ends hold
+/// [`storage::lock`] around calls when other processes may write too.
+pub struct TimeTracker {
+    store: Box<dyn Storage>,
+    backed_up: bool,
+}
+
+impl TimeTracker {
+    pub fn new(store: Box<dyn Storage>) -> Self {
+        Self { store, backed_up: false }
+    }
+    
+    /// Open the data file with an explicit backend, or one guessed from the
+    /// file extension.
+    pub fn open(path: &Path, backend: Option<Backend>) -> anyhow::Result<Self> {
+        Ok(Self::new(storage::open(path, backend)?))
+    }
+    
+    /// The underlying store, for changes the tracker doesn't cover.
+    pub fn store(&mut self) -> &mut dyn Storage {
+        self.store.as_mut()
+    }
+    
+    /// Back up the data file before the first change made through this
+    /// tracker. Later calls do nothing, so long sessions like the GUI back
+    /// up once, and only if they change something.
+    pub fn back_up_once(&mut self) -> anyhow::Result<()> {
+        if !self.backed_up {
+            let retention = self.store.load_settings()?.backup_retention();
+            storage::backup::create_backup(self.store.path(), retention)?;
+            self.backed_up = true;
+        }
+        Ok(())
+    }
+    
+    pub fn load(&mut self) -> anyhow::Result<TimeCardData> {
+        self.store.load()
+    }
+    
+    /// The configured timezone, for reading and showing local times.
+    pub fn timezone(&mut self) -> anyhow::Result<Tz> {
+        self.store.load_settings()?.timezone()
+    }
+    
+    pub fn active_entry(&mut self) -> anyhow::Result<Option<TimeEntry>> {
+        Ok(self.store.load()?.get_active_entry().cloned())
+    }
+    
+    /// Start a new entry now, or at `at`.
+    pub fn clock_in(&mut self, activity: Activity, at: Option<DateTime<Utc>>) -> anyhow::Result<Started> {
+        let mut data = self.store.load()?;
+        let tz = data.settings.timezone()?;
+        let start_time = resolve_at(at, &tz)?;
+        
+        if let Some(active) = data.get_active_entry() {
+            return Err(already_clocked_in(active, &tz));
+        }
+        check_after_previous(&data, start_time, &tz)?;
+        
+        let (mut entry, created_project) = new_entry(&mut data, activity)?;
+        entry.start_time = start_time;
+        
+        if let Some(project) = &created_project {
+            self.store.insert_project(project)?;
+        }
+        self.store.insert_entry(&entry)?;
+        
+        Ok(Started { entry, created_project })
+    }
+    
+    /// Finish the active entry now, or at `at`, optionally replacing its
+    /// description.
+    pub fn clock_out(&mut self, description: Option<String>, at: Option<DateTime<Utc>>) -> anyhow::Result<TimeEntry> {
+        let mut data = self.store.load()?;
+        let tz = data.settings.timezone()?;
+        let end_time = resolve_at(at, &tz)?;
+        
+        let entry = active_mut(&mut data)?;
+        stop_at(entry, end_time, &tz)?;
+        if let Some(desc) = description {
+            entry.set_description(Some(desc));
+        }
+        
+        self.store.update_entry(entry)?;
+        
+        Ok(entry.clone())
+    }
+    
+    /// Stop the active entry and start a new one at the same instant, in one
+    /// write.
+    pub fn switch(&mut self, activity: Activity, at: Option<DateTime<Utc>>) -> anyhow::Result<Switched> {
+        let mut data = self.store.load()?;
+        let tz = data.settings.timezone()?;
+        let switch_time = resolve_at(at, &tz)?;
+        
+        let active_id = active_mut(&mut data)?.id;
+        let (mut next, created_project) = new_entry(&mut data, activity)?;
+        next.start_time = switch_time;
+        
+        let previous = data.get_entry_mut(active_id).unwrap();
+        stop_at(previous, switch_time, &tz)?;
+        let previous = previous.clone();
+        
+        // A new project is saved together with both entries
+        if created_project.is_some() {
+            data.time_entries.push(next.clone());
+            self.store.save(&data)?;
+        } else {
+            self.store.write_entries(&[previous.clone(), next.clone()])?;
+        }
+        
+        Ok(Switched { previous, next, created_project })
+    }
+    
+    /// Start a new entry with the project, description and tags of an
+    /// earlier one: the given entry, or the most recent.
+    pub fn continue_entry(&mut self, id_prefix: Option<&str>) -> anyhow::Result<TimeEntry> {
+        let data = self.store.load()?;
+        let tz = data.settings.timezone()?;
+        
+        if let Some(active) = data.get_active_entry() {
+            return Err(already_clocked_in(active, &tz));
+        }
+        
+        let template = match id_prefix {
+            Some(prefix) => {
+                let id = data.find_entry_by_prefix(prefix)?;
+                data.time_entries.iter().find(|entry| entry.id == id).unwrap()
+            }
+            None => data.time_entries
+                .iter()
+                .max_by_key(|entry| entry.start_time)
+                .ok_or_else(|| TimeCardError::NotFound("No earlier entry to continue".to_string()))?,
+        };
+        if let Some(project) = template.project_id.and_then(|id| data.get_project(id)) {
+            if project.archived {
+                return Err(TimeCardError::InvalidInput(format!(
+                    "Project is archived: {}. Unarchive it first.",
+                    project.name
+                )).into());
+            }
+        }
+        
+        let mut entry = TimeEntry::new(template.project_id, None);
+        entry.description = template.description.clone();
+        entry.tags = template.tags.clone();
+        entry.description_tags = template.description_tags.clone();
+        entry.billable = template.billable;
+        
+        self.store.insert_entry(&entry)?;
+        
+        Ok(entry)
+    }
+    
+    /// Discard the active entry, e.g. one started by mistake.
+    pub fn cancel(&mut self) -> anyhow::Result<TimeEntry> {
+        let mut data = self.store.load()?;
+        let entry = active_mut(&mut data)?.clone();
+        
+        self.store.delete_entry(entry.id)?;
+        
+        Ok(entry)
+    }
+    
+    /// Start a break in the active entry.
+    pub fn pause(&mut self, at: Option<DateTime<Utc>>) -> anyhow::Result<TimeEntry> {
+        let mut data = self.store.load()?;
+        let tz = data.settings.timezone()?;
+        let time = resolve_at(at, &tz)?;
+        
+        let entry = active_mut(&mut data)?;
+        if entry.is_paused() {
+            return Err(TimeCardError::AlreadyPaused.into());
+        }
+        entry.pause(time)?;
+        
+        self.store.update_entry(entry)?;
+        
+        Ok(entry.clone())
+    }
+    
+    /// End the break in the active entry.
+    pub fn resume(&mut self, at: Option<DateTime<Utc>>) -> anyhow::Result<TimeEntry> {
+        let mut data = self.store.load()?;
+        let tz = data.settings.timezone()?;
+        let time = resolve_at(at, &tz)?;
+        
+        let entry = active_mut(&mut data)?;
+        if !entry.is_paused() {
+            return Err(TimeCardError::NotPaused.into());
+        }
+        entry.resume(time)?;
+        
+        self.store.update_entry(entry)?;
+        
+        Ok(entry.clone())
+    }
+    
+    /// Record a finished entry after the fact. Overlapping existing entries
+    /// are reported, not refused.
+    pub fn add(&mut self, new: NewEntry) -> anyhow::Result<Added> {
+        let mut data = self.store.load()?;
+        
+        if new.end <= new.start {
+            return Err(TimeCardError::InvalidDatetime("End time must be after start time".to_string()).into());
+        }
+        
+        let (mut entry, created_project) = new_entry(&mut data, new.activity)?;
+        entry.start_time = new.start;
+        entry.end_time = Some(new.end);
+        entry.updated_at = Utc::now();
+        
+        let overlaps = doctor::overlapping(&data, new.start, new.end).into_iter().cloned().collect();
+        
+        if let Some(project) = &created_project {
+            self.store.insert_project(project)?;
+        }
+        self.store.insert_entry(&entry)?;
+        
+        Ok(Added { entry, created_project, overlaps })
+    }
+    
+    /// Change an entry picked by id prefix. Invoiced entries can't change.
+    pub fn edit(&mut self, id_prefix: &str, changes: EntryChanges) -> anyhow::Result<Edited> {
+        let mut data = self.store.load()?;
+        
+        let id = data.find_entry_by_prefix(id_prefix)?;
+        let edited = change_entry(&mut data, id, &changes)?;
+        
+        if let Some(project) = &edited.created_project {
+            self.store.insert_project(project)?;
+        }
+        self.store.update_entry(&edited.after)?;
+        
+        Ok(edited)
+    }
+    
+    /// Make the same change to several entries, saved together: if one of
+    /// them can't be changed, none is.
+    pub fn edit_all(&mut self, ids: &[Uuid], changes: EntryChanges) -> anyhow::Result<Vec<Edited>> {
+        let mut data = self.store.load()?;
+        
+        let edited = ids
+            .iter()
+            .map(|&id| change_entry(&mut data, id, &changes))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.store.save(&data)?;
+        
+        Ok(edited)
+    }
+    
+    /// Delete an entry picked by id prefix. Invoiced entries can't be deleted.
+    pub fn delete(&mut self, id_prefix: &str) -> anyhow::Result<TimeEntry> {
+        let mut data = self.store.load()?;
+        
+        let id = data.find_entry_by_prefix(id_prefix)?;
+        check_not_invoiced(&data, id)?;
+        let entry = data.remove_time_entry(id).unwrap();
+        
+        self.store.delete_entry(id)?;
+        
+        Ok(entry)
+    }
+    
+    /// Delete several entries together: if one of them can't be deleted,
+    /// none is.
+    pub fn delete_all(&mut self, ids: &[Uuid]) -> anyhow::Result<Vec<TimeEntry>> {
+        let mut data = self.store.load()?;
+        
+        let mut deleted = Vec::new();
+        for &id in ids {
+            check_not_invoiced(&data, id)?;
+            deleted.push(data.remove_time_entry(id).ok_or_else(|| no_entry(id))?);
+        }
+        self.store.save(&data)?;
+        
+        Ok(deleted)
+    }
+    
+    /// Join finished entries of one project that follow each other into the
+    /// earliest one. It runs until the latest ends, and the gaps between
+    /// them become breaks, so the worked time stays the same.
+    pub fn merge(&mut self, ids: &[Uuid]) -> anyhow::Result<Merged> {
+        let data = self.store.load()?;
+        let now = Utc::now();
+        
+        let mut before = Vec::new();
+        for &id in ids {
+            let entry = data.time_entries
+                .iter()
+                .find(|entry| entry.id == id)
+                .ok_or_else(|| no_entry(id))?;
+            check_not_invoiced(&data, id)?;
+            if entry.is_active() {
+                return Err(TimeCardError::InvalidInput("The active entry can't be merged".to_string()).into());
+            }
+            before.push(entry.clone());
+        }
+        if before.len() < 2 {
+            return Err(TimeCardError::InvalidInput("Pick at least two entries to merge".to_string()).into());
+        }
+        before.sort_by_key(|entry| entry.start_time);
+        
+        let first = &before[0];
+        if before.iter().any(|entry| entry.project_id != first.project_id || entry.billable != first.billable) {
+            return Err(TimeCardError::InvalidInput(
+                "Only entries of the same project and billability can be merged".to_string()
+            ).into());
+        }
+        
+        let mut merged = first.clone();
+        for next in &before[1..] {
+            let end = merged.end_time.unwrap();
+            if next.start_time < end {
+                return Err(TimeCardError::Overlap(format!("Entry {} overlaps the one before it", next.short_id())).into());
+            }
+            // Anything overlapping the gap would overlap the merged entry
+            let between = data.time_entries.iter().find(|other| {
+                !ids.contains(&other.id)
+                    && other.start_time < next.start_time
+                    && other.end_time.unwrap_or(now) > end
+            });
+            if let Some(other) = between {
+                return Err(TimeCardError::InvalidInput(format!(
+                    "Entry {} overlaps the time between the entries to merge",
+                    other.short_id()
+                )).into());
+            }
+            
+            if next.start_time > end {
+                merged.pauses.push(Pause { start: end, end: Some(next.start_time) });
+            }
+            merged.pauses.extend(next.pauses.iter().cloned());
+            merged.end_time = next.end_time;
+            merged.tags.extend(next.tags.iter().cloned());
+            merged.description_tags.extend(next.description_tags.iter().cloned());
+            if let Some(desc) = &next.description {
+                merged.description = match merged.description {
+                    Some(existing) if existing.split("; ").any(|part| part == desc) => Some(existing),
+                    Some(existing) => Some(format!("{}; {}", existing, desc)),
+                    None => Some(desc.clone()),
+                };
+            }
+        }
+        merged.updated_at = now;
+        merged.check_pauses()?;
+        
+        let removed: Vec<Uuid> = before[1..].iter().map(|entry| entry.id).collect();
+        self.replace_entries(data, std::slice::from_ref(&merged), &removed)?;
+        
+        Ok(Merged { entry: merged, before })
+    }
+    
+    /// Put entries back the way they were, e.g. to undo a change: `entries`
+    /// are written as given and the entries in `remove` deleted.
+    pub fn restore(&mut self, entries: &[TimeEntry], remove: &[Uuid]) -> anyhow::Result<()> {
+        let data = self.store.load()?;
+        self.replace_entries(data, entries, remove)
+    }
+    
+    /// Write `entries` over their stored versions, or add them, and delete
+    /// the entries in `remove`, all in one save.
+    fn replace_entries(&mut self, mut data: TimeCardData, entries: &[TimeEntry], remove: &[Uuid]) -> anyhow::Result<()> {
+        data.time_entries.retain(|entry| !remove.contains(&entry.id));
+        for entry in entries {
+            match data.time_entries.iter_mut().find(|stored| stored.id == entry.id) {
+                Some(stored) => *stored = entry.clone(),
+                None => data.time_entries.push(entry.clone()),
+            }
+        }
+        self.store.save(&data)
+    }
+}
+
+/// An entry for `activity`, not yet stored, and its project if it had to be
+/// created in `data`.
+fn new_entry(data: &mut TimeCardData, activity: Activity) -> anyhow::Result<(TimeEntry, Option<Project>)> {
+    let (project_id, created_project) = match activity.project.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => {
+            let (id, created) = data.resolve_project(name, true)?;
+            (Some(id), data.get_project(id).filter(|_| created).cloned())
+        }
+        _ => (None, None),
+    };
+    
+    let mut entry = TimeEntry::new(project_id, activity.description);
+    entry.add_tags(&activity.tags)?;
+    entry.billable = activity.billable;
+    
+    Ok((entry, created_project))
+}
+
+/// Apply `changes` to an entry in `data`, creating the project it's moved
+/// to if needed.
+fn change_entry(data: &mut TimeCardData, id: Uuid, changes: &EntryChanges) -> anyhow::Result<Edited> {
+    if changes.is_empty() {
+        return Err(TimeCardError::InvalidInput("Nothing to change".to_string()).into());
+    }
+    
+    check_not_invoiced(data, id)?;
+    let before = data.time_entries.iter().find(|entry| entry.id == id).cloned().ok_or_else(|| no_entry(id))?;
+    let mut after = before.clone();
+    
+    let mut created_project = None;
+    if changes.clear_project {
+        after.project_id = None;
+    } else if let Some(name) = &changes.project {
+        let (project_id, created) = data.resolve_project(name, true)?;
+        after.project_id = Some(project_id);
+        created_project = data.get_project(project_id).filter(|_| created).cloned();
+    }
+    if let Some(desc) = &changes.description {
+        after.set_description(if desc.is_empty() { None } else { Some(desc.clone()) });
+    }
+    after.add_tags(&changes.add_tags)?;
+    for tag in changes.remove_tags.iter().filter_map(|tag| models::normalize_tag(tag)) {
+        after.remove_tag(&tag);
+    }
+    if let Some(billable) = changes.billable {
+        after.billable = billable;
+    }
+    if let Some(shift) = changes.shift {
+        after.shift(shift);
+    }
+    if let Some(start) = changes.start {
+        after.start_time = start;
+    }
+    if let Some(end) = changes.end {
+        after.end_time = Some(end);
+    }
+    
+    if let Some(end_time) = after.end_time {
+        if end_time <= after.start_time {
+            return Err(TimeCardError::InvalidDatetime("End time must be after start time".to_string()).into());
+        }
+    }
+    after.check_pauses()?;
+    
+    after.updated_at = Utc::now();
+    if let Some(stored) = data.time_entries.iter_mut().find(|entry| entry.id == id) {
+        *stored = after.clone();
+    }
+    
+    Ok(Edited { before, after, created_project })
+}
+
+fn no_entry(id: Uuid) -> anyhow::Error {
+    TimeCardError::NotFound(format!("No time entry matches id: {}", id)).into()
+}
+
+fn active_mut(data: &mut TimeCardData) -> anyhow::Result<&mut TimeEntry> {
+    data.time_entries
+        .iter_mut()
+        .find(|entry| entry.is_active())
+        .ok_or_else(|| TimeCardError::NotClockedIn.into())
+}
+
+fn already_clocked_in(active: &TimeEntry, tz: &Tz) -> anyhow::Error {
+    TimeCardError::AlreadyClockedIn {
+        since: active.start_time.with_timezone(tz).format("%Y-%m-%d %H:%M:%S").to_string(),
+    }.into()
+}
+
+/// When a clock change takes effect: now, or `at`, which can't be in the
+/// future.
+fn resolve_at(at: Option<DateTime<Utc>>, tz: &Tz) -> anyhow::Result<DateTime<Utc>> {
+    let now = Utc::now();
+    let Some(time) = at else {
+        return Ok(now);
+    };
+    if time > now {
+        return Err(TimeCardError::InvalidDatetime(format!(
+            "{} is in the future",
+            time.with_timezone(tz).format("%Y-%m-%d %H:%M:%S")
+        )).into());
+    }
+    Ok(time)
+}
+
+/// A new entry can't start before the most recent entry ended. Entries
+/// added ahead of time, ending in the future, don't count.
+fn check_after_previous(data: &TimeCardData, start_time: DateTime<Utc>, tz: &Tz) -> anyhow::Result<()> {
+    let now = Utc::now();
+    let previous_end = data.time_entries
+        .iter()
+        .filter_map(|entry| entry.end_time)
+        .filter(|end| *end <= now)
+        .max();
+    match previous_end {
+        Some(end) if start_time < end => Err(TimeCardError::Overlap(format!(
+            "{} is before the previous entry ended ({})",
+            start_time.with_timezone(tz).format("%Y-%m-%d %H:%M:%S"),
+            end.with_timezone(tz).format("%Y-%m-%d %H:%M:%S")
+        )).into()),
+        _ => Ok(()),
+    }
+}
+
+/// Finish the active entry at `end_time`, which must come after its start
+/// and its breaks.
+fn stop_at(entry: &mut TimeEntry, end_time: DateTime<Utc>, tz: &Tz) -> anyhow::Result<()> {
+    if end_time <= entry.start_time {
+        return Err(TimeCardError::InvalidDatetime(format!(
+            "{} is not after the entry started ({})",
+            end_time.with_timezone(tz).format("%Y-%m-%d %H:%M:%S"),
+            entry.start_time.with_timezone(tz).format("%Y-%m-%d %H:%M:%S")
+        )).into());
+    }
+    entry.stop(end_time);
+    entry.check_pauses()
+}
+
+/// Billed entries are frozen so invoices keep matching the time they list.
+fn check_not_invoiced(data: &TimeCardData, id: Uuid) -> anyhow::Result<()> {
+    let invoice_id = data.time_entries.iter().find(|entry| entry.id == id).and_then(|entry| entry.invoice_id);
+    match invoice_id.and_then(|invoice_id| data.invoices.iter().find(|invoice| invoice.id == invoice_id)) {
+        Some(invoice) => Err(TimeCardError::Invoiced { number: invoice.number.clone() }.into()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    /// A tracker on a fresh data file in UTC, and the directory holding it.
+    fn tracker(backend: Backend) -> (TimeTracker, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let file = match backend {
+            Backend::Json => "timecard.json",
+            Backend::Sqlite => "timecard.db",
+        };
+        let mut tracker = TimeTracker::open(&dir.path().join(file), Some(backend)).unwrap();
+        let mut data = tracker.load().unwrap();
+        data.settings.timezone = Some("UTC".to_string());
+        tracker.store().save(&data).unwrap();
+        (tracker, dir)
+    }
+    
+    fn activity(project: &str, description: Option<&str>) -> Activity {
+        Activity {
+            project: Some(project.to_string()),
+            description: description.map(str::to_string),
+            tags: Vec::new(),
+            billable: true,
+        }
+    }
+    
+    fn stored(data: &TimeCardData, id: Uuid) -> &TimeEntry {
+        data.time_entries.iter().find(|entry| entry.id == id).unwrap()
+    }
+    
+    fn ago(minutes: i64) -> DateTime<Utc> {
+        Utc::now() - Duration::minutes(minutes)
+    }
+    
+    /// Finished entries of one project, from `(start, end)` minutes ago.
+    fn add_entries(tracker: &mut TimeTracker, project: &str, times: &[(i64, i64)]) -> Vec<Uuid> {
+        let now = Utc::now();
+        times.iter().map(|&(start, end)| {
+            let added = tracker.add(NewEntry {
+                activity: activity(project, None),
+                start: now - Duration::minutes(start),
+                end: now - Duration::minutes(end),
+            }).unwrap();
+            added.entry.id
+        }).collect()
+    }
+    
+    #[test]
+    fn switch_stops_and_starts_at_the_same_instant() {
+        for backend in [Backend::Json, Backend::Sqlite] {
+            let (mut tracker, _dir) = tracker(backend);
+            tracker.clock_in(activity("Web", None), Some(ago(120))).unwrap();
+            tracker.clock_in(activity("Web", None), None).unwrap_err();
+            
+            let at = ago(60);
+            let switched = tracker.switch(activity("Web", Some("Review +meeting")), Some(at)).unwrap();
+            
+            assert!(switched.created_project.is_none());
+            let data = tracker.load().unwrap();
+            assert_eq!(data.time_entries.len(), 2);
+            let previous = stored(&data, switched.previous.id);
+            assert_eq!(previous.end_time, Some(at));
+            let active = data.get_active_entry().unwrap();
+            assert_eq!((active.id, active.start_time), (switched.next.id, at));
+            assert!(active.tags.contains("meeting"));
+        }
+    }
+    
+    #[test]
+    fn switch_saves_a_new_project_with_both_entries() {
+        for backend in [Backend::Json, Backend::Sqlite] {
+            let (mut tracker, _dir) = tracker(backend);
+            tracker.clock_in(activity("Web", None), Some(ago(120))).unwrap();
+            
+            let switched = tracker.switch(activity("Support", None), None).unwrap();
+            
+            let project = switched.created_project.unwrap();
+            let data = tracker.load().unwrap();
+            assert_eq!(data.find_project("support").map(|p| p.id), Some(project.id));
+            assert_eq!(data.get_active_entry().unwrap().project_id, Some(project.id));
+            assert!(stored(&data, switched.previous.id).end_time.is_some());
+        }
+    }
+    
+    #[test]
+    fn switch_changes_nothing_when_it_fails() {
+        let (mut tracker, _dir) = tracker(Backend::Json);
+        tracker.switch(activity("Web", None), None).unwrap_err();
+        
+        let started = tracker.clock_in(activity("Web", None), Some(ago(30))).unwrap();
+        tracker.switch(activity("Support", None), Some(ago(60))).unwrap_err();
+        
+        let data = tracker.load().unwrap();
+        assert_eq!(data.time_entries.len(), 1);
+        assert_eq!(data.get_active_entry().unwrap().id, started.entry.id);
+        assert!(data.find_project("Support").is_none());
+    }
+    
+    #[test]
+    fn merge_joins_entries_with_the_gaps_as_breaks() {
+        let (mut tracker, _dir) = tracker(Backend::Json);
+        let ids = add_entries(&mut tracker, "Web", &[(300, 240), (230, 200), (200, 180)]);
+        
+        let merged = tracker.merge(&[ids[2], ids[0], ids[1]]).unwrap();
+        
+        assert_eq!(merged.entry.id, ids[0]);
+        assert_eq!(merged.before.iter().map(|entry| entry.id).collect::<Vec<_>>(), ids);
+        let data = tracker.load().unwrap();
+        assert_eq!(data.time_entries.len(), 1);
+        let entry = &data.time_entries[0];
+        assert_eq!((entry.start_time, entry.end_time), (merged.before[0].start_time, merged.before[2].end_time));
+        assert_eq!(entry.pauses.len(), 1);
+        assert_eq!(entry.duration(), Some(Duration::minutes(110)));
+    }
+    
+    #[test]
+    fn merge_refuses_entries_around_another() {
+        let (mut tracker, _dir) = tracker(Backend::Json);
+        let ids = add_entries(&mut tracker, "Web", &[(300, 240), (180, 120)]);
+        add_entries(&mut tracker, "Support", &[(250, 200)]);
+        
+        let error = tracker.merge(&ids).unwrap_err();
+        
+        assert!(error.to_string().contains("overlaps the time between"), "{}", error);
+        assert_eq!(tracker.load().unwrap().time_entries.len(), 3);
+    }
+    
+    #[test]
+    fn merge_refuses_mixed_projects_and_single_entries() {
+        let (mut tracker, _dir) = tracker(Backend::Json);
+        let web = add_entries(&mut tracker, "Web", &[(300, 240)]);
+        let support = add_entries(&mut tracker, "Support", &[(180, 120)]);
+        
+        tracker.merge(&[web[0], support[0]]).unwrap_err();
+        tracker.merge(&web).unwrap_err();
+        assert_eq!(tracker.load().unwrap().time_entries.len(), 2);
+    }
+}
@@ -0,0 +1,724 @@
+use crate::gui::{read_disk_stamp, DISK_CHECK_INTERVAL, PERIOD_PRESETS};
+use chrono::{Duration, Utc};
+use chrono_tz::Tz;
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Cell, Clear, Paragraph, Row, Table, TableState, Tabs},
+    DefaultTerminal, Frame,
+};
+use std::collections::BTreeSet;
+use std::time::{Duration as StdDuration, Instant, SystemTime};
+use timecard::{
+    datetime,
+    models::{self, TagFilter, TimeCardData, TimeEntry},
+    period,
+    report::{self, Report},
+    storage::lock,
+    tracker::{Activity, EntryChanges, TimeTracker},
+};
+use uuid::Uuid;
+
+/// How long to wait for a key before redrawing the running timer.
+const TICK: StdDuration = StdDuration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Entries,
+    Report,
+}
+
+/// What a picked project is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Purpose {
+    ClockIn,
+    Switch,
+}
+
+/// A line in the project picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Choice {
+    NoProject,
+    Existing(String),
+    /// The typed name, created on clock-in
+    Create(String),
+}
+
+enum Mode {
+    Normal,
+    PickProject { purpose: Purpose, query: String, selected: usize },
+    Describe { purpose: Purpose, project: Option<String>, input: String },
+    Edit(EditForm),
+    ConfirmDelete { id: Uuid },
+}
+
+/// The editable fields of one entry, as text.
+struct EditForm {
+    id: Uuid,
+    fields: Vec<Field>,
+    focus: usize,
+}
+
+struct Field {
+    label: &'static str,
+    original: String,
+    value: String,
+}
+
+impl Field {
+    fn new(label: &'static str, value: String) -> Self {
+        Self { label, original: value.clone(), value }
+    }
+    
+    fn changed(&self) -> Option<&str> {
+        Some(self.value.trim()).filter(|value| *value != self.original)
+    }
+}
+
+const PROJECT: usize = 0;
+const DESCRIPTION: usize = 1;
+const TAGS: usize = 2;
+const START: usize = 3;
+const END: usize = 4;
+
+impl EditForm {
+    fn new(entry: &TimeEntry, data: &TimeCardData, tz: &Tz) -> Self {
+        let local = |time: chrono::DateTime<Utc>| time.with_timezone(tz).format("%Y-%m-%d %H:%M").to_string();
+        Self {
+            id: entry.id,
+            fields: vec![
+                Field::new("Project", data.project_name(entry.project_id).unwrap_or_default().to_string()),
+                Field::new("Description", entry.description.clone().unwrap_or_default()),
+                Field::new("Tags", entry.tags.iter().map(|tag| format!("+{}", tag)).collect::<Vec<_>>().join(" ")),
+                Field::new("Start", local(entry.start_time)),
+                Field::new("End", entry.end_time.map(local).unwrap_or_default()),
+            ],
+            focus: 0,
+        }
+    }
+    
+    /// The changes the form asks for. Times are only parsed when edited, so
+    /// an untouched start keeps its seconds.
+    fn changes(&self, tz: &Tz) -> anyhow::Result<EntryChanges> {
+        let mut changes = EntryChanges::default();
+        match self.fields[PROJECT].changed() {
+            Some("") => changes.clear_project = true,
+            Some(name) => changes.project = Some(name.to_string()),
+            None => {}
+        }
+        changes.description = self.fields[DESCRIPTION].changed().map(str::to_string);
+        if self.fields[TAGS].changed().is_some() {
+            let before = parse_tags(&self.fields[TAGS].original);
+            let after = parse_tags(&self.fields[TAGS].value);
+            changes.add_tags = after.difference(&before).cloned().collect();
+            changes.remove_tags = before.difference(&after).cloned().collect();
+        }
+        changes.start = self.fields[START].changed().map(|start| datetime::parse_datetime(start, tz)).transpose()?;
+        changes.end = self.fields[END].changed().map(|end| datetime::parse_datetime(end, tz)).transpose()?;
+        Ok(changes)
+    }
+}
+
+fn parse_tags(tags: &str) -> BTreeSet<String> {
+    tags.split_whitespace().map(|tag| tag.trim_start_matches('+').to_string()).collect()
+}
+
+/// Outcome of the last action, shown in the footer.
+enum Message {
+    Info(String),
+    Error(String),
+}
+
+pub struct TimeCardTui {
+    tracker: TimeTracker,
+    data: TimeCardData,
+    tz: Tz,
+    pane: Pane,
+    entries: TableState,
+    period: usize,
+    mode: Mode,
+    message: Option<Message>,
+    matcher: SkimMatcherV2,
+    disk_stamp: Option<(SystemTime, u64)>,
+    last_disk_check: Instant,
+    quit: bool,
+}
+
+/// Run the terminal UI until the user quits, restoring the terminal even
+/// when drawing fails.
+pub fn run(tracker: TimeTracker) -> anyhow::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = TimeCardTui::new(tracker).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl TimeCardTui {
+    pub fn new(tracker: TimeTracker) -> Self {
+        let mut app = Self {
+            tracker,
+            data: TimeCardData::default(),
+            tz: datetime::system_timezone(),
+            pane: Pane::Entries,
+            entries: TableState::default().with_selected(Some(0)),
+            period: 0,
+            mode: Mode::Normal,
+            message: None,
+            matcher: SkimMatcherV2::default(),
+            disk_stamp: None,
+            last_disk_check: Instant::now(),
+            quit: false,
+        };
+        app.reload();
+        app
+    }
+    
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<()> {
+        while !self.quit {
+            if self.last_disk_check.elapsed() >= DISK_CHECK_INTERVAL {
+                if read_disk_stamp(self.tracker.store().path()) != self.disk_stamp {
+                    self.reload();
+                }
+                self.last_disk_check = Instant::now();
+            }
+            
+            terminal.draw(|frame| self.draw(frame))?;
+            
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.handle_key(key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    fn reload(&mut self) {
+        match self.tracker.load() {
+            Ok(data) => {
+                self.tz = data.settings.timezone().unwrap_or_else(|_| datetime::system_timezone());
+                self.data = data;
+                self.disk_stamp = read_disk_stamp(self.tracker.store().path());
+            }
+            Err(e) => self.message = Some(Message::Error(format!("Error reloading data: {:#}", e))),
+        }
+        let last = self.data.time_entries.len().saturating_sub(1);
+        let selected = self.entries.selected().unwrap_or(0).min(last);
+        self.entries.select(Some(selected));
+    }
+    
+    /// Run a change under the data file lock, backing the file up before
+    /// the session's first change, then show its result or why it failed.
+    fn with_lock(&mut self, action: impl FnOnce(&mut TimeTracker) -> anyhow::Result<String>) {
+        self.message = Some(match lock::lock_exclusive(self.tracker.store().path()) {
+            Ok(_lock) => match self.tracker.back_up_once().and_then(|_| action(&mut self.tracker)) {
+                Ok(done) => Message::Info(done),
+                Err(e) => Message::Error(format!("{:#}", e)),
+            },
+            Err(e) => Message::Error(format!("{:#}", e)),
+        });
+        self.reload();
+    }
+    
+    /// All entries, newest first.
+    fn sorted_entries(&self) -> Vec<&TimeEntry> {
+        let mut entries: Vec<_> = self.data.time_entries.iter().collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.start_time));
+        entries
+    }
+    
+    fn selected_entry(&self) -> Option<&TimeEntry> {
+        self.sorted_entries().get(self.entries.selected()?).copied()
+    }
+    
+    /// Picker lines for `query`, best match first.
+    fn choices(&self, query: &str) -> Vec<Choice> {
+        let query = query.trim();
+        let projects = self.data.projects.iter().filter(|project| !project.archived);
+        if query.is_empty() {
+            let mut names: Vec<_> = projects.map(|project| project.name.clone()).collect();
+            names.sort_by_key(|name| name.to_lowercase());
+            return std::iter::once(Choice::NoProject).chain(names.into_iter().map(Choice::Existing)).collect();
+        }
+        
+        let mut scored: Vec<_> = projects
+            .filter_map(|project| Some((self.matcher.fuzzy_match(&project.name, query)?, &project.name)))
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+        let mut choices: Vec<_> = scored.into_iter().map(|(_, name)| Choice::Existing(name.clone())).collect();
+        if self.data.find_project(query).is_none() {
+            choices.push(Choice::Create(query.to_string()));
+        }
+        choices
+    }
+    
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::PickProject { purpose, mut query, mut selected } => {
+                let choices = self.choices(&query);
+                match key.code {
+                    KeyCode::Esc => return,
+                    KeyCode::Enter => {
+                        let project = match choices.get(selected) {
+                            Some(Choice::Existing(name) | Choice::Create(name)) => Some(name.clone()),
+                            _ => None,
+                        };
+                        self.mode = Mode::Describe { purpose, project, input: String::new() };
+                        return;
+                    }
+                    KeyCode::Up => selected = selected.saturating_sub(1),
+                    KeyCode::Down => selected = (selected + 1).min(choices.len().saturating_sub(1)),
+                    _ => {
+                        if edit_text(&mut query, key) {
+                            selected = 0;
+                        }
+                    }
+                }
+                self.mode = Mode::PickProject { purpose, query, selected };
+            }
+            Mode::Describe { purpose, project, mut input } => match key.code {
+                KeyCode::Esc => {}
+                KeyCode::Enter => {
+                    let activity = Activity {
+                        project,
+                        description: Some(input).filter(|desc| !desc.trim().is_empty()),
+                        tags: Vec::new(),
+                        billable: true,
+                    };
+                    self.with_lock(|tracker| match purpose {
+                        Purpose::ClockIn => {
+                            tracker.clock_in(activity, None)?;
+                            Ok("Clocked in".to_string())
+                        }
+                        Purpose::Switch => {
+                            let switched = tracker.switch(activity, None)?;
+                            Ok(format!("Switched after {:.2} hours", switched.previous.hours().unwrap_or(0.0)))
+                        }
+                    });
+                }
+                _ => {
+                    edit_text(&mut input, key);
+                    self.mode = Mode::Describe { purpose, project, input };
+                }
+            },
+            Mode::Edit(mut form) => match key.code {
+                KeyCode::Esc => {}
+                KeyCode::Enter => {
+                    let changes = match form.changes(&self.tz) {
+                        Ok(changes) => changes,
+                        Err(e) => {
+                            self.message = Some(Message::Error(format!("{:#}", e)));
+                            self.mode = Mode::Edit(form);
+                            return;
+                        }
+                    };
+                    if changes.is_empty() {
+                        return;
+                    }
+                    let id = form.id.to_string();
+                    self.with_lock(|tracker| {
+                        tracker.edit(&id, changes)?;
+                        Ok("Entry updated".to_string())
+                    });
+                    if matches!(self.message, Some(Message::Error(_))) {
+                        self.mode = Mode::Edit(form);
+                    }
+                }
+                KeyCode::Tab | KeyCode::Down => {
+                    form.focus = (form.focus + 1) % form.fields.len();
+                    self.mode = Mode::Edit(form);
+                }
+                KeyCode::BackTab | KeyCode::Up => {
+                    form.focus = (form.focus + form.fields.len() - 1) % form.fields.len();
+                    self.mode = Mode::Edit(form);
+                }
+                _ => {
+                    edit_text(&mut form.fields[form.focus].value, key);
+                    self.mode = Mode::Edit(form);
+                }
+            },
+            Mode::ConfirmDelete { id } => {
+                if matches!(key.code, KeyCode::Char('y' | 'Y')) {
+                    self.with_lock(|tracker| {
+                        let entry = tracker.delete(&id.to_string())?;
+                        Ok(format!("Deleted entry {}", entry.short_id()))
+                    });
+                }
+            }
+        }
+    }
+    
+    fn handle_normal_key(&mut self, key: KeyEvent) {
+        let active = self.data.get_active_entry();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab => {
+                self.pane = match self.pane {
+                    Pane::Entries => Pane::Report,
+                    Pane::Report => Pane::Entries,
+                };
+            }
+            KeyCode::Char('i') if active.is_some() => {
+                self.message = Some(Message::Error("Already clocked in. Press s to switch tasks.".to_string()));
+            }
+            KeyCode::Char('i') => self.pick_project(Purpose::ClockIn),
+            KeyCode::Char('s') if active.is_none() => {
+                self.message = Some(Message::Error("Not clocked in. Press i to start tracking.".to_string()));
+            }
+            KeyCode::Char('s') => self.pick_project(Purpose::Switch),
+            KeyCode::Char('o') => self.with_lock(|tracker| {
+                let entry = tracker.clock_out(None, None)?;
+                Ok(format!("Clocked out after {:.2} hours", entry.hours().unwrap_or(0.0)))
+            }),
+            KeyCode::Char('p') => {
+                let paused = active.is_some_and(TimeEntry::is_paused);
+                self.with_lock(|tracker| {
+                    if paused {
+                        tracker.resume(None)?;
+                        Ok("Resumed".to_string())
+                    } else {
+                        tracker.pause(None)?;
+                        Ok("Paused".to_string())
+                    }
+                });
+            }
+            _ if self.pane == Pane::Report => match key.code {
+                KeyCode::Left | KeyCode::Char('h') => {
+                    self.period = (self.period + PERIOD_PRESETS.len() - 1) % PERIOD_PRESETS.len();
+                }
+                KeyCode::Right | KeyCode::Char('l') => self.period = (self.period + 1) % PERIOD_PRESETS.len(),
+                _ => {}
+            },
+            KeyCode::Up | KeyCode::Char('k') => self.entries.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::Home | KeyCode::Char('g') => self.entries.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX),
+            KeyCode::Char('e') => {
+                if let Some(entry) = self.selected_entry() {
+                    self.mode = Mode::Edit(EditForm::new(entry, &self.data, &self.tz));
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(entry) = self.selected_entry() {
+                    self.mode = Mode::ConfirmDelete { id: entry.id };
+                }
+            }
+            KeyCode::Char('c') => {
+                if let Some(id) = self.selected_entry().map(|entry| entry.id.to_string()) {
+                    self.with_lock(|tracker| {
+                        tracker.continue_entry(Some(&id))?;
+                        Ok("Continuing".to_string())
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn pick_project(&mut self, purpose: Purpose) {
+        self.mode = Mode::PickProject { purpose, query: String::new(), selected: 0 };
+    }
+    
+    fn move_selection(&mut self, by: isize) {
+        let last = self.data.time_entries.len().saturating_sub(1);
+        let selected = self.entries.selected().unwrap_or(0).saturating_add_signed(by).min(last);
+        self.entries.select(Some(selected));
+    }
+    
+    fn draw(&mut self, frame: &mut Frame) {
+        let [status, tabs, body, footer] = Layout::vertical([
+            Constraint::Length(5),
+            Constraint::Length(1),
+            Constraint::Min(5),
+            Constraint::Length(2),
+        ])
+        .areas(frame.area());
+        
+        self.draw_status(frame, status);
+        frame.render_widget(
+            Tabs::new(["Entries", "Report"])
+                .select(self.pane as usize)
+                .highlight_style(Style::new().bold().reversed()),
+            tabs,
+        );
+        match self.pane {
+            Pane::Entries => self.draw_entries(frame, body),
+            Pane::Report => self.draw_report(frame, body),
+        }
+        self.draw_footer(frame, footer);
+        
+        match &self.mode {
+            Mode::Normal => {}
+            Mode::PickProject { purpose, query, selected } => self.draw_picker(frame, *purpose, query, *selected),
+            Mode::Describe { project, input, .. } => {
+                let area = popup(frame.area(), 60, 3);
+                let title = format!(" Description for {} (+tag to tag) ", project.as_deref().unwrap_or("No Project"));
+                frame.render_widget(Clear, area);
+                frame.render_widget(Paragraph::new(format!("{}▏", input)).block(Block::bordered().title(title)), area);
+            }
+            Mode::Edit(form) => draw_edit_form(frame, form),
+            Mode::ConfirmDelete { id } => {
+                let area = popup(frame.area(), 50, 3);
+                let short_id = id.to_string()[..8].to_string();
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    Paragraph::new(format!("Delete entry {}? (y/n)", short_id))
+                        .block(Block::bordered().title(" Delete ").red()),
+                    area,
+                );
+            }
+        }
+    }
+    
+    fn draw_status(&self, frame: &mut Frame, area: Rect) {
+        let now = Utc::now();
+        let mut lines = Vec::new();
+        match self.data.get_active_entry() {
+            Some(entry) => {
+                let (state, color) = if entry.is_paused() { ("⏸ On a break", Color::Yellow) } else { ("● Clocked in", Color::Green) };
+                lines.push(Line::from(vec![
+                    Span::styled(state, Style::new().fg(color).bold()),
+                    Span::raw("  "),
+                    Span::styled(format_elapsed(entry.net_duration_until(now)), Style::new().bold()),
+                    Span::raw(format!("  since {}", entry.start_time.with_timezone(&self.tz).format("%H:%M"))),
+                ]));
+                let breaks = entry.break_duration_until(now);
+                if breaks > Duration::zero() {
+                    lines.push(Line::raw(format!("Breaks: {}", format_elapsed(breaks))));
+                }
+                lines.push(Line::from(vec![
+                    Span::styled(self.data.project_name(entry.project_id).unwrap_or("No Project"), Style::new().blue()),
+                    Span::raw(entry.description.as_deref().map(|desc| format!(" - {}", desc)).unwrap_or_default()),
+                    Span::styled(format!(" {}", entry.format_tags()), Style::new().cyan()),
+                ]));
+            }
+            None => lines.push(Line::styled("○ Not clocked in", Style::new().red().bold())),
+        }
+        
+        let today = datetime::today(&self.tz);
+        let today_hours = models::sum_hours(
+            self.data.get_entries_by_date(today, &self.tz).iter().filter_map(|entry| entry.hours()),
+        );
+        let week_hours = period::parse_period("week", &self.tz)
+            .map(|week| self.data.total_hours_by_period(week.start, week.end))
+            .unwrap_or(0.0);
+        lines.push(Line::raw(format!("Today: {:.2} hours · This week: {:.2} hours", today_hours, week_hours)).dim());
+        
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" 🕐 TimeCard ")), area);
+    }
+    
+    fn draw_entries(&mut self, frame: &mut Frame, area: Rect) {
+        let rows: Vec<_> = self.sorted_entries()
+            .into_iter()
+            .map(|entry| {
+                let start = entry.start_time.with_timezone(&self.tz);
+                let end = match entry.end_time {
+                    Some(end) => end.with_timezone(&self.tz).format("%H:%M").to_string(),
+                    None => "now".to_string(),
+                };
+                let hours = entry.net_duration_until(Utc::now()).num_seconds() as f64 / 3600.0;
+                let row = Row::new(vec![
+                    Cell::from(entry.short_id()).dim(),
+                    Cell::from(start.format("%Y-%m-%d").to_string()),
+                    Cell::from(format!("{}-{}", start.format("%H:%M"), end)),
+                    Cell::from(format!("{:>6.2}", hours)),
+                    Cell::from(self.data.project_name(entry.project_id).unwrap_or("No Project").to_string()).blue(),
+                    Cell::from(entry.description.clone().unwrap_or_default()),
+                    Cell::from(entry.format_tags()).cyan(),
+                ]);
+                if entry.is_active() { row.green() } else { row }
+            })
+            .collect();
+        
+        let table = Table::new(rows, [
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(11),
+            Constraint::Length(6),
+            Constraint::Fill(1),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+        ])
+        .header(Row::new(["ID", "Date", "Time", "Hours", "Project", "Description", "Tags"]).bold())
+        .block(Block::bordered().title(format!(" Entries ({}) ", self.data.time_entries.len())))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        
+        frame.render_stateful_widget(table, area, &mut self.entries);
+    }
+    
+    /// The summary and project breakdown of `timecard report`.
+    fn draw_report(&self, frame: &mut Frame, area: Rect) {
+        let (value, label) = PERIOD_PRESETS[self.period];
+        let title = format!(" ◀ {} ▶ ", label);
+        let block = Block::bordered().title(title);
+        
+        let range = match period::parse_period(value, &self.tz) {
+            Ok(range) => range,
+            Err(e) => {
+                frame.render_widget(Paragraph::new(e.to_string().red()).block(block), area);
+                return;
+            }
+        };
+        let entries = match report::select_entries(&self.data, &range, None, &TagFilter::default()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                frame.render_widget(Paragraph::new(e.to_string().red()).block(block), area);
+                return;
+            }
+        };
+        let report = Report::new(&self.data, entries, &self.tz);
+        
+        let mut lines = vec![
+            Line::raw(format!(
+                "Period: {} to {}",
+                range.first_day.format("%Y-%m-%d"),
+                range.last_day.format("%Y-%m-%d")
+            )),
+            Line::raw(""),
+        ];
+        if report.entries.is_empty() {
+            lines.push(Line::raw("No time entries found for this period.").yellow());
+            frame.render_widget(Paragraph::new(lines).block(block), area);
+            return;
+        }
+        
+        lines.push(Line::raw("Summary").bold());
+        lines.push(Line::raw(format!("Total Hours: {:.2}", report.total_hours)));
+        lines.push(Line::raw(format!("Billable Hours: {:.2}", report.billable_hours)));
+        if !report.amounts.is_empty() {
+            lines.push(Line::from(vec![Span::raw("Amount: "), Span::raw(report.amounts.to_string()).green()]));
+        }
+        if report.unrated_hours > 0.0 && !self.data.rates.is_empty() {
+            lines.push(Line::raw(format!("⚠ {:.2} billable hours have no rate", report.unrated_hours)).yellow());
+        }
+        lines.push(Line::raw(format!("Total Entries: {}", report.entries.len())));
+        lines.push(Line::raw(""));
+        
+        lines.push(Line::raw("Project Breakdown").bold());
+        for (project, total) in &report.projects {
+            let percentage = (total.hours / report.total_hours) * 100.0;
+            let mut line = vec![
+                Span::raw("  "),
+                Span::raw(project.unwrap_or("No Project")).blue(),
+                Span::raw(format!(": {:.2} hours ({:.1}%)", total.hours, percentage)),
+            ];
+            if !total.amounts.is_empty() {
+                line.push(Span::raw(format!(" - {}", total.amounts)));
+            }
+            lines.push(Line::from(line));
+        }
+        
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+    
+    fn draw_footer(&self, frame: &mut Frame, area: Rect) {
+        let keys = match (&self.mode, self.pane) {
+            (Mode::PickProject { .. }, _) => "type to search · ↑↓ select · enter pick · esc cancel",
+            (Mode::Describe { .. }, _) => "enter start · esc cancel",
+            (Mode::Edit(_), _) => "tab/↑↓ field · enter save · esc cancel",
+            (Mode::ConfirmDelete { .. }, _) => "y delete · any other key cancels",
+            (Mode::Normal, Pane::Entries) => {
+                "i in · o out · s switch · p pause · ↑↓ select · e edit · d delete · c continue · tab report · q quit"
+            }
+            (Mode::Normal, Pane::Report) => "i in · o out · s switch · p pause · ←→ period · tab entries · q quit",
+        };
+        let message = match &self.message {
+            Some(Message::Info(text)) => Line::raw(format!("✓ {}", text)).green(),
+            Some(Message::Error(text)) => Line::raw(format!("✗ {}", text)).red(),
+            None => Line::raw(""),
+        };
+        frame.render_widget(Paragraph::new(vec![message, Line::raw(keys).dim()]), area);
+    }
+    
+    fn draw_picker(&self, frame: &mut Frame, purpose: Purpose, query: &str, selected: usize) {
+        let choices = self.choices(query);
+        let area = popup(frame.area(), 50, (choices.len() as u16 + 4).clamp(6, 16));
+        let title = match purpose {
+            Purpose::ClockIn => " Clock in: project ",
+            Purpose::Switch => " Switch to project ",
+        };
+        
+        let [input, list] = Layout::vertical([Constraint::Length(2), Constraint::Min(1)])
+            .areas(Block::bordered().inner(area));
+        let rows = choices.iter().enumerate().map(|(index, choice)| {
+            let line = match choice {
+                Choice::NoProject => Line::raw("No project").italic(),
+                Choice::Existing(name) => Line::raw(name.as_str()),
+                Choice::Create(name) => Line::raw(format!("+ Create \"{}\"", name)).green(),
+            };
+            if index == selected { line.reversed() } else { line }
+        });
+        // Keep the selection in view
+        let height = list.height as usize;
+        let skip = (selected + 1).saturating_sub(height);
+        
+        frame.render_widget(Clear, area);
+        frame.render_widget(Block::bordered().title(title), area);
+        frame.render_widget(Paragraph::new(format!("> {}▏", query)), input);
+        frame.render_widget(Paragraph::new(rows.skip(skip).collect::<Vec<_>>()), list);
+    }
+}
+
+fn draw_edit_form(frame: &mut Frame, form: &EditForm) {
+    let area = popup(frame.area(), 70, form.fields.len() as u16 + 4);
+    let lines: Vec<_> = form.fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let label = Span::styled(format!("{:>12}: ", field.label), Style::new().bold());
+            if index == form.focus {
+                Line::from(vec![label, Span::raw(format!("{}▏", field.value)).reversed()])
+            } else {
+                Line::from(vec![label, Span::raw(field.value.as_str())])
+            }
+        })
+        .collect();
+    
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Edit entry (empty project or tags clear them) ")),
+        area,
+    );
+}
+
+/// Apply a typing key to a text field. Returns whether the text changed.
+fn edit_text(text: &mut String, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => text.clear(),
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => text.push(c),
+        KeyCode::Backspace => return text.pop().is_some(),
+        _ => return false,
+    }
+    true
+}
+
+/// A centered area `percent_x` wide and `height` lines high.
+fn popup(area: Rect, percent_x: u16, height: u16) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// `1:05:09` for a running timer.
+fn format_elapsed(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}